@@ -6,6 +6,9 @@ rootDir = path.dirname(path.dirname(path.abspath(__file__)))
 specDir = path.join(rootDir, "spec")
 specTestDir = path.join(specDir, "test/core")
 
+# Tests of the post-MVP proposals, which the spec submodule doesn't cover yet
+proposalTestDir = path.join(rootDir, "tests/proposals")
+
 fixtureDir = path.join(rootDir, "tests/fixtures")
 
 wabtGitRemote = "git@github.com:WebAssembly/wabt.git"
@@ -37,6 +40,12 @@ def build_tests():
         moduleLocation = path.join(fixtureDir, moduleFileName)
         subprocess.call([wast2jsonLocation, wastFile, "-o", moduleLocation])
 
+    for wastFile in glob.glob(proposalTestDir + "/*.wast"):
+        wastFileName = path.basename(wastFile)
+        moduleFileName = wastFileName.replace(".wast", ".json")
+        moduleLocation = path.join(fixtureDir, moduleFileName)
+        subprocess.call([wast2jsonLocation, "--enable-all", wastFile, "-o", moduleLocation])
+
 if __name__ == "__main__":
     update_spec()
     build_tests()
\ No newline at end of file
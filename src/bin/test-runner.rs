@@ -1,14 +1,90 @@
-use std::{env,process};
+use std::time::Duration;
+use std::{env, process};
 
-use weaselm::test_runner::{run, RunnerConfig};
+use weaselm::test_runner::{run, Filter, OutputFormat, RunnerConfig};
+
+const USAGE: &str = "Usage: test-runner <directory> [--filter <suite>[:<line>]]... \
+                     [--expected-failures <file>] [--format text|json|junit|tap] \
+                     [--timeout <seconds>]";
+
+fn parse_filter(value: &str) -> Result<Filter, String> {
+    match value.split_once(':') {
+        Some((suite, line)) => {
+            let line = line
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid filter line: {}", value))?;
+            Ok(Filter {
+                suite: String::from(suite),
+                line: Some(line),
+            })
+        }
+        None => Ok(Filter {
+            suite: String::from(value),
+            line: None,
+        }),
+    }
+}
+
+fn parse_format(value: &str) -> Result<OutputFormat, String> {
+    match value {
+        "text" => Ok(OutputFormat::Text),
+        "json" => Ok(OutputFormat::Json),
+        "junit" => Ok(OutputFormat::Junit),
+        "tap" => Ok(OutputFormat::Tap),
+        _ => Err(format!("Unknown format: {}", value)),
+    }
+}
+
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|seconds| *seconds > 0.0)
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or(format!("Invalid timeout: {}", value))
+}
+
+fn parse_args(args: &[String]) -> Result<RunnerConfig, String> {
+    let mut dirname = None;
+    let mut filters = Vec::new();
+    let mut expected_failures = None;
+    let mut format = OutputFormat::Text;
+    let mut timeout = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut get_value = || args.next().ok_or(format!("Missing value for {}", arg));
+
+        match arg.as_str() {
+            "--filter" => filters.push(parse_filter(get_value()?)?),
+            "--expected-failures" => expected_failures = Some(get_value()?.clone()),
+            "--format" => format = parse_format(get_value()?)?,
+            "--timeout" => timeout = Some(parse_timeout(get_value()?)?),
+            _ if arg.starts_with("--") => return Err(format!("Unknown option: {}", arg)),
+            _ if dirname.is_none() => dirname = Some(arg.clone()),
+            _ => return Err(format!("Unexpected argument: {}", arg)),
+        }
+    }
+
+    Ok(RunnerConfig {
+        dirname: dirname.ok_or(String::from("No test directory found"))?,
+        filters,
+        expected_failures,
+        format,
+        timeout,
+    })
+}
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
-    let dirname = args.get(1).expect("No test directory found");
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    let exit_code = run(&RunnerConfig {
-        dirname: dirname.clone()
-    });
+    let config = match parse_args(&args) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{}\n{}", message, USAGE);
+            process::exit(2);
+        }
+    };
 
-    process::exit(exit_code);
+    process::exit(run(&config));
 }
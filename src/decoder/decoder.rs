@@ -4,11 +4,16 @@ use std::fmt;
 pub struct Decoder<'a> {
     pub bytes: &'a [u8],
     pub offset: usize,
+    pub data_index_used: bool,
 }
 
 impl<'a> Decoder<'a> {
     pub fn new(bytes: &'a [u8]) -> Decoder {
-        Decoder { bytes, offset: 0 }
+        Decoder {
+            bytes,
+            offset: 0,
+            data_index_used: false,
+        }
     }
 
     pub fn eat_byte(&mut self) -> Result<u8, DecoderError> {
@@ -1,22 +1,544 @@
 use crate::decoder::decoder::{Decoder, DecoderResult};
-use crate::decoder::types::decode_value_type;
-use crate::decoder::values::{decode_f32, decode_f64, decode_i32, decode_i64, decode_u32};
+use crate::decoder::types::{decode_heap_type, decode_value_type};
+use crate::decoder::values::{
+    decode_f32, decode_f64, decode_i32, decode_i64, decode_s33, decode_u32, decode_u64, decode_v128,
+};
 use crate::structure::*;
 
 // https://webassembly.github.io/spec/core/binary/types.html#binary-blocktype
 fn decode_block_type(decoder: &mut Decoder) -> DecoderResult<BlockType> {
-    Ok(if decoder.match_byte(0x40) {
-        BlockType::Void
-    } else {
-        BlockType::Return(decode_value_type(decoder)?)
-    })
+    if decoder.match_byte(0x40) {
+        return Ok(BlockType::Void);
+    }
+
+    // Value types are encoded as a single byte with the sign bit (0x40) set, which makes them
+    // negative numbers when read as a signed integer.
+    let mut value_type_decoder = *decoder;
+    if let Ok(value_type) = decode_value_type(&mut value_type_decoder) {
+        decoder.offset = value_type_decoder.offset;
+        return Ok(BlockType::Return(value_type));
+    }
+
+    let index = decode_s33(decoder)?;
+    if index < 0 || index > u32::MAX as i64 {
+        return Err(decoder.produce_error("Invalid block type"));
+    }
+
+    Ok(BlockType::TypeIndex(index as u32))
+}
+
+// https://webassembly.github.io/spec/core/binary/modules.html#binary-dataidx
+//
+// Data indices are recorded, as using them in the code section requires the data count section.
+fn decode_data_index(decoder: &mut Decoder) -> DecoderResult<u32> {
+    decoder.data_index_used = true;
+    decode_u32(decoder)
 }
 
 // https://webassembly.github.io/spec/core/binary/instructions.html#binary-memarg
+//
+// Bit 6 of the alignment field indicates that a memory index follows it, otherwise the first
+// memory is accessed.
 fn decode_memory_arg(decoder: &mut Decoder) -> DecoderResult<MemoryArg> {
+    let align = decode_u32(decoder)?;
+
+    let (align, memory) = if align & 0x40 != 0 {
+        (align & !0x40, decode_u32(decoder)?)
+    } else {
+        (align, 0)
+    };
+
     Ok(MemoryArg {
-        align: decode_u32(decoder)?,
-        offset: decode_u32(decoder)?,
+        align,
+        offset: decode_u64(decoder)?,
+        memory,
+    })
+}
+
+// https://webassembly.github.io/spec/core/binary/instructions.html#vector-instructions
+fn decode_lane_indexes(decoder: &mut Decoder) -> DecoderResult<[u8; 16]> {
+    let mut lanes = [0; 16];
+
+    for lane in lanes.iter_mut() {
+        *lane = decoder.eat_byte()?;
+    }
+
+    Ok(lanes)
+}
+
+// https://webassembly.github.io/exception-handling/core/binary/instructions.html#control-instructions
+fn decode_catch(decoder: &mut Decoder) -> DecoderResult<Catch> {
+    Ok(match decoder.eat_byte()? {
+        0x00 => Catch::Catch(decode_u32(decoder)?, decode_u32(decoder)?),
+        0x01 => Catch::CatchRef(decode_u32(decoder)?, decode_u32(decoder)?),
+        0x02 => Catch::CatchAll(decode_u32(decoder)?),
+        0x03 => Catch::CatchAllRef(decode_u32(decoder)?),
+        _ => return Err(decoder.produce_error("Invalid catch clause")),
+    })
+}
+
+// The nullability of both reference types of br_on_cast and br_on_cast_fail is encoded in a flags
+// byte, the first bit for the source type and the second one for the target type.
+//
+// https://webassembly.github.io/gc/core/binary/instructions.html#control-instructions
+fn decode_cast(decoder: &mut Decoder) -> DecoderResult<(u32, RefType, RefType)> {
+    let flags = decoder.eat_byte()?;
+    if flags > 0x03 {
+        return Err(decoder.produce_error("Invalid cast flags"));
+    }
+
+    let label_index = decode_u32(decoder)?;
+    let source_type = RefType {
+        nullable: flags & 0x01 != 0,
+        heap_type: decode_heap_type(decoder)?,
+    };
+    let target_type = RefType {
+        nullable: flags & 0x02 != 0,
+        heap_type: decode_heap_type(decoder)?,
+    };
+
+    Ok((label_index, source_type, target_type))
+}
+
+// https://webassembly.github.io/spec/core/binary/instructions.html#instructions
+//
+// Prefixed instructions are encoded as a single byte prefix followed by the instruction opcode
+// encoded as an unsigned LEB128 integer.
+fn decode_prefixed_instruction(decoder: &mut Decoder, prefix: u8) -> DecoderResult<Instruction> {
+    let opcode = decode_u32(decoder)?;
+
+    Ok(match (prefix, opcode) {
+        (0xfb, 0) => Instruction::StructNew(decode_u32(decoder)?),
+        (0xfb, 1) => Instruction::StructNewDefault(decode_u32(decoder)?),
+        (0xfb, 2) => Instruction::StructGet(decode_u32(decoder)?, decode_u32(decoder)?),
+        (0xfb, 3) => Instruction::StructGetS(decode_u32(decoder)?, decode_u32(decoder)?),
+        (0xfb, 4) => Instruction::StructGetU(decode_u32(decoder)?, decode_u32(decoder)?),
+        (0xfb, 5) => Instruction::StructSet(decode_u32(decoder)?, decode_u32(decoder)?),
+        (0xfb, 6) => Instruction::ArrayNew(decode_u32(decoder)?),
+        (0xfb, 7) => Instruction::ArrayNewDefault(decode_u32(decoder)?),
+        (0xfb, 8) => Instruction::ArrayNewFixed(decode_u32(decoder)?, decode_u32(decoder)?),
+        (0xfb, 9) => Instruction::ArrayNewData(decode_u32(decoder)?, decode_data_index(decoder)?),
+        (0xfb, 10) => Instruction::ArrayNewElem(decode_u32(decoder)?, decode_u32(decoder)?),
+        (0xfb, 11) => Instruction::ArrayGet(decode_u32(decoder)?),
+        (0xfb, 12) => Instruction::ArrayGetS(decode_u32(decoder)?),
+        (0xfb, 13) => Instruction::ArrayGetU(decode_u32(decoder)?),
+        (0xfb, 14) => Instruction::ArraySet(decode_u32(decoder)?),
+        (0xfb, 15) => Instruction::ArrayLen,
+        (0xfb, 16) => Instruction::ArrayFill(decode_u32(decoder)?),
+        (0xfb, 17) => Instruction::ArrayCopy(decode_u32(decoder)?, decode_u32(decoder)?),
+        (0xfb, 18) => Instruction::ArrayInitData(decode_u32(decoder)?, decode_data_index(decoder)?),
+        (0xfb, 19) => Instruction::ArrayInitElem(decode_u32(decoder)?, decode_u32(decoder)?),
+        (0xfb, 20) => Instruction::RefTest(RefType {
+            nullable: false,
+            heap_type: decode_heap_type(decoder)?,
+        }),
+        (0xfb, 21) => Instruction::RefTest(RefType {
+            nullable: true,
+            heap_type: decode_heap_type(decoder)?,
+        }),
+        (0xfb, 22) => Instruction::RefCast(RefType {
+            nullable: false,
+            heap_type: decode_heap_type(decoder)?,
+        }),
+        (0xfb, 23) => Instruction::RefCast(RefType {
+            nullable: true,
+            heap_type: decode_heap_type(decoder)?,
+        }),
+        (0xfb, 24) => {
+            let (label_index, source_type, target_type) = decode_cast(decoder)?;
+            Instruction::BrOnCast(label_index, source_type, target_type)
+        }
+        (0xfb, 25) => {
+            let (label_index, source_type, target_type) = decode_cast(decoder)?;
+            Instruction::BrOnCastFail(label_index, source_type, target_type)
+        }
+        (0xfb, 26) => Instruction::AnyConvertExtern,
+        (0xfb, 27) => Instruction::ExternConvertAny,
+        (0xfb, 28) => Instruction::RefI31,
+        (0xfb, 29) => Instruction::I31GetS,
+        (0xfb, 30) => Instruction::I31GetU,
+
+        (0xfc, 0) => Instruction::I32TruncSatSF32,
+        (0xfc, 1) => Instruction::I32TruncSatUF32,
+        (0xfc, 2) => Instruction::I32TruncSatSF64,
+        (0xfc, 3) => Instruction::I32TruncSatUF64,
+        (0xfc, 4) => Instruction::I64TruncSatSF32,
+        (0xfc, 5) => Instruction::I64TruncSatUF32,
+        (0xfc, 6) => Instruction::I64TruncSatSF64,
+        (0xfc, 7) => Instruction::I64TruncSatUF64,
+
+        (0xfc, 8) => {
+            let data = decode_data_index(decoder)?;
+            let memory = decode_u32(decoder)?;
+
+            Instruction::MemoryInit { memory, data }
+        }
+        (0xfc, 9) => Instruction::DataDrop(decode_data_index(decoder)?),
+        (0xfc, 10) => {
+            let destination_index = decode_u32(decoder)?;
+            let source_index = decode_u32(decoder)?;
+
+            Instruction::MemoryCopy(destination_index, source_index)
+        }
+        (0xfc, 11) => Instruction::MemoryFill(decode_u32(decoder)?),
+        (0xfc, 12) => {
+            let element = decode_u32(decoder)?;
+            let table = decode_u32(decoder)?;
+
+            Instruction::TableInit { table, element }
+        }
+        (0xfc, 13) => Instruction::ElemDrop(decode_u32(decoder)?),
+        (0xfc, 14) => {
+            let destination_index = decode_u32(decoder)?;
+            let source_index = decode_u32(decoder)?;
+
+            Instruction::TableCopy(destination_index, source_index)
+        }
+        (0xfc, 15) => Instruction::TableGrow(decode_u32(decoder)?),
+        (0xfc, 16) => Instruction::TableSize(decode_u32(decoder)?),
+        (0xfc, 17) => Instruction::TableFill(decode_u32(decoder)?),
+
+        (0xfd, 0) => Instruction::V128Load(decode_memory_arg(decoder)?),
+        (0xfd, 1) => Instruction::V128Load8x8S(decode_memory_arg(decoder)?),
+        (0xfd, 2) => Instruction::V128Load8x8U(decode_memory_arg(decoder)?),
+        (0xfd, 3) => Instruction::V128Load16x4S(decode_memory_arg(decoder)?),
+        (0xfd, 4) => Instruction::V128Load16x4U(decode_memory_arg(decoder)?),
+        (0xfd, 5) => Instruction::V128Load32x2S(decode_memory_arg(decoder)?),
+        (0xfd, 6) => Instruction::V128Load32x2U(decode_memory_arg(decoder)?),
+        (0xfd, 7) => Instruction::V128Load8Splat(decode_memory_arg(decoder)?),
+        (0xfd, 8) => Instruction::V128Load16Splat(decode_memory_arg(decoder)?),
+        (0xfd, 9) => Instruction::V128Load32Splat(decode_memory_arg(decoder)?),
+        (0xfd, 10) => Instruction::V128Load64Splat(decode_memory_arg(decoder)?),
+        (0xfd, 11) => Instruction::V128Store(decode_memory_arg(decoder)?),
+        (0xfd, 12) => Instruction::V128Const(decode_v128(decoder)?),
+        (0xfd, 13) => Instruction::I8x16Shuffle(decode_lane_indexes(decoder)?),
+        (0xfd, 14) => Instruction::I8x16Swizzle,
+        (0xfd, 15) => Instruction::I8x16Splat,
+        (0xfd, 16) => Instruction::I16x8Splat,
+        (0xfd, 17) => Instruction::I32x4Splat,
+        (0xfd, 18) => Instruction::I64x2Splat,
+        (0xfd, 19) => Instruction::F32x4Splat,
+        (0xfd, 20) => Instruction::F64x2Splat,
+        (0xfd, 21) => Instruction::I8x16ExtractLaneS(decoder.eat_byte()?),
+        (0xfd, 22) => Instruction::I8x16ExtractLaneU(decoder.eat_byte()?),
+        (0xfd, 23) => Instruction::I8x16ReplaceLane(decoder.eat_byte()?),
+        (0xfd, 24) => Instruction::I16x8ExtractLaneS(decoder.eat_byte()?),
+        (0xfd, 25) => Instruction::I16x8ExtractLaneU(decoder.eat_byte()?),
+        (0xfd, 26) => Instruction::I16x8ReplaceLane(decoder.eat_byte()?),
+        (0xfd, 27) => Instruction::I32x4ExtractLane(decoder.eat_byte()?),
+        (0xfd, 28) => Instruction::I32x4ReplaceLane(decoder.eat_byte()?),
+        (0xfd, 29) => Instruction::I64x2ExtractLane(decoder.eat_byte()?),
+        (0xfd, 30) => Instruction::I64x2ReplaceLane(decoder.eat_byte()?),
+        (0xfd, 31) => Instruction::F32x4ExtractLane(decoder.eat_byte()?),
+        (0xfd, 32) => Instruction::F32x4ReplaceLane(decoder.eat_byte()?),
+        (0xfd, 33) => Instruction::F64x2ExtractLane(decoder.eat_byte()?),
+        (0xfd, 34) => Instruction::F64x2ReplaceLane(decoder.eat_byte()?),
+        (0xfd, 35) => Instruction::I8x16Eq,
+        (0xfd, 36) => Instruction::I8x16Ne,
+        (0xfd, 37) => Instruction::I8x16LtS,
+        (0xfd, 38) => Instruction::I8x16LtU,
+        (0xfd, 39) => Instruction::I8x16GtS,
+        (0xfd, 40) => Instruction::I8x16GtU,
+        (0xfd, 41) => Instruction::I8x16LeS,
+        (0xfd, 42) => Instruction::I8x16LeU,
+        (0xfd, 43) => Instruction::I8x16GeS,
+        (0xfd, 44) => Instruction::I8x16GeU,
+        (0xfd, 45) => Instruction::I16x8Eq,
+        (0xfd, 46) => Instruction::I16x8Ne,
+        (0xfd, 47) => Instruction::I16x8LtS,
+        (0xfd, 48) => Instruction::I16x8LtU,
+        (0xfd, 49) => Instruction::I16x8GtS,
+        (0xfd, 50) => Instruction::I16x8GtU,
+        (0xfd, 51) => Instruction::I16x8LeS,
+        (0xfd, 52) => Instruction::I16x8LeU,
+        (0xfd, 53) => Instruction::I16x8GeS,
+        (0xfd, 54) => Instruction::I16x8GeU,
+        (0xfd, 55) => Instruction::I32x4Eq,
+        (0xfd, 56) => Instruction::I32x4Ne,
+        (0xfd, 57) => Instruction::I32x4LtS,
+        (0xfd, 58) => Instruction::I32x4LtU,
+        (0xfd, 59) => Instruction::I32x4GtS,
+        (0xfd, 60) => Instruction::I32x4GtU,
+        (0xfd, 61) => Instruction::I32x4LeS,
+        (0xfd, 62) => Instruction::I32x4LeU,
+        (0xfd, 63) => Instruction::I32x4GeS,
+        (0xfd, 64) => Instruction::I32x4GeU,
+        (0xfd, 65) => Instruction::F32x4Eq,
+        (0xfd, 66) => Instruction::F32x4Ne,
+        (0xfd, 67) => Instruction::F32x4Lt,
+        (0xfd, 68) => Instruction::F32x4Gt,
+        (0xfd, 69) => Instruction::F32x4Le,
+        (0xfd, 70) => Instruction::F32x4Ge,
+        (0xfd, 71) => Instruction::F64x2Eq,
+        (0xfd, 72) => Instruction::F64x2Ne,
+        (0xfd, 73) => Instruction::F64x2Lt,
+        (0xfd, 74) => Instruction::F64x2Gt,
+        (0xfd, 75) => Instruction::F64x2Le,
+        (0xfd, 76) => Instruction::F64x2Ge,
+        (0xfd, 77) => Instruction::V128Not,
+        (0xfd, 78) => Instruction::V128And,
+        (0xfd, 79) => Instruction::V128AndNot,
+        (0xfd, 80) => Instruction::V128Or,
+        (0xfd, 81) => Instruction::V128Xor,
+        (0xfd, 82) => Instruction::V128Bitselect,
+        (0xfd, 83) => Instruction::V128AnyTrue,
+        (0xfd, 84) => Instruction::V128Load8Lane(decode_memory_arg(decoder)?, decoder.eat_byte()?),
+        (0xfd, 85) => Instruction::V128Load16Lane(decode_memory_arg(decoder)?, decoder.eat_byte()?),
+        (0xfd, 86) => Instruction::V128Load32Lane(decode_memory_arg(decoder)?, decoder.eat_byte()?),
+        (0xfd, 87) => Instruction::V128Load64Lane(decode_memory_arg(decoder)?, decoder.eat_byte()?),
+        (0xfd, 88) => Instruction::V128Store8Lane(decode_memory_arg(decoder)?, decoder.eat_byte()?),
+        (0xfd, 89) => {
+            Instruction::V128Store16Lane(decode_memory_arg(decoder)?, decoder.eat_byte()?)
+        }
+        (0xfd, 90) => {
+            Instruction::V128Store32Lane(decode_memory_arg(decoder)?, decoder.eat_byte()?)
+        }
+        (0xfd, 91) => {
+            Instruction::V128Store64Lane(decode_memory_arg(decoder)?, decoder.eat_byte()?)
+        }
+        (0xfd, 92) => Instruction::V128Load32Zero(decode_memory_arg(decoder)?),
+        (0xfd, 93) => Instruction::V128Load64Zero(decode_memory_arg(decoder)?),
+        (0xfd, 94) => Instruction::F32x4DemoteF64x2Zero,
+        (0xfd, 95) => Instruction::F64x2PromoteLowF32x4,
+        (0xfd, 96) => Instruction::I8x16Abs,
+        (0xfd, 97) => Instruction::I8x16Neg,
+        (0xfd, 98) => Instruction::I8x16Popcnt,
+        (0xfd, 99) => Instruction::I8x16AllTrue,
+        (0xfd, 100) => Instruction::I8x16Bitmask,
+        (0xfd, 101) => Instruction::I8x16NarrowI16x8S,
+        (0xfd, 102) => Instruction::I8x16NarrowI16x8U,
+        (0xfd, 103) => Instruction::F32x4Ceil,
+        (0xfd, 104) => Instruction::F32x4Floor,
+        (0xfd, 105) => Instruction::F32x4Trunc,
+        (0xfd, 106) => Instruction::F32x4Nearest,
+        (0xfd, 107) => Instruction::I8x16Shl,
+        (0xfd, 108) => Instruction::I8x16ShrS,
+        (0xfd, 109) => Instruction::I8x16ShrU,
+        (0xfd, 110) => Instruction::I8x16Add,
+        (0xfd, 111) => Instruction::I8x16AddSatS,
+        (0xfd, 112) => Instruction::I8x16AddSatU,
+        (0xfd, 113) => Instruction::I8x16Sub,
+        (0xfd, 114) => Instruction::I8x16SubSatS,
+        (0xfd, 115) => Instruction::I8x16SubSatU,
+        (0xfd, 116) => Instruction::F64x2Ceil,
+        (0xfd, 117) => Instruction::F64x2Floor,
+        (0xfd, 118) => Instruction::I8x16MinS,
+        (0xfd, 119) => Instruction::I8x16MinU,
+        (0xfd, 120) => Instruction::I8x16MaxS,
+        (0xfd, 121) => Instruction::I8x16MaxU,
+        (0xfd, 122) => Instruction::F64x2Trunc,
+        (0xfd, 123) => Instruction::I8x16AvgrU,
+        (0xfd, 124) => Instruction::I16x8ExtAddPairwiseI8x16S,
+        (0xfd, 125) => Instruction::I16x8ExtAddPairwiseI8x16U,
+        (0xfd, 126) => Instruction::I32x4ExtAddPairwiseI16x8S,
+        (0xfd, 127) => Instruction::I32x4ExtAddPairwiseI16x8U,
+        (0xfd, 128) => Instruction::I16x8Abs,
+        (0xfd, 129) => Instruction::I16x8Neg,
+        (0xfd, 130) => Instruction::I16x8Q15MulrSatS,
+        (0xfd, 131) => Instruction::I16x8AllTrue,
+        (0xfd, 132) => Instruction::I16x8Bitmask,
+        (0xfd, 133) => Instruction::I16x8NarrowI32x4S,
+        (0xfd, 134) => Instruction::I16x8NarrowI32x4U,
+        (0xfd, 135) => Instruction::I16x8ExtendLowI8x16S,
+        (0xfd, 136) => Instruction::I16x8ExtendHighI8x16S,
+        (0xfd, 137) => Instruction::I16x8ExtendLowI8x16U,
+        (0xfd, 138) => Instruction::I16x8ExtendHighI8x16U,
+        (0xfd, 139) => Instruction::I16x8Shl,
+        (0xfd, 140) => Instruction::I16x8ShrS,
+        (0xfd, 141) => Instruction::I16x8ShrU,
+        (0xfd, 142) => Instruction::I16x8Add,
+        (0xfd, 143) => Instruction::I16x8AddSatS,
+        (0xfd, 144) => Instruction::I16x8AddSatU,
+        (0xfd, 145) => Instruction::I16x8Sub,
+        (0xfd, 146) => Instruction::I16x8SubSatS,
+        (0xfd, 147) => Instruction::I16x8SubSatU,
+        (0xfd, 148) => Instruction::F64x2Nearest,
+        (0xfd, 149) => Instruction::I16x8Mul,
+        (0xfd, 150) => Instruction::I16x8MinS,
+        (0xfd, 151) => Instruction::I16x8MinU,
+        (0xfd, 152) => Instruction::I16x8MaxS,
+        (0xfd, 153) => Instruction::I16x8MaxU,
+        (0xfd, 155) => Instruction::I16x8AvgrU,
+        (0xfd, 156) => Instruction::I16x8ExtMulLowI8x16S,
+        (0xfd, 157) => Instruction::I16x8ExtMulHighI8x16S,
+        (0xfd, 158) => Instruction::I16x8ExtMulLowI8x16U,
+        (0xfd, 159) => Instruction::I16x8ExtMulHighI8x16U,
+        (0xfd, 160) => Instruction::I32x4Abs,
+        (0xfd, 161) => Instruction::I32x4Neg,
+        (0xfd, 163) => Instruction::I32x4AllTrue,
+        (0xfd, 164) => Instruction::I32x4Bitmask,
+        (0xfd, 167) => Instruction::I32x4ExtendLowI16x8S,
+        (0xfd, 168) => Instruction::I32x4ExtendHighI16x8S,
+        (0xfd, 169) => Instruction::I32x4ExtendLowI16x8U,
+        (0xfd, 170) => Instruction::I32x4ExtendHighI16x8U,
+        (0xfd, 171) => Instruction::I32x4Shl,
+        (0xfd, 172) => Instruction::I32x4ShrS,
+        (0xfd, 173) => Instruction::I32x4ShrU,
+        (0xfd, 174) => Instruction::I32x4Add,
+        (0xfd, 177) => Instruction::I32x4Sub,
+        (0xfd, 181) => Instruction::I32x4Mul,
+        (0xfd, 182) => Instruction::I32x4MinS,
+        (0xfd, 183) => Instruction::I32x4MinU,
+        (0xfd, 184) => Instruction::I32x4MaxS,
+        (0xfd, 185) => Instruction::I32x4MaxU,
+        (0xfd, 186) => Instruction::I32x4DotI16x8S,
+        (0xfd, 188) => Instruction::I32x4ExtMulLowI16x8S,
+        (0xfd, 189) => Instruction::I32x4ExtMulHighI16x8S,
+        (0xfd, 190) => Instruction::I32x4ExtMulLowI16x8U,
+        (0xfd, 191) => Instruction::I32x4ExtMulHighI16x8U,
+        (0xfd, 192) => Instruction::I64x2Abs,
+        (0xfd, 193) => Instruction::I64x2Neg,
+        (0xfd, 195) => Instruction::I64x2AllTrue,
+        (0xfd, 196) => Instruction::I64x2Bitmask,
+        (0xfd, 199) => Instruction::I64x2ExtendLowI32x4S,
+        (0xfd, 200) => Instruction::I64x2ExtendHighI32x4S,
+        (0xfd, 201) => Instruction::I64x2ExtendLowI32x4U,
+        (0xfd, 202) => Instruction::I64x2ExtendHighI32x4U,
+        (0xfd, 203) => Instruction::I64x2Shl,
+        (0xfd, 204) => Instruction::I64x2ShrS,
+        (0xfd, 205) => Instruction::I64x2ShrU,
+        (0xfd, 206) => Instruction::I64x2Add,
+        (0xfd, 209) => Instruction::I64x2Sub,
+        (0xfd, 213) => Instruction::I64x2Mul,
+        (0xfd, 214) => Instruction::I64x2Eq,
+        (0xfd, 215) => Instruction::I64x2Ne,
+        (0xfd, 216) => Instruction::I64x2LtS,
+        (0xfd, 217) => Instruction::I64x2GtS,
+        (0xfd, 218) => Instruction::I64x2LeS,
+        (0xfd, 219) => Instruction::I64x2GeS,
+        (0xfd, 220) => Instruction::I64x2ExtMulLowI32x4S,
+        (0xfd, 221) => Instruction::I64x2ExtMulHighI32x4S,
+        (0xfd, 222) => Instruction::I64x2ExtMulLowI32x4U,
+        (0xfd, 223) => Instruction::I64x2ExtMulHighI32x4U,
+        (0xfd, 224) => Instruction::F32x4Abs,
+        (0xfd, 225) => Instruction::F32x4Neg,
+        (0xfd, 227) => Instruction::F32x4Sqrt,
+        (0xfd, 228) => Instruction::F32x4Add,
+        (0xfd, 229) => Instruction::F32x4Sub,
+        (0xfd, 230) => Instruction::F32x4Mul,
+        (0xfd, 231) => Instruction::F32x4Div,
+        (0xfd, 232) => Instruction::F32x4Min,
+        (0xfd, 233) => Instruction::F32x4Max,
+        (0xfd, 234) => Instruction::F32x4PMin,
+        (0xfd, 235) => Instruction::F32x4PMax,
+        (0xfd, 236) => Instruction::F64x2Abs,
+        (0xfd, 237) => Instruction::F64x2Neg,
+        (0xfd, 239) => Instruction::F64x2Sqrt,
+        (0xfd, 240) => Instruction::F64x2Add,
+        (0xfd, 241) => Instruction::F64x2Sub,
+        (0xfd, 242) => Instruction::F64x2Mul,
+        (0xfd, 243) => Instruction::F64x2Div,
+        (0xfd, 244) => Instruction::F64x2Min,
+        (0xfd, 245) => Instruction::F64x2Max,
+        (0xfd, 246) => Instruction::F64x2PMin,
+        (0xfd, 247) => Instruction::F64x2PMax,
+        (0xfd, 248) => Instruction::I32x4TruncSatF32x4S,
+        (0xfd, 249) => Instruction::I32x4TruncSatF32x4U,
+        (0xfd, 250) => Instruction::F32x4ConvertI32x4S,
+        (0xfd, 251) => Instruction::F32x4ConvertI32x4U,
+        (0xfd, 252) => Instruction::I32x4TruncSatF64x2SZero,
+        (0xfd, 253) => Instruction::I32x4TruncSatF64x2UZero,
+        (0xfd, 254) => Instruction::F64x2ConvertLowI32x4S,
+        (0xfd, 255) => Instruction::F64x2ConvertLowI32x4U,
+
+        (0xfd, 256) => Instruction::I8x16RelaxedSwizzle,
+        (0xfd, 257) => Instruction::I32x4RelaxedTruncF32x4S,
+        (0xfd, 258) => Instruction::I32x4RelaxedTruncF32x4U,
+        (0xfd, 259) => Instruction::I32x4RelaxedTruncF64x2SZero,
+        (0xfd, 260) => Instruction::I32x4RelaxedTruncF64x2UZero,
+        (0xfd, 261) => Instruction::F32x4RelaxedMadd,
+        (0xfd, 262) => Instruction::F32x4RelaxedNmadd,
+        (0xfd, 263) => Instruction::F64x2RelaxedMadd,
+        (0xfd, 264) => Instruction::F64x2RelaxedNmadd,
+        (0xfd, 265) => Instruction::I8x16RelaxedLaneselect,
+        (0xfd, 266) => Instruction::I16x8RelaxedLaneselect,
+        (0xfd, 267) => Instruction::I32x4RelaxedLaneselect,
+        (0xfd, 268) => Instruction::I64x2RelaxedLaneselect,
+        (0xfd, 269) => Instruction::F32x4RelaxedMin,
+        (0xfd, 270) => Instruction::F32x4RelaxedMax,
+        (0xfd, 271) => Instruction::F64x2RelaxedMin,
+        (0xfd, 272) => Instruction::F64x2RelaxedMax,
+        (0xfd, 273) => Instruction::I16x8RelaxedQ15MulrS,
+        (0xfd, 274) => Instruction::I16x8RelaxedDotI8x16I7x16S,
+        (0xfd, 275) => Instruction::I32x4RelaxedDotI8x16I7x16AddS,
+
+        (0xfe, 0) => Instruction::MemoryAtomicNotify(decode_memory_arg(decoder)?),
+        (0xfe, 1) => Instruction::MemoryAtomicWait32(decode_memory_arg(decoder)?),
+        (0xfe, 2) => Instruction::MemoryAtomicWait64(decode_memory_arg(decoder)?),
+        (0xfe, 3) => {
+            if !decoder.match_byte(0x00) {
+                return Err(
+                    decoder.produce_error("Invalid reserved byte after atomic.fence instruction")
+                );
+            }
+
+            Instruction::AtomicFence
+        }
+        (0xfe, 16) => Instruction::I32AtomicLoad(decode_memory_arg(decoder)?),
+        (0xfe, 17) => Instruction::I64AtomicLoad(decode_memory_arg(decoder)?),
+        (0xfe, 18) => Instruction::I32AtomicLoad8U(decode_memory_arg(decoder)?),
+        (0xfe, 19) => Instruction::I32AtomicLoad16U(decode_memory_arg(decoder)?),
+        (0xfe, 20) => Instruction::I64AtomicLoad8U(decode_memory_arg(decoder)?),
+        (0xfe, 21) => Instruction::I64AtomicLoad16U(decode_memory_arg(decoder)?),
+        (0xfe, 22) => Instruction::I64AtomicLoad32U(decode_memory_arg(decoder)?),
+        (0xfe, 23) => Instruction::I32AtomicStore(decode_memory_arg(decoder)?),
+        (0xfe, 24) => Instruction::I64AtomicStore(decode_memory_arg(decoder)?),
+        (0xfe, 25) => Instruction::I32AtomicStore8(decode_memory_arg(decoder)?),
+        (0xfe, 26) => Instruction::I32AtomicStore16(decode_memory_arg(decoder)?),
+        (0xfe, 27) => Instruction::I64AtomicStore8(decode_memory_arg(decoder)?),
+        (0xfe, 28) => Instruction::I64AtomicStore16(decode_memory_arg(decoder)?),
+        (0xfe, 29) => Instruction::I64AtomicStore32(decode_memory_arg(decoder)?),
+        (0xfe, 30) => Instruction::I32AtomicRmwAdd(decode_memory_arg(decoder)?),
+        (0xfe, 31) => Instruction::I64AtomicRmwAdd(decode_memory_arg(decoder)?),
+        (0xfe, 32) => Instruction::I32AtomicRmw8AddU(decode_memory_arg(decoder)?),
+        (0xfe, 33) => Instruction::I32AtomicRmw16AddU(decode_memory_arg(decoder)?),
+        (0xfe, 34) => Instruction::I64AtomicRmw8AddU(decode_memory_arg(decoder)?),
+        (0xfe, 35) => Instruction::I64AtomicRmw16AddU(decode_memory_arg(decoder)?),
+        (0xfe, 36) => Instruction::I64AtomicRmw32AddU(decode_memory_arg(decoder)?),
+        (0xfe, 37) => Instruction::I32AtomicRmwSub(decode_memory_arg(decoder)?),
+        (0xfe, 38) => Instruction::I64AtomicRmwSub(decode_memory_arg(decoder)?),
+        (0xfe, 39) => Instruction::I32AtomicRmw8SubU(decode_memory_arg(decoder)?),
+        (0xfe, 40) => Instruction::I32AtomicRmw16SubU(decode_memory_arg(decoder)?),
+        (0xfe, 41) => Instruction::I64AtomicRmw8SubU(decode_memory_arg(decoder)?),
+        (0xfe, 42) => Instruction::I64AtomicRmw16SubU(decode_memory_arg(decoder)?),
+        (0xfe, 43) => Instruction::I64AtomicRmw32SubU(decode_memory_arg(decoder)?),
+        (0xfe, 44) => Instruction::I32AtomicRmwAnd(decode_memory_arg(decoder)?),
+        (0xfe, 45) => Instruction::I64AtomicRmwAnd(decode_memory_arg(decoder)?),
+        (0xfe, 46) => Instruction::I32AtomicRmw8AndU(decode_memory_arg(decoder)?),
+        (0xfe, 47) => Instruction::I32AtomicRmw16AndU(decode_memory_arg(decoder)?),
+        (0xfe, 48) => Instruction::I64AtomicRmw8AndU(decode_memory_arg(decoder)?),
+        (0xfe, 49) => Instruction::I64AtomicRmw16AndU(decode_memory_arg(decoder)?),
+        (0xfe, 50) => Instruction::I64AtomicRmw32AndU(decode_memory_arg(decoder)?),
+        (0xfe, 51) => Instruction::I32AtomicRmwOr(decode_memory_arg(decoder)?),
+        (0xfe, 52) => Instruction::I64AtomicRmwOr(decode_memory_arg(decoder)?),
+        (0xfe, 53) => Instruction::I32AtomicRmw8OrU(decode_memory_arg(decoder)?),
+        (0xfe, 54) => Instruction::I32AtomicRmw16OrU(decode_memory_arg(decoder)?),
+        (0xfe, 55) => Instruction::I64AtomicRmw8OrU(decode_memory_arg(decoder)?),
+        (0xfe, 56) => Instruction::I64AtomicRmw16OrU(decode_memory_arg(decoder)?),
+        (0xfe, 57) => Instruction::I64AtomicRmw32OrU(decode_memory_arg(decoder)?),
+        (0xfe, 58) => Instruction::I32AtomicRmwXor(decode_memory_arg(decoder)?),
+        (0xfe, 59) => Instruction::I64AtomicRmwXor(decode_memory_arg(decoder)?),
+        (0xfe, 60) => Instruction::I32AtomicRmw8XorU(decode_memory_arg(decoder)?),
+        (0xfe, 61) => Instruction::I32AtomicRmw16XorU(decode_memory_arg(decoder)?),
+        (0xfe, 62) => Instruction::I64AtomicRmw8XorU(decode_memory_arg(decoder)?),
+        (0xfe, 63) => Instruction::I64AtomicRmw16XorU(decode_memory_arg(decoder)?),
+        (0xfe, 64) => Instruction::I64AtomicRmw32XorU(decode_memory_arg(decoder)?),
+        (0xfe, 65) => Instruction::I32AtomicRmwXchg(decode_memory_arg(decoder)?),
+        (0xfe, 66) => Instruction::I64AtomicRmwXchg(decode_memory_arg(decoder)?),
+        (0xfe, 67) => Instruction::I32AtomicRmw8XchgU(decode_memory_arg(decoder)?),
+        (0xfe, 68) => Instruction::I32AtomicRmw16XchgU(decode_memory_arg(decoder)?),
+        (0xfe, 69) => Instruction::I64AtomicRmw8XchgU(decode_memory_arg(decoder)?),
+        (0xfe, 70) => Instruction::I64AtomicRmw16XchgU(decode_memory_arg(decoder)?),
+        (0xfe, 71) => Instruction::I64AtomicRmw32XchgU(decode_memory_arg(decoder)?),
+        (0xfe, 72) => Instruction::I32AtomicRmwCmpxchg(decode_memory_arg(decoder)?),
+        (0xfe, 73) => Instruction::I64AtomicRmwCmpxchg(decode_memory_arg(decoder)?),
+        (0xfe, 74) => Instruction::I32AtomicRmw8CmpxchgU(decode_memory_arg(decoder)?),
+        (0xfe, 75) => Instruction::I32AtomicRmw16CmpxchgU(decode_memory_arg(decoder)?),
+        (0xfe, 76) => Instruction::I64AtomicRmw8CmpxchgU(decode_memory_arg(decoder)?),
+        (0xfe, 77) => Instruction::I64AtomicRmw16CmpxchgU(decode_memory_arg(decoder)?),
+        (0xfe, 78) => Instruction::I64AtomicRmw32CmpxchgU(decode_memory_arg(decoder)?),
+
+        _ => return Err(decoder.produce_error("Invalid instruction")),
     })
 }
 
@@ -29,7 +551,7 @@ fn decode_instruction(decoder: &mut Decoder) -> DecoderResult<Instruction> {
             let block_type = decode_block_type(decoder)?;
 
             let mut instructions = Vec::new();
-            while decoder.pick_byte().unwrap() != 0x0B {
+            while decoder.pick_byte() != Some(0x0B) {
                 instructions.push(decode_instruction(decoder)?);
             }
 
@@ -40,7 +562,7 @@ fn decode_instruction(decoder: &mut Decoder) -> DecoderResult<Instruction> {
             let block_type = decode_block_type(decoder)?;
 
             let mut instructions = Vec::new();
-            while decoder.pick_byte().unwrap() != 0x0B {
+            while decoder.pick_byte() != Some(0x0B) {
                 instructions.push(decode_instruction(decoder)?);
             }
 
@@ -51,14 +573,14 @@ fn decode_instruction(decoder: &mut Decoder) -> DecoderResult<Instruction> {
             let block_type = decode_block_type(decoder)?;
 
             let mut if_instructions = Vec::new();
-            while decoder.pick_byte().unwrap() != 0x0B && decoder.pick_byte().unwrap() != 0x05 {
+            while decoder.pick_byte() != Some(0x0B) && decoder.pick_byte() != Some(0x05) {
                 if_instructions.push(decode_instruction(decoder)?);
             }
 
             let else_instructions = if decoder.match_byte(0x05) {
                 let mut else_instructions = Vec::new();
 
-                while decoder.pick_byte().unwrap() != 0x0B {
+                while decoder.pick_byte() != Some(0x0B) {
                     else_instructions.push(decode_instruction(decoder)?);
                 }
 
@@ -70,6 +592,25 @@ fn decode_instruction(decoder: &mut Decoder) -> DecoderResult<Instruction> {
             decoder.eat_byte()?; // end
             Instruction::If(block_type, if_instructions, else_instructions)
         }
+        0x08 => Instruction::Throw(decode_u32(decoder)?),
+        0x0A => Instruction::ThrowRef,
+        0x1F => {
+            let block_type = decode_block_type(decoder)?;
+
+            let mut catches = Vec::new();
+            let catches_vector_size = decode_u32(decoder)?;
+            for _ in 0..catches_vector_size {
+                catches.push(decode_catch(decoder)?);
+            }
+
+            let mut instructions = Vec::new();
+            while decoder.pick_byte() != Some(0x0B) {
+                instructions.push(decode_instruction(decoder)?);
+            }
+
+            decoder.eat_byte()?; // end
+            Instruction::TryTable(block_type, catches, instructions)
+        }
         0x0C => Instruction::Br(decode_u32(decoder)?),
         0x0D => Instruction::BrIf(decode_u32(decoder)?),
         0x0E => {
@@ -87,24 +628,41 @@ fn decode_instruction(decoder: &mut Decoder) -> DecoderResult<Instruction> {
         0x0F => Instruction::Return,
         0x10 => Instruction::Call(decode_u32(decoder)?),
         0x11 => {
-            let index = decode_u32(decoder)?;
-            if !decoder.match_byte(0x00) {
-                return Err(
-                    decoder.produce_error("Invalid reserved byte after call_indirect instruction")
-                );
-            }
+            let type_index = decode_u32(decoder)?;
+            let table_index = decode_u32(decoder)?;
 
-            Instruction::CallIndirect(index)
+            Instruction::CallIndirect(type_index, table_index)
         }
+        0x12 => Instruction::ReturnCall(decode_u32(decoder)?),
+        0x13 => {
+            let type_index = decode_u32(decoder)?;
+            let table_index = decode_u32(decoder)?;
+
+            Instruction::ReturnCallIndirect(type_index, table_index)
+        }
+        0x14 => Instruction::CallRef(decode_u32(decoder)?),
+        0x15 => Instruction::ReturnCallRef(decode_u32(decoder)?),
 
         0x1A => Instruction::Drop,
         0x1B => Instruction::Select,
+        0x1C => {
+            let mut value_types = Vec::new();
+
+            let vector_size = decode_u32(decoder)?;
+            for _ in 0..vector_size {
+                value_types.push(decode_value_type(decoder)?);
+            }
+
+            Instruction::SelectTyped(value_types)
+        }
 
         0x20 => Instruction::LocalGet(decode_u32(decoder)?),
         0x21 => Instruction::LocalSet(decode_u32(decoder)?),
         0x22 => Instruction::LocalTee(decode_u32(decoder)?),
         0x23 => Instruction::GlobalGet(decode_u32(decoder)?),
         0x24 => Instruction::GlobalSet(decode_u32(decoder)?),
+        0x25 => Instruction::TableGet(decode_u32(decoder)?),
+        0x26 => Instruction::TableSet(decode_u32(decoder)?),
 
         0x28 => Instruction::I32Load(decode_memory_arg(decoder)?),
         0x29 => Instruction::I64Load(decode_memory_arg(decoder)?),
@@ -129,24 +687,8 @@ fn decode_instruction(decoder: &mut Decoder) -> DecoderResult<Instruction> {
         0x3c => Instruction::I64Store8(decode_memory_arg(decoder)?),
         0x3d => Instruction::I64Store16(decode_memory_arg(decoder)?),
         0x3e => Instruction::I64Store32(decode_memory_arg(decoder)?),
-        0x3f => {
-            if !decoder.match_byte(0x00) {
-                return Err(
-                    decoder.produce_error("Invalid reserved byte after memory_size instruction")
-                );
-            }
-
-            Instruction::MemorySize
-        }
-        0x40 => {
-            if !decoder.match_byte(0x00) {
-                return Err(
-                    decoder.produce_error("Invalid reserved byte after memory_grow instruction")
-                );
-            }
-
-            Instruction::MemoryGrow
-        }
+        0x3f => Instruction::MemorySize(decode_u32(decoder)?),
+        0x40 => Instruction::MemoryGrow(decode_u32(decoder)?),
 
         0x41 => Instruction::I32Const(decode_i32(decoder)?),
         0x42 => Instruction::I64Const(decode_i64(decoder)?),
@@ -280,6 +822,25 @@ fn decode_instruction(decoder: &mut Decoder) -> DecoderResult<Instruction> {
         0xbe => Instruction::F32ReinterpretI32,
         0xbf => Instruction::F64ReinterpretI64,
 
+        0xc0 => Instruction::I32Extend8S,
+        0xc1 => Instruction::I32Extend16S,
+        0xc2 => Instruction::I64Extend8S,
+        0xc3 => Instruction::I64Extend16S,
+        0xc4 => Instruction::I64Extend32S,
+
+        0xd0 => Instruction::RefNull(decode_heap_type(decoder)?),
+        0xd1 => Instruction::RefIsNull,
+        0xd2 => Instruction::RefFunc(decode_u32(decoder)?),
+        0xd3 => Instruction::RefEq,
+        0xd4 => Instruction::RefAsNonNull,
+        0xd5 => Instruction::BrOnNull(decode_u32(decoder)?),
+        0xd6 => Instruction::BrOnNonNull(decode_u32(decoder)?),
+
+        0xfb => decode_prefixed_instruction(decoder, 0xfb)?,
+        0xfc => decode_prefixed_instruction(decoder, 0xfc)?,
+        0xfd => decode_prefixed_instruction(decoder, 0xfd)?,
+        0xfe => decode_prefixed_instruction(decoder, 0xfe)?,
+
         _ => return Err(decoder.produce_error("Invalid instruction")),
     })
 }
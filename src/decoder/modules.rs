@@ -1,7 +1,8 @@
 use crate::decoder::decoder::{Decoder, DecoderResult};
 use crate::decoder::instructions::decode_expression;
 use crate::decoder::types::{
-    decode_function_type, decode_global_type, decode_limits, decode_memory_type, decode_value_type,
+    decode_global_type, decode_limits, decode_memory_type, decode_recursive_type,
+    decode_reference_type, decode_value_type,
 };
 use crate::decoder::values::{decode_name, decode_u32};
 use crate::structure::*;
@@ -18,6 +19,8 @@ const SECTION_ID_START: u8 = 8;
 const SECTION_ID_ELEMENT: u8 = 9;
 const SECTION_ID_CODE: u8 = 10;
 const SECTION_ID_DATA: u8 = 11;
+const SECTION_ID_DATA_COUNT: u8 = 12;
+const SECTION_ID_TAG: u8 = 13;
 
 fn decode_section<F, R>(decoder: &mut Decoder, section_id: u8, mut callback: F) -> DecoderResult<()>
 where
@@ -34,7 +37,7 @@ where
             return Err(closure_decoder.produce_error("Invalid section size"));
         }
 
-        decoder.offset = closure_decoder.offset;
+        *decoder = *closure_decoder;
     }
 
     Ok(())
@@ -69,20 +72,20 @@ fn decode_custom_sections<'a>(
     Ok(())
 }
 
-// https://webassembly.github.io/spec/core/binary/modules.html#type-section
-fn decode_function_type_section(decoder: &mut Decoder) -> DecoderResult<Vec<FunctionType>> {
-    let mut function_types = Vec::new();
+// https://webassembly.github.io/gc/core/binary/modules.html#type-section
+fn decode_type_section(decoder: &mut Decoder) -> DecoderResult<Vec<RecursiveType>> {
+    let mut types = Vec::new();
 
     decode_section(decoder, SECTION_ID_TYPE, |decoder| {
         let vector_size = decode_u32(decoder)?;
         for _ in 0..vector_size {
-            let function_type = decode_function_type(decoder)?;
-            function_types.push(function_type);
+            let recursive_type = decode_recursive_type(decoder)?;
+            types.push(recursive_type);
         }
         Ok(())
     })?;
 
-    Ok(function_types)
+    Ok(types)
 }
 
 // https://webassembly.github.io/spec/core/binary/modules.html#binary-importsec
@@ -100,6 +103,7 @@ fn decode_import_section(decoder: &mut Decoder) -> DecoderResult<Vec<Import>> {
                     0x01 => ImportDescriptor::Table(decode_table_type(decoder)?),
                     0x02 => ImportDescriptor::Memory(decode_memory_type(decoder)?),
                     0x03 => ImportDescriptor::Global(decode_global_type(decoder)?),
+                    0x04 => ImportDescriptor::Tag(decode_tag_type(decoder)?),
                     _ => return Err(decoder.produce_error("Invalid import descriptor")),
                 },
             })
@@ -128,10 +132,7 @@ fn decode_function_section(decoder: &mut Decoder) -> DecoderResult<Vec<u32>> {
 
 // https://webassembly.github.io/spec/core/binary/types.html#binary-tabletype
 fn decode_table_type(decoder: &mut Decoder) -> DecoderResult<TableType> {
-    let element_type = match decoder.eat_byte()? {
-        0x70 => ElementType::FuncRef,
-        _ => return Err(decoder.produce_error("Invalid element type")),
-    };
+    let element_type = decode_reference_type(decoder)?;
 
     let limits = decode_limits(decoder)?;
 
@@ -141,15 +142,29 @@ fn decode_table_type(decoder: &mut Decoder) -> DecoderResult<TableType> {
     })
 }
 
-// https://webassembly.github.io/spec/core/binary/modules.html#binary-tablesec
+// https://webassembly.github.io/function-references/core/binary/modules.html#table-section
 fn decode_table_section(decoder: &mut Decoder) -> DecoderResult<Vec<Table>> {
     let mut tables = Vec::new();
 
     decode_section(decoder, SECTION_ID_TABLE, |decoder| {
         let vector_size = decode_u32(decoder)?;
         for _ in 0..vector_size {
-            let table_type = decode_table_type(decoder)?;
-            tables.push(Table { table_type });
+            // Tables with an explicit initializer expression are prefixed with 0x40 0x00.
+            if decoder.match_byte(0x40) {
+                if !decoder.match_byte(0x00) {
+                    return Err(decoder.produce_error("Invalid reserved byte in table definition"));
+                }
+
+                tables.push(Table {
+                    table_type: decode_table_type(decoder)?,
+                    init: Some(decode_expression(decoder)?),
+                });
+            } else {
+                tables.push(Table {
+                    table_type: decode_table_type(decoder)?,
+                    init: None,
+                });
+            }
         }
         Ok(())
     })?;
@@ -174,6 +189,33 @@ fn decode_memory_section(decoder: &mut Decoder) -> DecoderResult<Vec<Memory>> {
     Ok(memories)
 }
 
+// https://webassembly.github.io/exception-handling/core/binary/types.html#tag-types
+fn decode_tag_type(decoder: &mut Decoder) -> DecoderResult<u32> {
+    // The only tag attribute defined so far is the exception attribute.
+    if decoder.eat_byte()? != 0x00 {
+        return Err(decoder.produce_error("Invalid tag attribute"));
+    }
+
+    decode_u32(decoder)
+}
+
+// https://webassembly.github.io/exception-handling/core/binary/modules.html#tag-section
+fn decode_tag_section(decoder: &mut Decoder) -> DecoderResult<Vec<Tag>> {
+    let mut tags = Vec::new();
+
+    decode_section(decoder, SECTION_ID_TAG, |decoder| {
+        let vector_size = decode_u32(decoder)?;
+        for _ in 0..vector_size {
+            tags.push(Tag {
+                tag_type: decode_tag_type(decoder)?,
+            });
+        }
+        Ok(())
+    })?;
+
+    Ok(tags)
+}
+
 // https://webassembly.github.io/spec/core/binary/modules.html#binary-globalsec
 fn decode_global_section(decoder: &mut Decoder) -> DecoderResult<Vec<Global>> {
     let mut globals = Vec::new();
@@ -206,6 +248,7 @@ fn decode_export_section(decoder: &mut Decoder) -> DecoderResult<Vec<Export>> {
                     0x01 => ExportDescriptor::Table(decode_u32(decoder)?),
                     0x02 => ExportDescriptor::Memory(decode_u32(decoder)?),
                     0x03 => ExportDescriptor::Global(decode_u32(decoder)?),
+                    0x04 => ExportDescriptor::Tag(decode_u32(decoder)?),
                     _ => return Err(decoder.produce_error("Invalid export descriptor")),
                 },
             })
@@ -230,26 +273,66 @@ fn decode_start_section(decoder: &mut Decoder) -> DecoderResult<Option<StartFunc
 }
 
 // https://webassembly.github.io/spec/core/binary/modules.html#element-section
+//
+// The first field of an element segment is a bit field describing its encoding: bit 0 indicates a
+// passive or declarative segment, bit 1 indicates an explicit table index for active segments or
+// a declarative segment otherwise, and bit 2 indicates that the segment is initialized with
+// expressions rather than function indexes.
 fn decode_element_section(decoder: &mut Decoder) -> DecoderResult<Vec<Element>> {
     let mut elements = Vec::new();
 
     decode_section(decoder, SECTION_ID_ELEMENT, |decoder| {
         let vector_size = decode_u32(decoder)?;
         for _ in 0..vector_size {
-            let table = decode_u32(decoder)?;
+            let flags = decode_u32(decoder)?;
+            if flags > 7 {
+                return Err(decoder.produce_error("Invalid element segment flags"));
+            }
 
-            let offset = decode_expression(decoder)?;
+            let mode = match flags & 0b011 {
+                0 => ElementMode::Active {
+                    table: 0,
+                    offset: decode_expression(decoder)?,
+                },
+                1 => ElementMode::Passive,
+                2 => ElementMode::Active {
+                    table: decode_u32(decoder)?,
+                    offset: decode_expression(decoder)?,
+                },
+                _ => ElementMode::Declarative,
+            };
+
+            let uses_expressions = flags & 0b100 != 0;
+
+            // Segments using the legacy encoding, with an implicit table index, have an implicit
+            // function reference type. Segments of function indexes can't hold null references.
+            let element_type = if uses_expressions && flags & 0b011 == 0 {
+                RefType::FUNCREF
+            } else if uses_expressions {
+                decode_reference_type(decoder)?
+            } else if flags & 0b011 == 0 || decoder.eat_byte()? == 0x00 {
+                RefType {
+                    nullable: false,
+                    heap_type: HeapType::Func,
+                }
+            } else {
+                return Err(decoder.produce_error("Invalid element kind"));
+            };
 
             let mut init = Vec::new();
             let vector_size = decode_u32(decoder)?;
 
             for _ in 0..vector_size {
-                init.push(decode_u32(decoder)?);
+                init.push(if uses_expressions {
+                    decode_expression(decoder)?
+                } else {
+                    vec![Instruction::RefFunc(decode_u32(decoder)?)]
+                });
             }
 
             elements.push(Element {
-                table,
-                offset,
+                element_type,
+                mode,
                 init,
             });
         }
@@ -259,6 +342,18 @@ fn decode_element_section(decoder: &mut Decoder) -> DecoderResult<Vec<Element>>
     Ok(elements)
 }
 
+// https://webassembly.github.io/spec/core/binary/modules.html#data-count-section
+fn decode_data_count_section(decoder: &mut Decoder) -> DecoderResult<Option<u32>> {
+    let mut data_count = None;
+
+    decode_section(decoder, SECTION_ID_DATA_COUNT, |decoder| {
+        data_count = Some(decode_u32(decoder)?);
+        Ok(())
+    })?;
+
+    Ok(data_count)
+}
+
 // https://webassembly.github.io/spec/core/binary/modules.html#code-section
 fn decode_code_section(
     decoder: &mut Decoder,
@@ -311,8 +406,18 @@ fn decode_data_section(decoder: &mut Decoder) -> DecoderResult<Vec<Data>> {
     decode_section(decoder, SECTION_ID_DATA, |decoder| {
         let vector_size = decode_u32(decoder)?;
         for _ in 0..vector_size {
-            let data = decode_u32(decoder)?;
-            let offset = decode_expression(decoder)?;
+            let mode = match decode_u32(decoder)? {
+                0 => DataMode::Active {
+                    memory: 0,
+                    offset: decode_expression(decoder)?,
+                },
+                1 => DataMode::Passive,
+                2 => DataMode::Active {
+                    memory: decode_u32(decoder)?,
+                    offset: decode_expression(decoder)?,
+                },
+                _ => return Err(decoder.produce_error("Invalid data segment flags")),
+            };
 
             let mut init = Vec::new();
             let init_vector_size = decode_u32(decoder)?;
@@ -321,7 +426,7 @@ fn decode_data_section(decoder: &mut Decoder) -> DecoderResult<Vec<Data>> {
                 init.push(decoder.eat_byte()?)
             }
 
-            datas.push(Data { data, offset, init })
+            datas.push(Data { mode, init })
         }
         Ok(())
     })?;
@@ -350,7 +455,7 @@ pub fn decode(bytes: &[u8]) -> DecoderResult<Module> {
     }
 
     decode_custom_sections(decoder, &mut custom_sections)?;
-    let function_types = decode_function_type_section(decoder)?;
+    let types = decode_type_section(decoder)?;
     decode_custom_sections(decoder, &mut custom_sections)?;
     let imports = decode_import_section(decoder)?;
     decode_custom_sections(decoder, &mut custom_sections)?;
@@ -360,6 +465,8 @@ pub fn decode(bytes: &[u8]) -> DecoderResult<Module> {
     decode_custom_sections(decoder, &mut custom_sections)?;
     let memories = decode_memory_section(decoder)?;
     decode_custom_sections(decoder, &mut custom_sections)?;
+    let tags = decode_tag_section(decoder)?;
+    decode_custom_sections(decoder, &mut custom_sections)?;
     let globals = decode_global_section(decoder)?;
     decode_custom_sections(decoder, &mut custom_sections)?;
     let exports = decode_export_section(decoder)?;
@@ -368,7 +475,12 @@ pub fn decode(bytes: &[u8]) -> DecoderResult<Module> {
     decode_custom_sections(decoder, &mut custom_sections)?;
     let elements = decode_element_section(decoder)?;
     decode_custom_sections(decoder, &mut custom_sections)?;
+    let data_count = decode_data_count_section(decoder)?;
+    decode_custom_sections(decoder, &mut custom_sections)?;
+    // Only the data indices of the code section require the data count section.
+    decoder.data_index_used = false;
     let codes = decode_code_section(decoder)?;
+    let data_index_used = decoder.data_index_used;
     decode_custom_sections(decoder, &mut custom_sections)?;
     let data = decode_data_section(decoder)?;
     decode_custom_sections(decoder, &mut custom_sections)?;
@@ -381,6 +493,16 @@ pub fn decode(bytes: &[u8]) -> DecoderResult<Module> {
         return Err(decoder.produce_error("Function indexes and codes size mismatch"));
     }
 
+    if let Some(count) = data_count {
+        if count as usize != data.len() {
+            return Err(decoder.produce_error("Data count and data segments size mismatch"));
+        }
+    }
+
+    if data_count.is_none() && data_index_used {
+        return Err(decoder.produce_error("Data count section required"));
+    }
+
     let mut functions = Vec::new();
 
     for i in 0..function_type_indexes.len() {
@@ -397,13 +519,15 @@ pub fn decode(bytes: &[u8]) -> DecoderResult<Module> {
 
     Ok(Module {
         custom_sections,
-        function_types,
+        types,
         functions,
         tables,
         memories,
+        tags,
         globals,
         elements,
         data,
+        data_count,
         start,
         imports,
         exports,
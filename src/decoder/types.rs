@@ -1,15 +1,88 @@
 use crate::decoder::decoder::{Decoder, DecoderResult};
-use crate::decoder::values::decode_u32;
+use crate::decoder::values::{decode_s33, decode_u32, decode_u64};
 use crate::structure::*;
 
 // https://webassembly.github.io/spec/core/binary/types.html#value-types
 pub fn decode_value_type(decoder: &mut Decoder) -> DecoderResult<ValueType> {
-    match decoder.eat_byte()? {
-        0x7F => Ok(ValueType::I32),
-        0x7E => Ok(ValueType::I64),
-        0x7D => Ok(ValueType::F32),
-        0x7C => Ok(ValueType::F64),
-        _ => Err(decoder.produce_error("Invalid value type")),
+    match decoder.pick_byte() {
+        Some(0x63) | Some(0x64) => Ok(ValueType::Ref(decode_reference_type(decoder)?)),
+        Some(byte) if decode_abstract_heap_type(byte).is_some() => {
+            Ok(ValueType::Ref(decode_reference_type(decoder)?))
+        }
+        _ => match decoder.eat_byte()? {
+            0x7F => Ok(ValueType::I32),
+            0x7E => Ok(ValueType::I64),
+            0x7D => Ok(ValueType::F32),
+            0x7C => Ok(ValueType::F64),
+            0x7B => Ok(ValueType::V128),
+            _ => Err(decoder.produce_error("Invalid value type")),
+        },
+    }
+}
+
+// https://webassembly.github.io/gc/core/binary/types.html#heap-types
+fn decode_abstract_heap_type(byte: u8) -> Option<HeapType> {
+    match byte {
+        0x74 => Some(HeapType::NoExn),
+        0x73 => Some(HeapType::NoFunc),
+        0x72 => Some(HeapType::NoExtern),
+        0x71 => Some(HeapType::None),
+        0x70 => Some(HeapType::Func),
+        0x6F => Some(HeapType::Extern),
+        0x6E => Some(HeapType::Any),
+        0x6D => Some(HeapType::Eq),
+        0x6C => Some(HeapType::I31),
+        0x6B => Some(HeapType::Struct),
+        0x6A => Some(HeapType::Array),
+        0x69 => Some(HeapType::Exn),
+        _ => None,
+    }
+}
+
+// https://webassembly.github.io/function-references/core/binary/types.html#heap-types
+//
+// Abstract heap types are encoded as single bytes which are negative numbers when read as a
+// signed integer, while type indexes are positive.
+pub fn decode_heap_type(decoder: &mut Decoder) -> DecoderResult<HeapType> {
+    match decoder.pick_byte().and_then(decode_abstract_heap_type) {
+        Some(heap_type) => {
+            decoder.eat_byte()?;
+            Ok(heap_type)
+        }
+        None => {
+            let index = decode_s33(decoder)?;
+            if index < 0 || index > u32::MAX as i64 {
+                return Err(decoder.produce_error("Invalid heap type"));
+            }
+
+            Ok(HeapType::Index(index as u32))
+        }
+    }
+}
+
+// https://webassembly.github.io/gc/core/binary/types.html#reference-types
+//
+// Abstract heap types used as reference types are shorthands for nullable references, such as
+// funcref standing for (ref null func).
+pub fn decode_reference_type(decoder: &mut Decoder) -> DecoderResult<RefType> {
+    let byte = decoder.eat_byte()?;
+    if let Some(heap_type) = decode_abstract_heap_type(byte) {
+        return Ok(RefType {
+            nullable: true,
+            heap_type,
+        });
+    }
+
+    match byte {
+        0x64 => Ok(RefType {
+            nullable: false,
+            heap_type: decode_heap_type(decoder)?,
+        }),
+        0x63 => Ok(RefType {
+            nullable: true,
+            heap_type: decode_heap_type(decoder)?,
+        }),
+        _ => Err(decoder.produce_error("Invalid reference type")),
     }
 }
 
@@ -17,12 +90,12 @@ pub fn decode_value_type(decoder: &mut Decoder) -> DecoderResult<ValueType> {
 pub fn decode_limits(decoder: &mut Decoder) -> DecoderResult<Limits> {
     match decoder.eat_byte()? {
         0x00 => Ok(Limits {
-            min: decode_u32(decoder)?,
+            min: u64::from(decode_u32(decoder)?),
             max: None,
         }),
         0x01 => Ok(Limits {
-            min: decode_u32(decoder)?,
-            max: Some(decode_u32(decoder)?),
+            min: u64::from(decode_u32(decoder)?),
+            max: Some(u64::from(decode_u32(decoder)?)),
         }),
         _ => Err(decoder.produce_error("Invalid limit")),
     }
@@ -52,10 +125,127 @@ pub fn decode_function_type(decoder: &mut Decoder) -> DecoderResult<FunctionType
     Ok((params, results))
 }
 
+// https://webassembly.github.io/gc/core/binary/types.html#binary-storagetype
+fn decode_field_type(decoder: &mut Decoder) -> DecoderResult<FieldType> {
+    let storage_type = match decoder.pick_byte() {
+        Some(0x78) => {
+            decoder.eat_byte()?;
+            StorageType::I8
+        }
+        Some(0x77) => {
+            decoder.eat_byte()?;
+            StorageType::I16
+        }
+        _ => StorageType::Value(decode_value_type(decoder)?),
+    };
+
+    Ok(FieldType {
+        storage_type,
+        mutability: decode_mutability(decoder)?,
+    })
+}
+
+// https://webassembly.github.io/gc/core/binary/types.html#composite-types
+fn decode_composite_type(decoder: &mut Decoder) -> DecoderResult<CompositeType> {
+    match decoder.pick_byte() {
+        Some(0x60) => Ok(CompositeType::Function(decode_function_type(decoder)?)),
+        Some(0x5F) => {
+            decoder.eat_byte()?;
+
+            let mut fields = Vec::new();
+            let fields_vector_size = decode_u32(decoder)?;
+            for _ in 0..fields_vector_size {
+                fields.push(decode_field_type(decoder)?);
+            }
+
+            Ok(CompositeType::Struct(fields))
+        }
+        Some(0x5E) => {
+            decoder.eat_byte()?;
+            Ok(CompositeType::Array(decode_field_type(decoder)?))
+        }
+        _ => Err(decoder.produce_error("Invalid composite type")),
+    }
+}
+
+// https://webassembly.github.io/gc/core/binary/types.html#recursive-types
+//
+// A composite type without any sub prefix is final and has no supertypes.
+fn decode_sub_type(decoder: &mut Decoder) -> DecoderResult<SubType> {
+    let is_final = match decoder.pick_byte() {
+        Some(0x50) => false,
+        Some(0x4F) => true,
+        _ => {
+            return Ok(SubType {
+                is_final: true,
+                supertypes: vec![],
+                composite_type: decode_composite_type(decoder)?,
+            })
+        }
+    };
+    decoder.eat_byte()?;
+
+    let mut supertypes = Vec::new();
+    let supertypes_vector_size = decode_u32(decoder)?;
+    for _ in 0..supertypes_vector_size {
+        supertypes.push(decode_u32(decoder)?);
+    }
+
+    Ok(SubType {
+        is_final,
+        supertypes,
+        composite_type: decode_composite_type(decoder)?,
+    })
+}
+
+// https://webassembly.github.io/gc/core/binary/types.html#recursive-types
+//
+// A sub type outside of a rec group forms a recursion group on its own.
+pub fn decode_recursive_type(decoder: &mut Decoder) -> DecoderResult<RecursiveType> {
+    if decoder.pick_byte() != Some(0x4E) {
+        return Ok(vec![decode_sub_type(decoder)?]);
+    }
+    decoder.eat_byte()?;
+
+    let mut sub_types = Vec::new();
+    let sub_types_vector_size = decode_u32(decoder)?;
+    for _ in 0..sub_types_vector_size {
+        sub_types.push(decode_sub_type(decoder)?);
+    }
+
+    Ok(sub_types)
+}
+
 // https://webassembly.github.io/spec/core/binary/types.html#memory-types
 pub fn decode_memory_type(decoder: &mut Decoder) -> DecoderResult<MemoryType> {
+    // Memories extend the limits flag with a shared bit and a 64 bits address type bit, which
+    // tables do not accept.
+    let flags = decoder.eat_byte()?;
+    if flags > 0x07 {
+        return Err(decoder.produce_error("Invalid limit"));
+    }
+
+    let address_type = if flags & 0x04 != 0 {
+        AddressType::I64
+    } else {
+        AddressType::I32
+    };
+    let decode_bound = |decoder: &mut Decoder| match address_type {
+        AddressType::I32 => Ok(u64::from(decode_u32(decoder)?)),
+        AddressType::I64 => decode_u64(decoder),
+    };
+
+    let min = decode_bound(decoder)?;
+    let max = if flags & 0x01 != 0 {
+        Some(decode_bound(decoder)?)
+    } else {
+        None
+    };
+
     Ok(MemoryType {
-        limits: decode_limits(decoder)?,
+        limits: Limits { min, max },
+        shared: flags & 0x02 != 0,
+        address_type,
     })
 }
 
@@ -63,12 +253,15 @@ pub fn decode_memory_type(decoder: &mut Decoder) -> DecoderResult<MemoryType> {
 pub fn decode_global_type(decoder: &mut Decoder) -> DecoderResult<GlobalType> {
     Ok(GlobalType {
         value_type: decode_value_type(decoder)?,
-        mutability: match decoder.eat_byte()? {
-            0x00 => GlobalTypeMutability::Const,
-            0x01 => GlobalTypeMutability::Var,
-            _ => {
-                return Err(decoder.produce_error("Invalid global type mutability"));
-            }
-        },
+        mutability: decode_mutability(decoder)?,
     })
 }
+
+// https://webassembly.github.io/spec/core/binary/types.html#binary-globaltype
+fn decode_mutability(decoder: &mut Decoder) -> DecoderResult<GlobalTypeMutability> {
+    match decoder.eat_byte()? {
+        0x00 => Ok(GlobalTypeMutability::Const),
+        0x01 => Ok(GlobalTypeMutability::Var),
+        _ => Err(decoder.produce_error("Invalid global type mutability")),
+    }
+}
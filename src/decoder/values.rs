@@ -1,7 +1,7 @@
 use crate::decoder::decoder::{Decoder, DecoderResult};
 
 // http://webassembly.github.io/spec/core/binary/values.html#integers
-fn decode_unsigned_leb_128(decoder: &mut Decoder) -> DecoderResult<u64> {
+fn decode_unsigned_leb_128(decoder: &mut Decoder, bits: u32) -> DecoderResult<u64> {
     let mut result: u64 = 0;
     let mut shift = 0;
 
@@ -12,9 +12,10 @@ fn decode_unsigned_leb_128(decoder: &mut Decoder) -> DecoderResult<u64> {
         // result.
         result |= ((byte & 0x7f) as u64) << shift;
 
-        // TODO: understand why it is the case
+        // The last byte of the encoding must not have its continuation bit set, nor set any bit
+        // above the expected number of bits.
         // https://github.com/yurydelendik/wasmparser.rs/blob/master/src/binary_reader.rs#L436-L461
-        if shift >= 25 && (byte >> (32 - shift)) != 0 {
+        if shift + 7 > bits && (byte >> (bits - shift)) != 0 {
             return Err(decoder.produce_error("Invalid LEB 128 encoding"));
         }
 
@@ -30,38 +31,96 @@ fn decode_unsigned_leb_128(decoder: &mut Decoder) -> DecoderResult<u64> {
     Ok(result)
 }
 
+// http://webassembly.github.io/spec/core/binary/values.html#integers
+fn decode_signed_leb_128(decoder: &mut Decoder, bits: u32) -> DecoderResult<i64> {
+    let mut result: i64 = 0;
+    let mut shift = 0;
+
+    loop {
+        let byte = decoder.eat_byte()?;
+
+        // The encoding can't be longer than needed for the expected number of bits, and the last
+        // byte of a 64 bits integer only holds its sign bit, the unused bits must match it.
+        if shift >= bits || (shift == 63 && byte != 0x00 && byte != 0x7f) {
+            return Err(decoder.produce_error("Invalid LEB 128 encoding"));
+        }
+
+        result |= ((byte & 0x7f) as i64) << shift;
+        shift += 7;
+
+        if (byte & 0x80) != 0x80 {
+            // The value is sign-extended when the sign bit (0x40) of the last byte is set.
+            if shift < 64 && (byte & 0x40) == 0x40 {
+                result |= -1 << shift;
+            }
+            break;
+        }
+    }
+
+    // Reject values that don't fit into the expected number of bits.
+    if bits < 64 && (result < -(1 << (bits - 1)) || result >= (1 << (bits - 1))) {
+        return Err(decoder.produce_error("Invalid LEB 128 encoding"));
+    }
+
+    Ok(result)
+}
+
 pub fn decode_u32(decoder: &mut Decoder) -> DecoderResult<u32> {
-    Ok(decode_unsigned_leb_128(decoder)? as u32)
+    Ok(decode_unsigned_leb_128(decoder, 32)? as u32)
+}
+
+pub fn decode_u64(decoder: &mut Decoder) -> DecoderResult<u64> {
+    decode_unsigned_leb_128(decoder, 64)
 }
 
 pub fn decode_i32(decoder: &mut Decoder) -> DecoderResult<i32> {
-    Ok(decode_unsigned_leb_128(decoder)? as i32)
+    Ok(decode_signed_leb_128(decoder, 32)? as i32)
 }
 
 pub fn decode_i64(decoder: &mut Decoder) -> DecoderResult<i64> {
-    Ok(decode_unsigned_leb_128(decoder)? as i64)
+    decode_signed_leb_128(decoder, 64)
 }
 
+/// https://webassembly.github.io/spec/core/binary/instructions.html#binary-blocktype
+///
+/// Block types referencing a function type are encoded as a positive signed 33 bits integer, in
+/// order to be distinguishable from the single byte value types.
+pub fn decode_s33(decoder: &mut Decoder) -> DecoderResult<i64> {
+    decode_signed_leb_128(decoder, 33)
+}
+
+// https://webassembly.github.io/spec/core/binary/values.html#floating-point
+//
+// Floating point values are encoded with their IEEE 754 bit pattern, in little endian byte order.
 pub fn decode_f32(decoder: &mut Decoder) -> DecoderResult<f32> {
-    let mut bits: u32 = 0;
+    let mut bytes = [0; 4];
 
-    for _ in 0..4 {
-        bits = (bits << 8) | decoder.eat_byte()? as u32;
+    for byte in bytes.iter_mut() {
+        *byte = decoder.eat_byte()?;
     }
 
-    Ok(f32::from_bits(bits))
+    Ok(f32::from_le_bytes(bytes))
 }
 
 pub fn decode_f64(decoder: &mut Decoder) -> DecoderResult<f64> {
-    let mut bits: u64 = 0;
+    let mut bytes = [0; 8];
 
-    for _ in 0..8 {
-        bits = (bits << 8) | decoder.eat_byte()? as u64;
+    for byte in bytes.iter_mut() {
+        *byte = decoder.eat_byte()?;
     }
 
-    Ok(f64::from_bits(bits))
+    Ok(f64::from_le_bytes(bytes))
 }
 
+pub fn decode_v128(decoder: &mut Decoder) -> DecoderResult<u128> {
+    let mut bytes = [0; 16];
+
+    for byte in bytes.iter_mut() {
+        *byte = decoder.eat_byte()?;
+    }
+
+    Ok(u128::from_le_bytes(bytes))
+}
 
 /// https://webassembly.github.io/spec/core/binary/values.html#binary-name
 ///
@@ -0,0 +1,78 @@
+use std::convert::TryInto;
+
+use crate::execution::values::{Reference, Value};
+use crate::structure::*;
+
+pub fn get_struct_type(types: &[SubType], type_index: u32) -> Option<&[FieldType]> {
+    match &types.get(type_index as usize)?.composite_type {
+        CompositeType::Struct(field_types) => Some(field_types),
+        _ => None,
+    }
+}
+
+pub fn get_array_type(types: &[SubType], type_index: u32) -> Option<&FieldType> {
+    match &types.get(type_index as usize)?.composite_type {
+        CompositeType::Array(field_type) => Some(field_type),
+        _ => None,
+    }
+}
+
+/// https://webassembly.github.io/gc/core/exec/runtime.html#aggregate-instances
+///
+/// Packed fields hold the i32 value they were last written with, which is wrapped to the width of
+/// the field when read.
+pub fn default(storage_type: &StorageType) -> Value {
+    // Non-defaultable fields are initialized explicitly, as ensured by the validation.
+    Value::default(storage_type.unpack()).unwrap_or(Value::Ref(Reference::Null(HeapType::None)))
+}
+
+// https://webassembly.github.io/gc/core/exec/runtime.html#aux-unpackval
+pub fn unpack(storage_type: &StorageType, value: Value, is_signed: bool) -> Value {
+    match (storage_type, value) {
+        (StorageType::I8, Value::I32(value)) if is_signed => Value::I32(i32::from(value as i8)),
+        (StorageType::I8, Value::I32(value)) => Value::I32(i32::from(value as u8)),
+        (StorageType::I16, Value::I32(value)) if is_signed => Value::I32(i32::from(value as i16)),
+        (StorageType::I16, Value::I32(value)) => Value::I32(i32::from(value as u16)),
+        _ => value,
+    }
+}
+
+// https://webassembly.github.io/gc/core/syntax/types.html#bit-width
+pub fn width(storage_type: &StorageType) -> usize {
+    match storage_type {
+        StorageType::I8 => 1,
+        StorageType::I16 => 2,
+        StorageType::Value(ValueType::I32) | StorageType::Value(ValueType::F32) => 4,
+        StorageType::Value(ValueType::I64) | StorageType::Value(ValueType::F64) => 8,
+        StorageType::Value(ValueType::V128) => 16,
+        StorageType::Value(ValueType::Ref(_)) => 0,
+    }
+}
+
+// Reads the little endian value of a numeric or packed field, as done for the data segments of
+// arrays. The bytes are expected to have the width of the field, and reference fields can't be
+// read from bytes, as ensured by the validation.
+pub fn from_bytes(storage_type: &StorageType, bytes: &[u8]) -> Value {
+    match storage_type {
+        StorageType::I8 => Value::I32(i32::from(bytes[0])),
+        StorageType::I16 => Value::I32(i32::from(u16::from_le_bytes(bytes.try_into().unwrap()))),
+        StorageType::Value(ValueType::I32) => {
+            Value::I32(i32::from_le_bytes(bytes.try_into().unwrap()))
+        }
+        StorageType::Value(ValueType::I64) => {
+            Value::I64(i64::from_le_bytes(bytes.try_into().unwrap()))
+        }
+        StorageType::Value(ValueType::F32) => {
+            Value::F32(f32::from_le_bytes(bytes.try_into().unwrap()))
+        }
+        StorageType::Value(ValueType::F64) => {
+            Value::F64(f64::from_le_bytes(bytes.try_into().unwrap()))
+        }
+        StorageType::Value(ValueType::V128) => {
+            Value::V128(u128::from_le_bytes(bytes.try_into().unwrap()))
+        }
+        StorageType::Value(ValueType::Ref(ref_type)) => {
+            Value::Ref(Reference::Null(ref_type.heap_type))
+        }
+    }
+}
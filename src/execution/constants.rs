@@ -0,0 +1,155 @@
+use crate::execution::aggregates;
+use crate::execution::instance::ModuleInstance;
+use crate::execution::numerics;
+use crate::execution::store::Store;
+use crate::execution::values::{Reference, Value};
+use crate::structure::*;
+
+#[derive(Debug)]
+pub struct EvaluationError {
+    pub message: String,
+}
+
+impl EvaluationError {
+    pub fn from(message: &str) -> EvaluationError {
+        EvaluationError {
+            message: String::from(message),
+        }
+    }
+}
+
+pub type EvaluationResult<T> = Result<T, EvaluationError>;
+
+fn pop_value(stack: &mut Vec<Value>) -> EvaluationResult<Value> {
+    stack
+        .pop()
+        .ok_or(EvaluationError::from("Unexpected empty operand stack"))
+}
+
+fn pop_values(stack: &mut Vec<Value>, count: usize) -> EvaluationResult<Vec<Value>> {
+    let start = stack
+        .len()
+        .checked_sub(count)
+        .ok_or(EvaluationError::from("Unexpected empty operand stack"))?;
+    Ok(stack.split_off(start))
+}
+
+/// https://webassembly.github.io/spec/core/exec/instructions.html#expressions
+///
+/// Evaluates a validated constant expression, where `globals` holds the values of the globals
+/// the expression can refer to and `module` the instance of the module defining the expression,
+/// which doesn't have to be in the store yet. Function references hold the address of the
+/// function, and aggregates are allocated in the store.
+pub fn evaluate_constant_expression(
+    expression: &Expression,
+    globals: &[Value],
+    module: &ModuleInstance,
+    store: &mut Store,
+) -> EvaluationResult<Value> {
+    let mut stack = Vec::new();
+
+    for instruction in expression {
+        let value = match instruction {
+            Instruction::I32Const(value) => Value::I32(*value),
+            Instruction::I64Const(value) => Value::I64(*value),
+            Instruction::F32Const(value) => Value::F32(*value),
+            Instruction::F64Const(value) => Value::F64(*value),
+            Instruction::V128Const(value) => Value::V128(*value),
+            Instruction::RefNull(heap_type) => Value::Ref(Reference::Null(*heap_type)),
+            Instruction::RefFunc(function_index) => {
+                let function_address = module
+                    .function_addresses
+                    .get(*function_index as usize)
+                    .ok_or(EvaluationError::from("Invalid function reference"))?;
+                Value::Ref(Reference::Function(*function_address as u32))
+            }
+            Instruction::GlobalGet(global_index) => *globals
+                .get(*global_index as usize)
+                .ok_or(EvaluationError::from("Invalid global reference"))?,
+            Instruction::RefI31 => match pop_value(&mut stack)? {
+                Value::I32(value) => Value::Ref(Reference::I31(value as u32 & 0x7fff_ffff)),
+                _ => return Err(EvaluationError::from("Mismatching operand type")),
+            },
+            Instruction::AnyConvertExtern => match pop_value(&mut stack)? {
+                Value::Ref(reference) => Value::Ref(reference.convert_any()),
+                _ => return Err(EvaluationError::from("Mismatching operand type")),
+            },
+            Instruction::ExternConvertAny => match pop_value(&mut stack)? {
+                Value::Ref(reference) => Value::Ref(reference.convert_extern()),
+                _ => return Err(EvaluationError::from("Mismatching operand type")),
+            },
+
+            // https://webassembly.github.io/gc/core/exec/instructions.html#aggregate-reference-instructions
+            Instruction::StructNew(type_index) | Instruction::StructNewDefault(type_index) => {
+                let field_types = aggregates::get_struct_type(&module.types, *type_index)
+                    .ok_or(EvaluationError::from("Invalid structure type reference"))?;
+                let fields = match instruction {
+                    Instruction::StructNew(_) => pop_values(&mut stack, field_types.len())?,
+                    _ => field_types
+                        .iter()
+                        .map(|field_type| aggregates::default(&field_type.storage_type))
+                        .collect(),
+                };
+                let type_address = module.type_addresses[*type_index as usize];
+                Value::Ref(store.allocate_struct(type_address, fields))
+            }
+            Instruction::ArrayNew(type_index)
+            | Instruction::ArrayNewDefault(type_index)
+            | Instruction::ArrayNewFixed(type_index, _) => {
+                let field_type = aggregates::get_array_type(&module.types, *type_index)
+                    .ok_or(EvaluationError::from("Invalid array type reference"))?;
+                let elements = match instruction {
+                    Instruction::ArrayNewFixed(_, length) => {
+                        pop_values(&mut stack, *length as usize)?
+                    }
+                    _ => {
+                        let length = match pop_value(&mut stack)? {
+                            Value::I32(length) => length as u32 as usize,
+                            _ => return Err(EvaluationError::from("Mismatching operand type")),
+                        };
+                        let value = match instruction {
+                            Instruction::ArrayNew(_) => pop_value(&mut stack)?,
+                            _ => aggregates::default(&field_type.storage_type),
+                        };
+                        let mut elements = Vec::new();
+                        elements
+                            .try_reserve_exact(length)
+                            .map_err(|_| EvaluationError::from("Unable to allocate array"))?;
+                        elements.resize(length, value);
+                        elements
+                    }
+                };
+                let type_address = module.type_addresses[*type_index as usize];
+                Value::Ref(store.allocate_array(type_address, elements))
+            }
+
+            // Integer arithmetic wraps around, so constant expressions can't trap.
+            Instruction::I32Add
+            | Instruction::I32Sub
+            | Instruction::I32Mul
+            | Instruction::I64Add
+            | Instruction::I64Sub
+            | Instruction::I64Mul => {
+                let rhs = pop_value(&mut stack)?;
+                let lhs = pop_value(&mut stack)?;
+                numerics::evaluate(instruction, &[lhs, rhs])
+                    .map_err(|trap| EvaluationError::from(&trap.message))?
+            }
+
+            _ => {
+                return Err(EvaluationError::from(
+                    "Invalid instruction in constant expression",
+                ))
+            }
+        };
+
+        stack.push(value);
+    }
+
+    let value = pop_value(&mut stack)?;
+    if !stack.is_empty() {
+        return Err(EvaluationError::from("Invalid stack size"));
+    }
+
+    Ok(value)
+}
@@ -0,0 +1,89 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::execution::instance::ExternalValue;
+use crate::execution::interpreter::Interpreter;
+use crate::execution::store::*;
+use crate::execution::trap::ExecutionResult;
+use crate::execution::values::Value;
+
+type Callable = dyn Fn(&mut Caller, &[Value]) -> ExecutionResult<Vec<Value>>;
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#function-instances
+///
+/// Host functions receive arguments matching the parameters of their function type, and return
+/// either results matching its results or a trap aborting the execution.
+#[derive(Clone)]
+pub struct HostFunction {
+    callable: Rc<Callable>,
+}
+
+impl HostFunction {
+    pub fn new<F>(callable: F) -> HostFunction
+    where
+        F: Fn(&mut Caller, &[Value]) -> ExecutionResult<Vec<Value>> + 'static,
+    {
+        HostFunction {
+            callable: Rc::new(callable),
+        }
+    }
+
+    pub fn call(&self, caller: &mut Caller, arguments: &[Value]) -> ExecutionResult<Vec<Value>> {
+        (self.callable)(caller, arguments)
+    }
+}
+
+impl fmt::Debug for HostFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("HostFunction")
+    }
+}
+
+/// Context of a host function call, giving access to the store and to the exports of the calling
+/// module. Host functions invoked by the embedder directly have no calling module.
+pub struct Caller<'a, 'b> {
+    interpreter: &'a mut Interpreter<'b>,
+    module: Option<ModuleAddress>,
+}
+
+impl<'a, 'b> Caller<'a, 'b> {
+    pub(crate) fn new(
+        interpreter: &'a mut Interpreter<'b>,
+        module: Option<ModuleAddress>,
+    ) -> Caller<'a, 'b> {
+        Caller {
+            interpreter,
+            module,
+        }
+    }
+
+    pub fn store(&mut self) -> &mut Store {
+        self.interpreter.store()
+    }
+
+    pub fn get_export(&mut self, name: &str) -> Option<ExternalValue> {
+        let module = self.module?;
+        self.store().modules[module].get_export(name)
+    }
+
+    pub fn get_memory(&mut self, name: &str) -> Option<&mut MemoryInstance> {
+        match self.get_export(name)? {
+            ExternalValue::Memory(memory_address) => {
+                Some(&mut self.store().memories[memory_address])
+            }
+            _ => None,
+        }
+    }
+
+    // Calls back into WebAssembly, sharing the call stack of the interrupted execution.
+    pub fn invoke(
+        &mut self,
+        function_address: FunctionAddress,
+        arguments: &[Value],
+    ) -> ExecutionResult<Vec<Value>> {
+        let function_type = self.store().functions[function_address].function_type();
+        check_arguments(function_type, arguments)?;
+
+        self.interpreter.call_nested(function_address, arguments)
+    }
+}
@@ -0,0 +1,396 @@
+use std::rc::Rc;
+
+use crate::execution::constants::evaluate_constant_expression;
+use crate::execution::interpreter::Interpreter;
+use crate::execution::store::*;
+use crate::execution::trap::Trap;
+use crate::execution::values::{Reference, Value};
+use crate::structure::*;
+
+/// Unlinkable modules don't match their imports or don't fit their segments, and are rejected
+/// before any change to the imported instances. Uninstantiable modules trap while running their
+/// start function, after having initialized their segments.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum InstantiationErrorKind {
+    Unlinkable,
+    Uninstantiable,
+}
+
+#[derive(Debug)]
+pub struct InstantiationError {
+    pub kind: InstantiationErrorKind,
+    pub message: String,
+}
+
+impl InstantiationError {
+    pub fn unlinkable(message: &str) -> InstantiationError {
+        InstantiationError {
+            kind: InstantiationErrorKind::Unlinkable,
+            message: String::from(message),
+        }
+    }
+
+    pub fn uninstantiable(message: &str) -> InstantiationError {
+        InstantiationError {
+            kind: InstantiationErrorKind::Uninstantiable,
+            message: String::from(message),
+        }
+    }
+}
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#external-values
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ExternalValue {
+    Function(FunctionAddress),
+    Table(TableAddress),
+    Memory(MemoryAddress),
+    Global(GlobalAddress),
+    Tag(TagAddress),
+}
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#export-instances
+#[derive(Debug, Clone)]
+pub struct ExportInstance {
+    pub name: String,
+    pub value: ExternalValue,
+}
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#module-instances
+///
+/// Module instances map the indices of the module to the addresses of their instances in the
+/// store, imported entities preceding the ones defined by the module.
+#[derive(Debug, Default)]
+pub struct ModuleInstance {
+    pub types: Vec<SubType>,
+    pub type_addresses: Vec<TypeAddress>,
+    pub function_addresses: Vec<FunctionAddress>,
+    pub table_addresses: Vec<TableAddress>,
+    pub memory_addresses: Vec<MemoryAddress>,
+    pub global_addresses: Vec<GlobalAddress>,
+    pub tag_addresses: Vec<TagAddress>,
+    pub element_addresses: Vec<ElementAddress>,
+    pub data_addresses: Vec<DataAddress>,
+    pub exports: Vec<ExportInstance>,
+}
+
+impl ModuleInstance {
+    pub fn get_export(&self, name: &str) -> Option<ExternalValue> {
+        self.exports
+            .iter()
+            .find(|export| export.name == name)
+            .map(|export| export.value)
+    }
+}
+
+fn get_function_type(types: &[SubType], type_index: u32) -> Option<&FunctionType> {
+    match &types.get(type_index as usize)?.composite_type {
+        CompositeType::Function(function_type) => Some(function_type),
+        _ => None,
+    }
+}
+
+// https://webassembly.github.io/spec/core/valid/types.html#limits
+fn match_limits(min: u64, max: Option<u64>, limits: &Limits) -> bool {
+    min >= limits.min
+        && match (max, limits.max) {
+            (_, None) => true,
+            (Some(max), Some(expected_max)) => max <= expected_max,
+            (None, Some(_)) => false,
+        }
+}
+
+// Function and tag types are compared by their address, which equivalent types of different
+// modules share. Functions can be imported with a supertype of their type, while tags have to be
+// imported with the same type.
+//
+// https://webassembly.github.io/spec/core/exec/modules.html#import-matching
+fn match_import(
+    store: &Store,
+    type_addresses: &[TypeAddress],
+    descriptor: &ImportDescriptor,
+    value: ExternalValue,
+) -> bool {
+    match (descriptor, value) {
+        (ImportDescriptor::Function(type_index), ExternalValue::Function(address)) => {
+            let actual = store.functions[address].type_address();
+            type_addresses
+                .get(*type_index as usize)
+                .is_some_and(|expected| store.match_type(actual, *expected))
+        }
+        (ImportDescriptor::Table(table_type), ExternalValue::Table(address)) => {
+            let table = &store.tables[address];
+            let size = table.elements.len() as u64;
+
+            table.element_type == table_type.element_type
+                && match_limits(size, table.max, &table_type.limits)
+        }
+        (ImportDescriptor::Memory(memory_type), ExternalValue::Memory(address)) => {
+            let memory = &store.memories[address];
+            let size = memory.data.len() as u64 / PAGE_SIZE;
+
+            memory.address_type == memory_type.address_type
+                && memory.shared == memory_type.shared
+                && match_limits(size, memory.max, &memory_type.limits)
+        }
+        (ImportDescriptor::Global(global_type), ExternalValue::Global(address)) => {
+            let global = &store.globals[address];
+
+            global.value_type == global_type.value_type
+                && global.mutability == global_type.mutability
+        }
+        (ImportDescriptor::Tag(type_index), ExternalValue::Tag(address)) => {
+            type_addresses.get(*type_index as usize) == Some(&store.tags[address].type_address)
+        }
+        _ => false,
+    }
+}
+
+fn trap_error(trap: Trap) -> InstantiationError {
+    InstantiationError::uninstantiable(&trap.message)
+}
+
+fn evaluate(
+    expression: &Expression,
+    globals: &[Value],
+    module: &ModuleInstance,
+    store: &mut Store,
+) -> Result<Value, InstantiationError> {
+    evaluate_constant_expression(expression, globals, module, store)
+        .map_err(|error| InstantiationError::uninstantiable(&error.message))
+}
+
+fn evaluate_reference(
+    expression: &Expression,
+    globals: &[Value],
+    module: &ModuleInstance,
+    store: &mut Store,
+) -> Result<Reference, InstantiationError> {
+    match evaluate(expression, globals, module, store)? {
+        Value::Ref(reference) => Ok(reference),
+        _ => Err(InstantiationError::uninstantiable(
+            "Invalid reference initializer",
+        )),
+    }
+}
+
+// Segment offsets are unsigned, including for 64 bits memories. Numeric expressions only refer to
+// the globals of the module.
+fn evaluate_offset(
+    expression: &Expression,
+    globals: &[Value],
+    store: &mut Store,
+) -> Result<u64, InstantiationError> {
+    match evaluate(expression, globals, &ModuleInstance::default(), store)? {
+        Value::I32(offset) => Ok(u64::from(offset as u32)),
+        Value::I64(offset) => Ok(offset as u64),
+        _ => Err(InstantiationError::uninstantiable("Invalid segment offset")),
+    }
+}
+
+/// https://webassembly.github.io/spec/core/exec/modules.html#instantiation
+///
+/// Instantiates a validated module in the store, the imports being given in the order of the
+/// module imports. Returns the address of the module instance.
+pub fn instantiate(
+    store: &mut Store,
+    module: &Module,
+    imports: &[ExternalValue],
+) -> Result<ModuleAddress, InstantiationError> {
+    if imports.len() != module.imports.len() {
+        return Err(InstantiationError::unlinkable(
+            "Mismatching number of imports",
+        ));
+    }
+
+    let types: Vec<SubType> = module.types.iter().flatten().cloned().collect();
+    let type_addresses = store.allocate_types(&module.types);
+    for (import, value) in module.imports.iter().zip(imports) {
+        if !match_import(store, &type_addresses, &import.descriptor, *value) {
+            return Err(InstantiationError::unlinkable("incompatible import type"));
+        }
+    }
+
+    let module_address = store.modules.len();
+    let mut instance = ModuleInstance {
+        types,
+        type_addresses,
+        ..ModuleInstance::default()
+    };
+    for value in imports {
+        match *value {
+            ExternalValue::Function(address) => instance.function_addresses.push(address),
+            ExternalValue::Table(address) => instance.table_addresses.push(address),
+            ExternalValue::Memory(address) => instance.memory_addresses.push(address),
+            ExternalValue::Global(address) => instance.global_addresses.push(address),
+            ExternalValue::Tag(address) => instance.tag_addresses.push(address),
+        }
+    }
+
+    // https://webassembly.github.io/spec/core/exec/modules.html#allocation
+    for function in &module.functions {
+        let function_type = get_function_type(&instance.types, function.function_type).ok_or(
+            InstantiationError::uninstantiable("Invalid function type reference"),
+        )?;
+
+        instance.function_addresses.push(store.functions.len());
+        store.functions.push(FunctionInstance::Module {
+            function_type: function_type.clone(),
+            type_address: instance.type_addresses[function.function_type as usize],
+            module: module_address,
+            code: Rc::new(function.clone()),
+        });
+    }
+
+    for tag in &module.tags {
+        let tag_type = get_function_type(&instance.types, tag.tag_type).ok_or(
+            InstantiationError::uninstantiable("Invalid tag type reference"),
+        )?;
+
+        instance.tag_addresses.push(store.tags.len());
+        store.tags.push(TagInstance {
+            tag_type: tag_type.clone(),
+            type_address: instance.type_addresses[tag.tag_type as usize],
+        });
+    }
+
+    // Globals can refer to the imported globals and to the previously defined globals.
+    let mut globals: Vec<Value> = instance
+        .global_addresses
+        .iter()
+        .map(|address| store.globals[*address].value)
+        .collect();
+    for global in &module.globals {
+        let value = evaluate(&global.init, &globals, &instance, store)?;
+        globals.push(value);
+
+        let global_address = store.allocate_global(&global.global_type, value);
+        instance.global_addresses.push(global_address);
+    }
+
+    for table in &module.tables {
+        let table_type = &table.table_type;
+        let init = match &table.init {
+            Some(init) => evaluate_reference(init, &globals, &instance, store)?,
+            None => Reference::Null(table_type.element_type.heap_type),
+        };
+
+        let error = InstantiationError::uninstantiable("Unable to allocate table");
+        let table_address = store.allocate_table(table_type, init).ok_or(error)?;
+        instance.table_addresses.push(table_address);
+    }
+
+    for memory in &module.memories {
+        let memory_address = store.allocate_memory(&memory.memory_type).ok_or(
+            InstantiationError::uninstantiable("Unable to allocate memory"),
+        )?;
+        instance.memory_addresses.push(memory_address);
+    }
+
+    for element in &module.elements {
+        let mut references = Vec::new();
+        for init in &element.init {
+            references.push(evaluate_reference(init, &globals, &instance, store)?);
+        }
+
+        instance.element_addresses.push(store.elements.len());
+        store.elements.push(references);
+    }
+
+    for data in &module.data {
+        instance.data_addresses.push(store.data.len());
+        store.data.push(data.init.clone());
+    }
+
+    for export in &module.exports {
+        let value = match export.descriptor {
+            ExportDescriptor::Function(index) => {
+                ExternalValue::Function(instance.function_addresses[index as usize])
+            }
+            ExportDescriptor::Table(index) => {
+                ExternalValue::Table(instance.table_addresses[index as usize])
+            }
+            ExportDescriptor::Memory(index) => {
+                ExternalValue::Memory(instance.memory_addresses[index as usize])
+            }
+            ExportDescriptor::Global(index) => {
+                ExternalValue::Global(instance.global_addresses[index as usize])
+            }
+            ExportDescriptor::Tag(index) => {
+                ExternalValue::Tag(instance.tag_addresses[index as usize])
+            }
+        };
+
+        instance.exports.push(ExportInstance {
+            name: export.name.clone(),
+            value,
+        });
+    }
+
+    let table_addresses = instance.table_addresses.clone();
+    let memory_addresses = instance.memory_addresses.clone();
+    let element_addresses = instance.element_addresses.clone();
+    let data_addresses = instance.data_addresses.clone();
+    let function_addresses = instance.function_addresses.clone();
+    store.modules.push(instance);
+
+    // Active segments have to fit in their table or memory before any of them is copied, so that
+    // unlinkable modules leave the imported tables and memories unchanged.
+    let mut element_segments = Vec::new();
+    for (element, element_address) in module.elements.iter().zip(element_addresses) {
+        match &element.mode {
+            ElementMode::Active { table, offset } => {
+                let offset = evaluate_offset(offset, &globals, store)?;
+                let length = element.init.len() as u64;
+                let table_address = table_addresses[*table as usize];
+
+                let size = store.tables[table_address].elements.len();
+                if get_range(offset, length, size).is_none() {
+                    return Err(InstantiationError::unlinkable(
+                        "elements segment does not fit",
+                    ));
+                }
+                element_segments.push((table_address, element_address, offset, length));
+            }
+            ElementMode::Declarative => store.elements[element_address].clear(),
+            ElementMode::Passive => {}
+        }
+    }
+
+    let mut data_segments = Vec::new();
+    for (data, data_address) in module.data.iter().zip(data_addresses) {
+        if let DataMode::Active { memory, offset } = &data.mode {
+            let offset = evaluate_offset(offset, &globals, store)?;
+            let length = data.init.len() as u64;
+            let memory_address = memory_addresses[*memory as usize];
+
+            let size = store.memories[memory_address].data.len();
+            if get_range(offset, length, size).is_none() {
+                return Err(InstantiationError::unlinkable("data segment does not fit"));
+            }
+            data_segments.push((memory_address, data_address, offset, length));
+        }
+    }
+
+    // Active segments are dropped once copied, like declarative ones.
+    for (table_address, element_address, offset, length) in element_segments {
+        store
+            .table_init(table_address, element_address, offset, 0, length)
+            .map_err(trap_error)?;
+        store.elements[element_address].clear();
+    }
+    for (memory_address, data_address, offset, length) in data_segments {
+        store
+            .memory_init(memory_address, data_address, offset, 0, length)
+            .map_err(trap_error)?;
+        store.data[data_address].clear();
+    }
+
+    if let Some(start) = &module.start {
+        let function_address = function_addresses[start.function as usize];
+        Interpreter::new(store)
+            .invoke(function_address, &[])
+            .map_err(trap_error)?;
+    }
+
+    Ok(module_address)
+}
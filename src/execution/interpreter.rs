@@ -0,0 +1,1662 @@
+use std::ops::Range;
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::execution::aggregates;
+use crate::execution::host::{Caller, HostFunction};
+use crate::execution::instance::ModuleInstance;
+use crate::execution::numerics;
+use crate::execution::store::*;
+use crate::execution::trap::{ExecutionResult, Trap, TrapKind};
+use crate::execution::values::{FromValue, Reference, Value};
+use crate::execution::vectors;
+use crate::structure::*;
+
+// Each call is executed on the host stack, so the depth of the calls has to be bounded.
+const MAX_CALL_DEPTH: usize = 512;
+
+// Reading the clock is slow compared to a call or a loop iteration, so the deadline is only
+// checked every few of them.
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#labels
+///
+/// Outcome of the execution of an instruction sequence. Branches carry the relative depth of
+/// their target label, tail calls the function replacing the current one, and throws the exception
+/// unwinding the frames up to its handler.
+enum Control {
+    Continue,
+    Branch(u32),
+    Return,
+    ReturnCall(FunctionAddress),
+    Throw(ExceptionAddress),
+}
+
+/// Tree-walking interpreter following the execution semantics of the specification.
+///
+/// Operands of every frame share a single stack, labels being unwound to the height they were
+/// entered at. The module of the executing function resolves the indices of the instructions, and
+/// is the calling module of the host functions.
+pub struct Interpreter<'a> {
+    store: &'a mut Store,
+    module: Option<ModuleAddress>,
+    stack: Vec<Value>,
+    depth: usize,
+    steps: usize,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(store: &'a mut Store) -> Interpreter<'a> {
+        Interpreter {
+            store,
+            module: None,
+            stack: Vec::new(),
+            depth: 0,
+            steps: 0,
+        }
+    }
+
+    // Arguments are expected to match the parameters of the function.
+    //
+    // https://webassembly.github.io/spec/core/exec/modules.html#invocation
+    pub fn invoke(
+        mut self,
+        function_address: FunctionAddress,
+        arguments: &[Value],
+    ) -> ExecutionResult<Vec<Value>> {
+        self.call_nested(function_address, arguments)
+    }
+
+    // Calls a function on top of the current stack, as done by host functions calling back into
+    // WebAssembly.
+    pub fn call_nested(
+        &mut self,
+        function_address: FunctionAddress,
+        arguments: &[Value],
+    ) -> ExecutionResult<Vec<Value>> {
+        let height = self.stack.len();
+        self.stack.extend_from_slice(arguments);
+
+        // Host functions can recover from a trap, so the operands of the trapped frames are
+        // discarded. Exceptions can't be handled by the host, and trap when leaving WebAssembly.
+        match self.call(function_address) {
+            Ok(Control::Throw(_)) => {
+                self.stack.truncate(height);
+                Err(Trap::new(TrapKind::UncaughtException))
+            }
+            Ok(_) => Ok(self.stack.split_off(height)),
+            Err(trap) => {
+                self.stack.truncate(height);
+                Err(trap)
+            }
+        }
+    }
+
+    pub fn store(&mut self) -> &mut Store {
+        self.store
+    }
+
+    fn pop_value(&mut self) -> ExecutionResult<Value> {
+        self.stack
+            .pop()
+            .ok_or(Trap::from("Unexpected empty operand stack"))
+    }
+
+    fn pop<T: FromValue>(&mut self) -> ExecutionResult<T> {
+        let value = self.pop_value()?;
+        T::from_value(value).ok_or(Trap::from("Mismatching operand type"))
+    }
+
+    fn push<T: Into<Value>>(&mut self, value: T) {
+        self.stack.push(value.into());
+    }
+
+    // Addresses are unsigned, with either 32 or 64 bits depending on the memory.
+    fn pop_address(&mut self) -> ExecutionResult<u64> {
+        match self.pop_value()? {
+            Value::I32(address) => Ok(u64::from(address as u32)),
+            Value::I64(address) => Ok(address as u64),
+            _ => Err(Trap::from("Mismatching operand type")),
+        }
+    }
+
+    fn pop_index(&mut self) -> ExecutionResult<u64> {
+        Ok(u64::from(self.pop::<i32>()? as u32))
+    }
+
+    // Keeps the `arity` values on top of the stack, discarding the operands above `height`.
+    fn unwind(&mut self, height: usize, arity: usize) {
+        let start = self.stack.len() - arity;
+        self.stack.drain(height..start);
+    }
+
+    // Instructions are only executed within the frame of a module function.
+    fn module(&self) -> &ModuleInstance {
+        &self.store.modules[self.module.unwrap_or_default()]
+    }
+
+    fn memory_address(&self, memory_index: u32) -> MemoryAddress {
+        self.module().memory_addresses[memory_index as usize]
+    }
+
+    fn table_address(&self, table_index: u32) -> TableAddress {
+        self.module().table_addresses[table_index as usize]
+    }
+
+    fn get_function_type(&self, type_index: u32) -> ExecutionResult<&FunctionType> {
+        match &self.module().types[type_index as usize].composite_type {
+            CompositeType::Function(function_type) => Ok(function_type),
+            _ => Err(Trap::from("Invalid function type reference")),
+        }
+    }
+
+    fn get_struct_type(&self, type_index: u32) -> ExecutionResult<&[FieldType]> {
+        aggregates::get_struct_type(&self.module().types, type_index)
+            .ok_or(Trap::from("Invalid structure type reference"))
+    }
+
+    fn get_array_type(&self, type_index: u32) -> ExecutionResult<FieldType> {
+        aggregates::get_array_type(&self.module().types, type_index)
+            .copied()
+            .ok_or(Trap::from("Invalid array type reference"))
+    }
+
+    fn get_block_arity(&self, block_type: &BlockType) -> ExecutionResult<(usize, usize)> {
+        match block_type {
+            BlockType::Void => Ok((0, 0)),
+            BlockType::Return(_) => Ok((0, 1)),
+            BlockType::TypeIndex(type_index) => {
+                let (params, results) = self.get_function_type(*type_index)?;
+                Ok((params.len(), results.len()))
+            }
+        }
+    }
+
+    // Calls and loop iterations are the only way to execute unbounded code, so the deadline of the
+    // store is checked there.
+    fn check_deadline(&mut self) -> ExecutionResult<()> {
+        self.steps += 1;
+        if !self.steps.is_multiple_of(DEADLINE_CHECK_INTERVAL) {
+            return Ok(());
+        }
+
+        match self.store.deadline {
+            Some(deadline) if Instant::now() >= deadline => Err(Trap::from("Execution timed out")),
+            _ => Ok(()),
+        }
+    }
+
+    // The frame of the caller is restored whether the call returns, throws or traps.
+    //
+    // https://webassembly.github.io/spec/core/exec/instructions.html#function-calls
+    fn call(&mut self, function_address: FunctionAddress) -> ExecutionResult<Control> {
+        if self.depth >= MAX_CALL_DEPTH {
+            return Err(Trap::new(TrapKind::CallStackExhausted));
+        }
+        self.depth += 1;
+        let caller_module = self.module;
+
+        let result = self.execute_function(function_address);
+
+        self.module = caller_module;
+        self.depth -= 1;
+        result
+    }
+
+    fn execute_function(&mut self, function_address: FunctionAddress) -> ExecutionResult<Control> {
+        // Tail calls replace the frame of the calling function instead of nesting a new one.
+        let mut function_address = function_address;
+        loop {
+            self.check_deadline()?;
+            let (function_type, module, code) = match &self.store.functions[function_address] {
+                FunctionInstance::Module {
+                    function_type,
+                    module,
+                    code,
+                    ..
+                } => (function_type, *module, Rc::clone(code)),
+                FunctionInstance::Host {
+                    function_type,
+                    host_function,
+                    ..
+                } => {
+                    let host_function = host_function.clone();
+                    let (params, results) = function_type.clone();
+                    self.call_host(&host_function, params.len(), &results)?;
+                    break;
+                }
+            };
+            self.module = Some(module);
+            let (params, results) = function_type;
+            let result_count = results.len();
+
+            let height = self.stack.len() - params.len();
+            let mut locals = self.stack.split_off(height);
+            for (count, value_type) in &code.locals {
+                // Non-defaultable locals are set before being read, as ensured by the validation.
+                let value = Value::default(*value_type)
+                    .unwrap_or(Value::Ref(Reference::Null(HeapType::None)));
+                locals.extend((0..*count).map(|_| value));
+            }
+
+            match self.execute(&code.body, &mut locals)? {
+                Control::ReturnCall(callee) => {
+                    let (params, _) = self.store.functions[callee].function_type();
+                    let arity = params.len();
+                    self.unwind(height, arity);
+                    function_address = callee;
+                }
+                // The operands of the frame are discarded by the handler.
+                Control::Throw(exception) => return Ok(Control::Throw(exception)),
+                _ => {
+                    self.unwind(height, result_count);
+                    break;
+                }
+            }
+        }
+
+        Ok(Control::Continue)
+    }
+
+    // The arguments are taken from the stack, and replaced with the results.
+    //
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-invoke
+    fn call_host(
+        &mut self,
+        host_function: &HostFunction,
+        param_count: usize,
+        results: &[ValueType],
+    ) -> ExecutionResult<()> {
+        let arguments = self.stack.split_off(self.stack.len() - param_count);
+        let module = self.module;
+        let values = host_function.call(&mut Caller::new(self, module), &arguments)?;
+
+        let is_matching = values.len() == results.len()
+            && values
+                .iter()
+                .zip(results)
+                .all(|(value, result)| value.has_type(result));
+        if !is_matching {
+            return Err(Trap::from("Mismatching host function results"));
+        }
+
+        self.stack.extend(values);
+        Ok(())
+    }
+
+    // https://webassembly.github.io/spec/core/exec/instructions.html#instruction-sequences
+    fn execute(
+        &mut self,
+        instructions: &[Instruction],
+        locals: &mut [Value],
+    ) -> ExecutionResult<Control> {
+        for instruction in instructions {
+            match self.execute_instruction(instruction, locals)? {
+                Control::Continue => {}
+                control => return Ok(control),
+            }
+        }
+
+        Ok(Control::Continue)
+    }
+
+    // Branches targeting a loop restart it with the loop parameters, while branches targeting a
+    // block exit it with the block results.
+    //
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-block
+    fn execute_block(
+        &mut self,
+        block_type: &BlockType,
+        instructions: &[Instruction],
+        locals: &mut [Value],
+        is_loop: bool,
+    ) -> ExecutionResult<Control> {
+        let (param_count, result_count) = self.get_block_arity(block_type)?;
+        let height = self.stack.len() - param_count;
+
+        loop {
+            match self.execute(instructions, locals)? {
+                Control::Branch(0) if is_loop => {
+                    self.check_deadline()?;
+                    self.unwind(height, param_count)
+                }
+                Control::Branch(0) => {
+                    self.unwind(height, result_count);
+                    return Ok(Control::Continue);
+                }
+                Control::Branch(depth) => return Ok(Control::Branch(depth - 1)),
+                control => return Ok(control),
+            }
+        }
+    }
+
+    // The first clause matching the exception branches to its label, with the operands of the
+    // try_table block replaced by the fields of the exception, or by a reference to it. The
+    // exception keeps unwinding when no clause matches.
+    //
+    // https://webassembly.github.io/exception-handling/core/exec/instructions.html#exec-throw-ref
+    fn catch(&mut self, exception: ExceptionAddress, catches: &[Catch], height: usize) -> Control {
+        let tag = self.store.exceptions[exception].tag;
+        for catch in catches {
+            let (tag_index, label_index, is_ref) = match catch {
+                Catch::Catch(tag_index, label_index) => (Some(tag_index), label_index, false),
+                Catch::CatchRef(tag_index, label_index) => (Some(tag_index), label_index, true),
+                Catch::CatchAll(label_index) => (None, label_index, false),
+                Catch::CatchAllRef(label_index) => (None, label_index, true),
+            };
+            if let Some(tag_index) = tag_index {
+                if self.module().tag_addresses[*tag_index as usize] != tag {
+                    continue;
+                }
+            }
+
+            self.stack.truncate(height);
+            if tag_index.is_some() {
+                let fields = &self.store.exceptions[exception].fields;
+                self.stack.extend_from_slice(fields);
+            }
+            if is_ref {
+                self.push(Value::Ref(Reference::Exception(exception as u32)));
+            }
+            return Control::Branch(*label_index);
+        }
+
+        Control::Throw(exception)
+    }
+
+    // Defined types are matched by address, against the declared supertypes of the referenced
+    // instance. The other references only match the abstract types of their hierarchy, host
+    // references and the references converted to the extern hierarchy keeping their
+    // representation.
+    //
+    // https://webassembly.github.io/gc/core/exec/instructions.html#exec-ref-test
+    fn match_reference(&self, reference: &Reference, ref_type: &RefType) -> bool {
+        let type_address = match reference {
+            Reference::Null(_) => return ref_type.nullable,
+            Reference::Function(address) => self.store.functions[*address as usize].type_address(),
+            Reference::Struct(address) => self.store.structs[*address as usize].type_address,
+            Reference::Array(address) => self.store.arrays[*address as usize].type_address,
+            Reference::I31(_) => {
+                return matches!(
+                    ref_type.heap_type,
+                    HeapType::Any | HeapType::Eq | HeapType::I31 | HeapType::Extern
+                )
+            }
+            Reference::Extern(_) => {
+                return matches!(ref_type.heap_type, HeapType::Any | HeapType::Extern)
+            }
+            Reference::Exception(_) => return ref_type.heap_type == HeapType::Exn,
+        };
+
+        match ref_type.heap_type {
+            HeapType::Index(type_index) => {
+                let expected_type = self.module().type_addresses[type_index as usize];
+                self.store.match_type(type_address, expected_type)
+            }
+            HeapType::Func => matches!(reference, Reference::Function(_)),
+            HeapType::Any | HeapType::Eq | HeapType::Extern => {
+                !matches!(reference, Reference::Function(_))
+            }
+            HeapType::Struct => matches!(reference, Reference::Struct(_)),
+            HeapType::Array => matches!(reference, Reference::Array(_)),
+            _ => false,
+        }
+    }
+
+    fn pop_struct(&mut self) -> ExecutionResult<StructAddress> {
+        match self.pop()? {
+            Reference::Struct(address) => Ok(address as StructAddress),
+            _ => Err(Trap::new(TrapKind::NullStructureReference)),
+        }
+    }
+
+    fn pop_array(&mut self) -> ExecutionResult<ArrayAddress> {
+        match self.pop()? {
+            Reference::Array(address) => Ok(address as ArrayAddress),
+            _ => Err(Trap::new(TrapKind::NullArrayReference)),
+        }
+    }
+
+    // Pops the array and the offset operands, and returns the range of the `length` elements
+    // accessed from the offset.
+    fn pop_array_range(&mut self, length: u64) -> ExecutionResult<(ArrayAddress, Range<usize>)> {
+        let offset = self.pop_index()?;
+        let address = self.pop_array()?;
+        let range = get_range(offset, length, self.store.arrays[address].elements.len())
+            .ok_or(Trap::new(TrapKind::OutOfBoundsArrayAccess))?;
+        Ok((address, range))
+    }
+
+    // Reads the elements of an array from a data segment, returning `None` when they don't fit
+    // in the segment.
+    fn read_data(
+        &self,
+        field_type: &FieldType,
+        data_index: u32,
+        offset: u64,
+        length: u64,
+    ) -> Option<Vec<Value>> {
+        let width = aggregates::width(&field_type.storage_type);
+        let data = &self.store.data[self.module().data_addresses[data_index as usize]];
+        let range = get_range(offset, length.checked_mul(width as u64)?, data.len())?;
+
+        let elements = data[range]
+            .chunks(width)
+            .map(|bytes| aggregates::from_bytes(&field_type.storage_type, bytes))
+            .collect();
+        Some(elements)
+    }
+
+    fn read_elements(&self, element_index: u32, offset: u64, length: u64) -> Option<Vec<Value>> {
+        let element = &self.store.elements[self.module().element_addresses[element_index as usize]];
+        let range = get_range(offset, length, element.len())?;
+        Some(
+            element[range]
+                .iter()
+                .map(|reference| Value::Ref(*reference))
+                .collect(),
+        )
+    }
+
+    fn push_array(&mut self, type_index: u32, elements: Vec<Value>) {
+        let type_address = self.module().type_addresses[type_index as usize];
+        let reference = self.store.allocate_array(type_address, elements);
+        self.push(Value::Ref(reference));
+    }
+
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-call-indirect
+    fn get_indirect_function(
+        &mut self,
+        type_index: u32,
+        table_index: u32,
+    ) -> ExecutionResult<FunctionAddress> {
+        let index = self.pop_index()?;
+        let table = &self.store.tables[self.table_address(table_index)];
+
+        let function_address = match table.elements.get(index as usize) {
+            Some(Reference::Function(function_address)) => *function_address as usize,
+            Some(_) => return Err(Trap::new(TrapKind::UninitializedElement)),
+            None => return Err(Trap::new(TrapKind::UndefinedElement)),
+        };
+
+        // The type of the function can be a subtype of the expected one.
+        let expected_type = self.module().type_addresses[type_index as usize];
+        let actual_type = self.store.functions[function_address].type_address();
+        if !self.store.match_type(actual_type, expected_type) {
+            return Err(Trap::new(TrapKind::IndirectCallTypeMismatch));
+        }
+
+        Ok(function_address)
+    }
+
+    // https://webassembly.github.io/function-references/core/exec/instructions.html#exec-call-ref
+    fn get_referenced_function(&mut self) -> ExecutionResult<FunctionAddress> {
+        match self.pop()? {
+            Reference::Function(function_address) => Ok(function_address as usize),
+            _ => Err(Trap::new(TrapKind::NullFunctionReference)),
+        }
+    }
+
+    // Pops the address operand, and returns the range of the `width` bytes accessed at its
+    // effective address.
+    //
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-load
+    fn pop_memory_range(
+        &mut self,
+        memory_arg: &MemoryArg,
+        width: usize,
+    ) -> ExecutionResult<Range<usize>> {
+        let address = self.pop_address()?;
+        let memory = &self.store.memories[self.memory_address(memory_arg.memory)];
+
+        address
+            .checked_add(memory_arg.offset)
+            .and_then(|address| get_range(address, width as u64, memory.data.len()))
+            .ok_or(Trap::new(TrapKind::OutOfBoundsMemoryAccess))
+    }
+
+    // Atomic accesses also trap when their effective address isn't aligned to their width.
+    //
+    // https://webassembly.github.io/threads/core/exec/instructions.html#exec-atomic-load
+    fn pop_atomic_range(
+        &mut self,
+        memory_arg: &MemoryArg,
+        width: usize,
+    ) -> ExecutionResult<Range<usize>> {
+        let range = self.pop_memory_range(memory_arg, width)?;
+        if !range.start.is_multiple_of(width) {
+            return Err(Trap::new(TrapKind::UnalignedAtomic));
+        }
+
+        Ok(range)
+    }
+
+    fn read_memory(&self, memory_index: u32, range: Range<usize>) -> u128 {
+        let memory = &self.store.memories[self.memory_address(memory_index)];
+        let mut bytes = [0; 16];
+        bytes[..range.len()].copy_from_slice(&memory.data[range]);
+        u128::from_le_bytes(bytes)
+    }
+
+    fn write_memory(&mut self, memory_index: u32, range: Range<usize>, value: u128) {
+        let memory_address = self.memory_address(memory_index);
+        let width = range.len();
+        self.store.memories[memory_address].data[range]
+            .copy_from_slice(&value.to_le_bytes()[..width]);
+    }
+
+    // Returns the `width` bytes at the effective address as a little endian integer.
+    //
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-load
+    fn load_memory(&mut self, memory_arg: &MemoryArg, width: usize) -> ExecutionResult<u128> {
+        let range = self.pop_memory_range(memory_arg, width)?;
+        Ok(self.read_memory(memory_arg.memory, range))
+    }
+
+    // Stores the `width` low bytes of the value in little endian order.
+    //
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-store
+    fn store_memory(
+        &mut self,
+        memory_arg: &MemoryArg,
+        value: u128,
+        width: usize,
+    ) -> ExecutionResult<()> {
+        let range = self.pop_memory_range(memory_arg, width)?;
+        self.write_memory(memory_arg.memory, range, value);
+        Ok(())
+    }
+
+    fn load_atomic(&mut self, memory_arg: &MemoryArg, width: usize) -> ExecutionResult<u128> {
+        let range = self.pop_atomic_range(memory_arg, width)?;
+        Ok(self.read_memory(memory_arg.memory, range))
+    }
+
+    fn store_atomic(&mut self, memory_arg: &MemoryArg, width: usize) -> ExecutionResult<()> {
+        let (value, _) = self.pop_integer()?;
+        let range = self.pop_atomic_range(memory_arg, width)?;
+        self.write_memory(memory_arg.memory, range, u128::from(value));
+        Ok(())
+    }
+
+    // Integer operands of the atomic instructions are either i32 or i64, the results of the
+    // read-modify-write instructions having the type of their operands.
+    fn pop_integer(&mut self) -> ExecutionResult<(u64, bool)> {
+        match self.pop_value()? {
+            Value::I32(value) => Ok((u64::from(value as u32), false)),
+            Value::I64(value) => Ok((value as u64, true)),
+            _ => Err(Trap::from("Mismatching operand type")),
+        }
+    }
+
+    fn push_integer(&mut self, value: u64, is_64: bool) {
+        if is_64 {
+            self.push(value as i64);
+        } else {
+            self.push(value as i32);
+        }
+    }
+
+    // Stores the result of the operation on the loaded value and the operand, and pushes the loaded
+    // value. Both are zero extended from the accessed width.
+    //
+    // https://webassembly.github.io/threads/core/exec/instructions.html#exec-atomic-rmw
+    fn atomic_rmw(
+        &mut self,
+        memory_arg: &MemoryArg,
+        width: usize,
+        operation: impl Fn(u64, u64) -> u64,
+    ) -> ExecutionResult<()> {
+        let (operand, is_64) = self.pop_integer()?;
+        let range = self.pop_atomic_range(memory_arg, width)?;
+
+        let loaded = self.read_memory(memory_arg.memory, range.clone()) as u64;
+        let value = operation(loaded, operand);
+        self.write_memory(memory_arg.memory, range, u128::from(value));
+        self.push_integer(loaded, is_64);
+        Ok(())
+    }
+
+    // The replacement is only stored when the loaded value equals the expected one wrapped to the
+    // accessed width.
+    //
+    // https://webassembly.github.io/threads/core/exec/instructions.html#exec-atomic-rmw-cmpxchg
+    fn atomic_cmpxchg(&mut self, memory_arg: &MemoryArg, width: usize) -> ExecutionResult<()> {
+        let (replacement, _) = self.pop_integer()?;
+        let (expected, is_64) = self.pop_integer()?;
+        let range = self.pop_atomic_range(memory_arg, width)?;
+
+        let loaded = self.read_memory(memory_arg.memory, range.clone()) as u64;
+        let mask = u64::MAX >> (64 - 8 * width);
+        if loaded == expected & mask {
+            self.write_memory(memory_arg.memory, range, u128::from(replacement));
+        }
+        self.push_integer(loaded, is_64);
+        Ok(())
+    }
+
+    // Waiting is only allowed on shared memories. As no other thread can notify the waiter, a wait
+    // on the expected value always times out, which is reported as 2, while 1 reports a loaded
+    // value different from the expected one.
+    //
+    // https://webassembly.github.io/threads/core/exec/instructions.html#exec-atomic-wait
+    fn wait(&mut self, memory_arg: &MemoryArg, expected: u64, width: usize) -> ExecutionResult<()> {
+        if !self.store.memories[self.memory_address(memory_arg.memory)].shared {
+            return Err(Trap::new(TrapKind::ExpectedSharedMemory));
+        }
+
+        let range = self.pop_atomic_range(memory_arg, width)?;
+        let loaded = self.read_memory(memory_arg.memory, range) as u64;
+        self.push(if loaded == expected { 2 } else { 1 });
+        Ok(())
+    }
+
+    // Replaces a lane of the vector on top of the stack with the loaded value.
+    //
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-load-lane
+    fn load_lane<T: vectors::Lane>(
+        &mut self,
+        memory_arg: &MemoryArg,
+        lane: u8,
+    ) -> ExecutionResult<()> {
+        let vector = self.pop::<u128>()?;
+        let value = self.load_memory(memory_arg, T::WIDTH)?;
+
+        let mut lanes = vectors::lanes::<T>(vector);
+        lanes[lane as usize] = T::from_bytes(&value.to_le_bytes()[..T::WIDTH]);
+        self.push(vectors::from_lanes(lanes));
+        Ok(())
+    }
+
+    fn push_address(&mut self, memory_address: MemoryAddress, value: u64) {
+        match self.store.memories[memory_address].address_type {
+            AddressType::I32 => self.push(value as i32),
+            AddressType::I64 => self.push(value as i64),
+        }
+    }
+
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-memory-grow
+    fn grow_memory(&mut self, memory_address: MemoryAddress, delta: u64) -> Option<u64> {
+        let memory = &mut self.store.memories[memory_address];
+        let limit = memory_limit(memory.address_type);
+        let max = memory.max.unwrap_or(limit).min(limit);
+
+        let size = memory.data.len() as u64 / PAGE_SIZE;
+        let new_size = size
+            .checked_add(delta)
+            .filter(|new_size| *new_size <= max)?;
+
+        // Failing to allocate the memory is not a trap, the growth is reported as failed instead.
+        let length = (new_size * PAGE_SIZE) as usize;
+        memory
+            .data
+            .try_reserve_exact(length - memory.data.len())
+            .ok()?;
+        memory.data.resize(length, 0);
+
+        Some(size)
+    }
+
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-table-grow
+    fn grow_table(
+        &mut self,
+        table_address: TableAddress,
+        delta: u64,
+        init: Reference,
+    ) -> Option<u64> {
+        let table = &mut self.store.tables[table_address];
+        let max = table.max.unwrap_or(u64::from(u32::MAX));
+
+        let size = table.elements.len() as u64;
+        let new_size = size
+            .checked_add(delta)
+            .filter(|new_size| *new_size <= max)?;
+
+        table.elements.try_reserve_exact(delta as usize).ok()?;
+        table.elements.resize(new_size as usize, init);
+
+        Some(size)
+    }
+
+    // https://webassembly.github.io/spec/core/exec/instructions.html
+    fn execute_instruction(
+        &mut self,
+        instruction: &Instruction,
+        locals: &mut [Value],
+    ) -> ExecutionResult<Control> {
+        match instruction {
+            // Control flow instructions
+            Instruction::Unreachable => return Err(Trap::new(TrapKind::Unreachable)),
+            Instruction::Nop => {}
+            Instruction::Block(block_type, instructions) => {
+                return self.execute_block(block_type, instructions, locals, false);
+            }
+            Instruction::Loop(block_type, instructions) => {
+                return self.execute_block(block_type, instructions, locals, true);
+            }
+            Instruction::If(block_type, then_instructions, else_instructions) => {
+                let instructions = if self.pop::<i32>()? != 0 {
+                    then_instructions
+                } else {
+                    match else_instructions {
+                        Some(else_instructions) => else_instructions,
+                        None => return Ok(Control::Continue),
+                    }
+                };
+                return self.execute_block(block_type, instructions, locals, false);
+            }
+            Instruction::Br(label_index) => return Ok(Control::Branch(*label_index)),
+            Instruction::BrIf(label_index) => {
+                if self.pop::<i32>()? != 0 {
+                    return Ok(Control::Branch(*label_index));
+                }
+            }
+            Instruction::BrTable(label_indexes, default_label_index) => {
+                let index = self.pop::<i32>()? as u32;
+                let label_index = label_indexes
+                    .get(index as usize)
+                    .unwrap_or(default_label_index);
+                return Ok(Control::Branch(*label_index));
+            }
+            Instruction::Return => return Ok(Control::Return),
+            Instruction::Call(function_index) => {
+                let function_address = self.module().function_addresses[*function_index as usize];
+                return self.call(function_address);
+            }
+            Instruction::CallIndirect(type_index, table_index) => {
+                let function_address = self.get_indirect_function(*type_index, *table_index)?;
+                return self.call(function_address);
+            }
+            Instruction::CallRef(_) => {
+                let function_address = self.get_referenced_function()?;
+                return self.call(function_address);
+            }
+            Instruction::ReturnCall(function_index) => {
+                let function_address = self.module().function_addresses[*function_index as usize];
+                return Ok(Control::ReturnCall(function_address));
+            }
+            Instruction::ReturnCallIndirect(type_index, table_index) => {
+                let function_address = self.get_indirect_function(*type_index, *table_index)?;
+                return Ok(Control::ReturnCall(function_address));
+            }
+            Instruction::ReturnCallRef(_) => {
+                let function_address = self.get_referenced_function()?;
+                return Ok(Control::ReturnCall(function_address));
+            }
+            Instruction::BrOnNull(label_index) => match self.pop()? {
+                Reference::Null(_) => return Ok(Control::Branch(*label_index)),
+                reference => self.push(Value::Ref(reference)),
+            },
+            Instruction::BrOnNonNull(label_index) => match self.pop()? {
+                Reference::Null(_) => {}
+                reference => {
+                    self.push(Value::Ref(reference));
+                    return Ok(Control::Branch(*label_index));
+                }
+            },
+            Instruction::BrOnCast(label_index, _, ref_type) => {
+                let reference = self.pop()?;
+                self.push(Value::Ref(reference));
+                if self.match_reference(&reference, ref_type) {
+                    return Ok(Control::Branch(*label_index));
+                }
+            }
+            Instruction::BrOnCastFail(label_index, _, ref_type) => {
+                let reference = self.pop()?;
+                self.push(Value::Ref(reference));
+                if !self.match_reference(&reference, ref_type) {
+                    return Ok(Control::Branch(*label_index));
+                }
+            }
+
+            // Exception instructions
+            Instruction::Throw(tag_index) => {
+                let tag = self.module().tag_addresses[*tag_index as usize];
+                let (params, _) = &self.store.tags[tag].tag_type;
+                let fields = self.stack.split_off(self.stack.len() - params.len());
+                self.store
+                    .exceptions
+                    .push(ExceptionInstance { tag, fields });
+                return Ok(Control::Throw(self.store.exceptions.len() - 1));
+            }
+            Instruction::ThrowRef => match self.pop()? {
+                Reference::Exception(exception) => {
+                    return Ok(Control::Throw(exception as ExceptionAddress));
+                }
+                _ => return Err(Trap::new(TrapKind::NullExceptionReference)),
+            },
+            Instruction::TryTable(block_type, catches, instructions) => {
+                let (param_count, _) = self.get_block_arity(block_type)?;
+                let height = self.stack.len() - param_count;
+                return match self.execute_block(block_type, instructions, locals, false)? {
+                    Control::Throw(exception) => Ok(self.catch(exception, catches, height)),
+                    control => Ok(control),
+                };
+            }
+
+            // Other instructions don't affect the control flow.
+            _ => self.execute_variable_instruction(instruction, locals)?,
+        }
+
+        Ok(Control::Continue)
+    }
+
+    // Reference, parametric and variable instructions.
+    //
+    // https://webassembly.github.io/spec/core/exec/instructions.html#variable-instructions
+    fn execute_variable_instruction(
+        &mut self,
+        instruction: &Instruction,
+        locals: &mut [Value],
+    ) -> ExecutionResult<()> {
+        match instruction {
+            // Reference instructions
+            Instruction::RefNull(heap_type) => self.push(Value::Ref(Reference::Null(*heap_type))),
+            Instruction::RefIsNull => {
+                let reference = self.pop()?;
+                self.push(matches!(reference, Reference::Null(_)));
+            }
+            Instruction::RefFunc(function_index) => {
+                let function_address = self.module().function_addresses[*function_index as usize];
+                self.push(Value::Ref(Reference::Function(function_address as u32)));
+            }
+            Instruction::RefAsNonNull => match self.pop()? {
+                Reference::Null(_) => return Err(Trap::new(TrapKind::NullReference)),
+                reference => self.push(Value::Ref(reference)),
+            },
+            Instruction::RefEq => {
+                let reference_2 = self.pop()?;
+                let reference_1 = self.pop()?;
+                let is_equal = match (reference_1, reference_2) {
+                    (Reference::Null(_), Reference::Null(_)) => true,
+                    _ => reference_1 == reference_2,
+                };
+                self.push(is_equal);
+            }
+            Instruction::RefTest(ref_type) => {
+                let reference = self.pop()?;
+                let is_matching = self.match_reference(&reference, ref_type);
+                self.push(is_matching);
+            }
+            Instruction::RefCast(ref_type) => {
+                let reference = self.pop()?;
+                if !self.match_reference(&reference, ref_type) {
+                    return Err(Trap::new(TrapKind::CastFailure));
+                }
+                self.push(Value::Ref(reference));
+            }
+            Instruction::RefI31 => {
+                let value = self.pop::<i32>()?;
+                self.push(Value::Ref(Reference::I31(value as u32 & 0x7fff_ffff)));
+            }
+            Instruction::I31GetS => match self.pop()? {
+                Reference::I31(value) => self.push(((value << 1) as i32) >> 1),
+                _ => return Err(Trap::new(TrapKind::NullI31Reference)),
+            },
+            Instruction::I31GetU => match self.pop()? {
+                Reference::I31(value) => self.push(value as i32),
+                _ => return Err(Trap::new(TrapKind::NullI31Reference)),
+            },
+            Instruction::AnyConvertExtern => {
+                let reference: Reference = self.pop()?;
+                self.push(Value::Ref(reference.convert_any()));
+            }
+            Instruction::ExternConvertAny => {
+                let reference: Reference = self.pop()?;
+                self.push(Value::Ref(reference.convert_extern()));
+            }
+
+            // Parametric instructions
+            Instruction::Drop => {
+                self.pop_value()?;
+            }
+            Instruction::Select | Instruction::SelectTyped(_) => {
+                let condition = self.pop::<i32>()?;
+                let value_2 = self.pop_value()?;
+                let value_1 = self.pop_value()?;
+                self.push(if condition != 0 { value_1 } else { value_2 });
+            }
+
+            // Variable instructions
+            Instruction::LocalGet(local_index) => self.push(locals[*local_index as usize]),
+            Instruction::LocalSet(local_index) => {
+                locals[*local_index as usize] = self.pop_value()?
+            }
+            Instruction::LocalTee(local_index) => {
+                let value = self.pop_value()?;
+                locals[*local_index as usize] = value;
+                self.push(value);
+            }
+            Instruction::GlobalGet(global_index) => {
+                let global_address = self.module().global_addresses[*global_index as usize];
+                let value = self.store.globals[global_address].value;
+                self.push(value);
+            }
+            Instruction::GlobalSet(global_index) => {
+                let value = self.pop_value()?;
+                let global_address = self.module().global_addresses[*global_index as usize];
+                self.store.globals[global_address].value = value;
+            }
+
+            _ => self.execute_memory_instruction(instruction)?,
+        }
+
+        Ok(())
+    }
+
+    // Memory and table instructions, the tables being accessed like memories of references.
+    //
+    // https://webassembly.github.io/spec/core/exec/instructions.html#memory-instructions
+    fn execute_memory_instruction(&mut self, instruction: &Instruction) -> ExecutionResult<()> {
+        match instruction {
+            // Memory instructions
+            Instruction::I32Load(memory_arg) => {
+                let value = self.load_memory(memory_arg, 4)?;
+                self.push(value as i32);
+            }
+            Instruction::I64Load(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(value as i64);
+            }
+            Instruction::F32Load(memory_arg) => {
+                let value = self.load_memory(memory_arg, 4)?;
+                self.push(f32::from_bits(value as u32));
+            }
+            Instruction::F64Load(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(f64::from_bits(value as u64));
+            }
+            Instruction::I32Load8S(memory_arg) => {
+                let value = self.load_memory(memory_arg, 1)?;
+                self.push(i32::from(value as i8));
+            }
+            Instruction::I32Load8U(memory_arg) => {
+                let value = self.load_memory(memory_arg, 1)?;
+                self.push(value as i32);
+            }
+            Instruction::I32Load16S(memory_arg) => {
+                let value = self.load_memory(memory_arg, 2)?;
+                self.push(i32::from(value as i16));
+            }
+            Instruction::I32Load16U(memory_arg) => {
+                let value = self.load_memory(memory_arg, 2)?;
+                self.push(value as i32);
+            }
+            Instruction::I64Load8S(memory_arg) => {
+                let value = self.load_memory(memory_arg, 1)?;
+                self.push(i64::from(value as i8));
+            }
+            Instruction::I64Load8U(memory_arg) => {
+                let value = self.load_memory(memory_arg, 1)?;
+                self.push(value as i64);
+            }
+            Instruction::I64Load16S(memory_arg) => {
+                let value = self.load_memory(memory_arg, 2)?;
+                self.push(i64::from(value as i16));
+            }
+            Instruction::I64Load16U(memory_arg) => {
+                let value = self.load_memory(memory_arg, 2)?;
+                self.push(value as i64);
+            }
+            Instruction::I64Load32S(memory_arg) => {
+                let value = self.load_memory(memory_arg, 4)?;
+                self.push(i64::from(value as i32));
+            }
+            Instruction::I64Load32U(memory_arg) => {
+                let value = self.load_memory(memory_arg, 4)?;
+                self.push(value as i64);
+            }
+            Instruction::I32Store(memory_arg) => {
+                let value = self.pop::<i32>()?;
+                self.store_memory(memory_arg, u128::from(value as u32), 4)?;
+            }
+            Instruction::I64Store(memory_arg) => {
+                let value = self.pop::<i64>()?;
+                self.store_memory(memory_arg, u128::from(value as u64), 8)?;
+            }
+            Instruction::F32Store(memory_arg) => {
+                let value = self.pop::<f32>()?;
+                self.store_memory(memory_arg, u128::from(value.to_bits()), 4)?;
+            }
+            Instruction::F64Store(memory_arg) => {
+                let value = self.pop::<f64>()?;
+                self.store_memory(memory_arg, u128::from(value.to_bits()), 8)?;
+            }
+            Instruction::I32Store8(memory_arg) => {
+                let value = self.pop::<i32>()?;
+                self.store_memory(memory_arg, u128::from(value as u32), 1)?;
+            }
+            Instruction::I32Store16(memory_arg) => {
+                let value = self.pop::<i32>()?;
+                self.store_memory(memory_arg, u128::from(value as u32), 2)?;
+            }
+            Instruction::I64Store8(memory_arg) => {
+                let value = self.pop::<i64>()?;
+                self.store_memory(memory_arg, u128::from(value as u64), 1)?;
+            }
+            Instruction::I64Store16(memory_arg) => {
+                let value = self.pop::<i64>()?;
+                self.store_memory(memory_arg, u128::from(value as u64), 2)?;
+            }
+            Instruction::I64Store32(memory_arg) => {
+                let value = self.pop::<i64>()?;
+                self.store_memory(memory_arg, u128::from(value as u64), 4)?;
+            }
+            Instruction::V128Load(memory_arg) => {
+                let value = self.load_memory(memory_arg, 16)?;
+                self.push(value);
+            }
+            Instruction::V128Load8x8S(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(vectors::extend(value, false, |a: i8| i16::from(a)));
+            }
+            Instruction::V128Load8x8U(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(vectors::extend(value, false, |a: u8| u16::from(a)));
+            }
+            Instruction::V128Load16x4S(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(vectors::extend(value, false, |a: i16| i32::from(a)));
+            }
+            Instruction::V128Load16x4U(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(vectors::extend(value, false, |a: u16| u32::from(a)));
+            }
+            Instruction::V128Load32x2S(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(vectors::extend(value, false, |a: i32| i64::from(a)));
+            }
+            Instruction::V128Load32x2U(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(vectors::extend(value, false, |a: u32| u64::from(a)));
+            }
+            Instruction::V128Load8Splat(memory_arg) => {
+                let value = self.load_memory(memory_arg, 1)?;
+                self.push(vectors::from_lanes(std::iter::repeat(value as u8)));
+            }
+            Instruction::V128Load16Splat(memory_arg) => {
+                let value = self.load_memory(memory_arg, 2)?;
+                self.push(vectors::from_lanes(std::iter::repeat(value as u16)));
+            }
+            Instruction::V128Load32Splat(memory_arg) => {
+                let value = self.load_memory(memory_arg, 4)?;
+                self.push(vectors::from_lanes(std::iter::repeat(value as u32)));
+            }
+            Instruction::V128Load64Splat(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(vectors::from_lanes(std::iter::repeat(value as u64)));
+            }
+            Instruction::V128Load32Zero(memory_arg) => {
+                let value = self.load_memory(memory_arg, 4)?;
+                self.push(value);
+            }
+            Instruction::V128Load64Zero(memory_arg) => {
+                let value = self.load_memory(memory_arg, 8)?;
+                self.push(value);
+            }
+            Instruction::V128Load8Lane(memory_arg, lane) => {
+                self.load_lane::<u8>(memory_arg, *lane)?;
+            }
+            Instruction::V128Load16Lane(memory_arg, lane) => {
+                self.load_lane::<u16>(memory_arg, *lane)?;
+            }
+            Instruction::V128Load32Lane(memory_arg, lane) => {
+                self.load_lane::<u32>(memory_arg, *lane)?;
+            }
+            Instruction::V128Load64Lane(memory_arg, lane) => {
+                self.load_lane::<u64>(memory_arg, *lane)?;
+            }
+            Instruction::V128Store(memory_arg) => {
+                let value = self.pop::<u128>()?;
+                self.store_memory(memory_arg, value, 16)?;
+            }
+            Instruction::V128Store8Lane(memory_arg, lane) => {
+                let value = self.pop::<u128>()?;
+                let lane = vectors::lanes::<u8>(value)[*lane as usize];
+                self.store_memory(memory_arg, u128::from(lane), 1)?;
+            }
+            Instruction::V128Store16Lane(memory_arg, lane) => {
+                let value = self.pop::<u128>()?;
+                let lane = vectors::lanes::<u16>(value)[*lane as usize];
+                self.store_memory(memory_arg, u128::from(lane), 2)?;
+            }
+            Instruction::V128Store32Lane(memory_arg, lane) => {
+                let value = self.pop::<u128>()?;
+                let lane = vectors::lanes::<u32>(value)[*lane as usize];
+                self.store_memory(memory_arg, u128::from(lane), 4)?;
+            }
+            Instruction::V128Store64Lane(memory_arg, lane) => {
+                let value = self.pop::<u128>()?;
+                let lane = vectors::lanes::<u64>(value)[*lane as usize];
+                self.store_memory(memory_arg, u128::from(lane), 8)?;
+            }
+            Instruction::MemorySize(memory_index) => {
+                let memory_address = self.memory_address(*memory_index);
+                let size = self.store.memories[memory_address].data.len() as u64 / PAGE_SIZE;
+                self.push_address(memory_address, size);
+            }
+            Instruction::MemoryGrow(memory_index) => {
+                let memory_address = self.memory_address(*memory_index);
+                let delta = self.pop_address()?;
+                let size = self.grow_memory(memory_address, delta).unwrap_or(u64::MAX);
+                self.push_address(memory_address, size);
+            }
+            Instruction::MemoryInit { memory, data } => {
+                let length = self.pop_index()?;
+                let source = self.pop_index()?;
+                let destination = self.pop_address()?;
+                let memory_address = self.memory_address(*memory);
+                let data_address = self.module().data_addresses[*data as usize];
+                self.store.memory_init(
+                    memory_address,
+                    data_address,
+                    destination,
+                    source,
+                    length,
+                )?;
+            }
+            Instruction::DataDrop(data_index) => {
+                let data_address = self.module().data_addresses[*data_index as usize];
+                self.store.data[data_address].clear();
+            }
+            Instruction::MemoryCopy(destination_index, source_index) => {
+                let length = self.pop_address()?;
+                let source = self.pop_address()?;
+                let destination = self.pop_address()?;
+
+                let destination_address = self.memory_address(*destination_index);
+                let source_address = self.memory_address(*source_index);
+
+                let memories = &mut self.store.memories;
+                let source_range = get_range(source, length, memories[source_address].data.len())
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsMemoryAccess))?;
+                let destination_range = get_range(
+                    destination,
+                    length,
+                    memories[destination_address].data.len(),
+                )
+                .ok_or(Trap::new(TrapKind::OutOfBoundsMemoryAccess))?;
+
+                if destination_address == source_address {
+                    let data = &mut memories[destination_address].data;
+                    data.copy_within(source_range, destination_range.start);
+                } else {
+                    let bytes = memories[source_address].data[source_range].to_vec();
+                    memories[destination_address].data[destination_range].copy_from_slice(&bytes);
+                }
+            }
+            Instruction::MemoryFill(memory_index) => {
+                let length = self.pop_address()?;
+                let value = self.pop::<i32>()?;
+                let destination = self.pop_address()?;
+
+                let memory_address = self.memory_address(*memory_index);
+                let data = &mut self.store.memories[memory_address].data;
+                let range = get_range(destination, length, data.len())
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsMemoryAccess))?;
+                data[range].fill(value as u8);
+            }
+
+            // Table instructions
+            Instruction::TableGet(table_index) => {
+                let index = self.pop_index()?;
+                let table = &self.store.tables[self.table_address(*table_index)];
+                let reference = *table
+                    .elements
+                    .get(index as usize)
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsTableAccess))?;
+                self.push(Value::Ref(reference));
+            }
+            Instruction::TableSet(table_index) => {
+                let reference = self.pop()?;
+                let index = self.pop_index()?;
+                let table_address = self.table_address(*table_index);
+                let table = &mut self.store.tables[table_address];
+                let element = table
+                    .elements
+                    .get_mut(index as usize)
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsTableAccess))?;
+                *element = reference;
+            }
+            Instruction::TableSize(table_index) => {
+                let table = &self.store.tables[self.table_address(*table_index)];
+                let size = table.elements.len() as i32;
+                self.push(size);
+            }
+            Instruction::TableGrow(table_index) => {
+                let delta = self.pop_index()?;
+                let init = self.pop()?;
+                let size = self.grow_table(self.table_address(*table_index), delta, init);
+                self.push(size.map_or(-1, |size| size as i32));
+            }
+            Instruction::TableFill(table_index) => {
+                let length = self.pop_index()?;
+                let reference = self.pop()?;
+                let destination = self.pop_index()?;
+
+                let table_address = self.table_address(*table_index);
+                let elements = &mut self.store.tables[table_address].elements;
+                let range = get_range(destination, length, elements.len())
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsTableAccess))?;
+                elements[range].fill(reference);
+            }
+            Instruction::TableInit { table, element } => {
+                let length = self.pop_index()?;
+                let source = self.pop_index()?;
+                let destination = self.pop_index()?;
+                let table_address = self.table_address(*table);
+                let element_address = self.module().element_addresses[*element as usize];
+                self.store.table_init(
+                    table_address,
+                    element_address,
+                    destination,
+                    source,
+                    length,
+                )?;
+            }
+            Instruction::ElemDrop(element_index) => {
+                let element_address = self.module().element_addresses[*element_index as usize];
+                self.store.elements[element_address].clear();
+            }
+            Instruction::TableCopy(destination_index, source_index) => {
+                let length = self.pop_index()?;
+                let source = self.pop_index()?;
+                let destination = self.pop_index()?;
+
+                let destination_address = self.table_address(*destination_index);
+                let source_address = self.table_address(*source_index);
+
+                let tables = &mut self.store.tables;
+                let source_range = get_range(source, length, tables[source_address].elements.len())
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsTableAccess))?;
+                let destination_range = get_range(
+                    destination,
+                    length,
+                    tables[destination_address].elements.len(),
+                )
+                .ok_or(Trap::new(TrapKind::OutOfBoundsTableAccess))?;
+
+                if destination_address == source_address {
+                    let elements = &mut tables[destination_address].elements;
+                    elements.copy_within(source_range, destination_range.start);
+                } else {
+                    let references = tables[source_address].elements[source_range].to_vec();
+                    tables[destination_address].elements[destination_range]
+                        .copy_from_slice(&references);
+                }
+            }
+
+            _ => self.execute_numeric_instruction(instruction)?,
+        }
+
+        Ok(())
+    }
+
+    // https://webassembly.github.io/spec/core/exec/instructions.html#numeric-instructions
+    fn execute_numeric_instruction(&mut self, instruction: &Instruction) -> ExecutionResult<()> {
+        match instruction {
+            // Constants instructions
+            Instruction::I32Const(value) => self.push(*value),
+            Instruction::I64Const(value) => self.push(*value),
+            Instruction::F32Const(value) => self.push(*value),
+            Instruction::F64Const(value) => self.push(*value),
+            Instruction::V128Const(value) => self.push(*value),
+
+            _ => match numerics::get_operand_count(instruction) {
+                Some(1) => {
+                    let operand = self.pop_value()?;
+                    self.stack
+                        .push(numerics::evaluate(instruction, &[operand])?);
+                }
+                Some(_) => {
+                    let rhs = self.pop_value()?;
+                    let lhs = self.pop_value()?;
+                    self.stack
+                        .push(numerics::evaluate(instruction, &[lhs, rhs])?);
+                }
+                None => self.execute_vector_instruction(instruction)?,
+            },
+        }
+
+        Ok(())
+    }
+
+    // https://webassembly.github.io/spec/core/exec/instructions.html#vector-instructions
+    fn execute_vector_instruction(&mut self, instruction: &Instruction) -> ExecutionResult<()> {
+        match vectors::get_operand_count(instruction) {
+            Some(count) => {
+                let height = self
+                    .stack
+                    .len()
+                    .checked_sub(count)
+                    .ok_or(Trap::from("Unexpected empty operand stack"))?;
+                let operands = self.stack.split_off(height);
+                self.stack.push(vectors::evaluate(instruction, &operands)?);
+            }
+            None => self.execute_atomic_instruction(instruction)?,
+        }
+
+        Ok(())
+    }
+
+    // Instructions are executed by a single thread, so the atomic accesses behave as the plain
+    // ones, and no other thread can wake a waiter up.
+    //
+    // https://webassembly.github.io/threads/core/exec/instructions.html#atomic-memory-instructions
+    fn execute_atomic_instruction(&mut self, instruction: &Instruction) -> ExecutionResult<()> {
+        match instruction {
+            Instruction::MemoryAtomicNotify(memory_arg) => {
+                self.pop::<i32>()?;
+                self.pop_atomic_range(memory_arg, 4)?;
+                self.push(0);
+            }
+            Instruction::MemoryAtomicWait32(memory_arg) => {
+                self.pop::<i64>()?;
+                let expected = u64::from(self.pop::<i32>()? as u32);
+                self.wait(memory_arg, expected, 4)?;
+            }
+            Instruction::MemoryAtomicWait64(memory_arg) => {
+                self.pop::<i64>()?;
+                let expected = self.pop::<i64>()? as u64;
+                self.wait(memory_arg, expected, 8)?;
+            }
+            Instruction::AtomicFence => {}
+            Instruction::I32AtomicLoad(memory_arg) => {
+                let value = self.load_atomic(memory_arg, 4)?;
+                self.push(value as i32);
+            }
+            Instruction::I64AtomicLoad(memory_arg) => {
+                let value = self.load_atomic(memory_arg, 8)?;
+                self.push(value as i64);
+            }
+            Instruction::I32AtomicLoad8U(memory_arg) => {
+                let value = self.load_atomic(memory_arg, 1)?;
+                self.push(value as i32);
+            }
+            Instruction::I32AtomicLoad16U(memory_arg) => {
+                let value = self.load_atomic(memory_arg, 2)?;
+                self.push(value as i32);
+            }
+            Instruction::I64AtomicLoad8U(memory_arg) => {
+                let value = self.load_atomic(memory_arg, 1)?;
+                self.push(value as i64);
+            }
+            Instruction::I64AtomicLoad16U(memory_arg) => {
+                let value = self.load_atomic(memory_arg, 2)?;
+                self.push(value as i64);
+            }
+            Instruction::I64AtomicLoad32U(memory_arg) => {
+                let value = self.load_atomic(memory_arg, 4)?;
+                self.push(value as i64);
+            }
+            Instruction::I32AtomicStore(memory_arg) => self.store_atomic(memory_arg, 4)?,
+            Instruction::I64AtomicStore(memory_arg) => self.store_atomic(memory_arg, 8)?,
+            Instruction::I32AtomicStore8(memory_arg) | Instruction::I64AtomicStore8(memory_arg) => {
+                self.store_atomic(memory_arg, 1)?
+            }
+            Instruction::I32AtomicStore16(memory_arg)
+            | Instruction::I64AtomicStore16(memory_arg) => self.store_atomic(memory_arg, 2)?,
+            Instruction::I64AtomicStore32(memory_arg) => self.store_atomic(memory_arg, 4)?,
+            Instruction::I32AtomicRmwAdd(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, u64::wrapping_add)?
+            }
+            Instruction::I64AtomicRmwAdd(memory_arg) => {
+                self.atomic_rmw(memory_arg, 8, u64::wrapping_add)?
+            }
+            Instruction::I32AtomicRmw8AddU(memory_arg)
+            | Instruction::I64AtomicRmw8AddU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 1, u64::wrapping_add)?
+            }
+            Instruction::I32AtomicRmw16AddU(memory_arg)
+            | Instruction::I64AtomicRmw16AddU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 2, u64::wrapping_add)?
+            }
+            Instruction::I64AtomicRmw32AddU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, u64::wrapping_add)?
+            }
+            Instruction::I32AtomicRmwSub(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, u64::wrapping_sub)?
+            }
+            Instruction::I64AtomicRmwSub(memory_arg) => {
+                self.atomic_rmw(memory_arg, 8, u64::wrapping_sub)?
+            }
+            Instruction::I32AtomicRmw8SubU(memory_arg)
+            | Instruction::I64AtomicRmw8SubU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 1, u64::wrapping_sub)?
+            }
+            Instruction::I32AtomicRmw16SubU(memory_arg)
+            | Instruction::I64AtomicRmw16SubU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 2, u64::wrapping_sub)?
+            }
+            Instruction::I64AtomicRmw32SubU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, u64::wrapping_sub)?
+            }
+            Instruction::I32AtomicRmwAnd(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, |a, b| a & b)?
+            }
+            Instruction::I64AtomicRmwAnd(memory_arg) => {
+                self.atomic_rmw(memory_arg, 8, |a, b| a & b)?
+            }
+            Instruction::I32AtomicRmw8AndU(memory_arg)
+            | Instruction::I64AtomicRmw8AndU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 1, |a, b| a & b)?
+            }
+            Instruction::I32AtomicRmw16AndU(memory_arg)
+            | Instruction::I64AtomicRmw16AndU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 2, |a, b| a & b)?
+            }
+            Instruction::I64AtomicRmw32AndU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, |a, b| a & b)?
+            }
+            Instruction::I32AtomicRmwOr(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, |a, b| a | b)?
+            }
+            Instruction::I64AtomicRmwOr(memory_arg) => {
+                self.atomic_rmw(memory_arg, 8, |a, b| a | b)?
+            }
+            Instruction::I32AtomicRmw8OrU(memory_arg)
+            | Instruction::I64AtomicRmw8OrU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 1, |a, b| a | b)?
+            }
+            Instruction::I32AtomicRmw16OrU(memory_arg)
+            | Instruction::I64AtomicRmw16OrU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 2, |a, b| a | b)?
+            }
+            Instruction::I64AtomicRmw32OrU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, |a, b| a | b)?
+            }
+            Instruction::I32AtomicRmwXor(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, |a, b| a ^ b)?
+            }
+            Instruction::I64AtomicRmwXor(memory_arg) => {
+                self.atomic_rmw(memory_arg, 8, |a, b| a ^ b)?
+            }
+            Instruction::I32AtomicRmw8XorU(memory_arg)
+            | Instruction::I64AtomicRmw8XorU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 1, |a, b| a ^ b)?
+            }
+            Instruction::I32AtomicRmw16XorU(memory_arg)
+            | Instruction::I64AtomicRmw16XorU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 2, |a, b| a ^ b)?
+            }
+            Instruction::I64AtomicRmw32XorU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, |a, b| a ^ b)?
+            }
+            Instruction::I32AtomicRmwXchg(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, |_, b| b)?
+            }
+            Instruction::I64AtomicRmwXchg(memory_arg) => {
+                self.atomic_rmw(memory_arg, 8, |_, b| b)?
+            }
+            Instruction::I32AtomicRmw8XchgU(memory_arg)
+            | Instruction::I64AtomicRmw8XchgU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 1, |_, b| b)?
+            }
+            Instruction::I32AtomicRmw16XchgU(memory_arg)
+            | Instruction::I64AtomicRmw16XchgU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 2, |_, b| b)?
+            }
+            Instruction::I64AtomicRmw32XchgU(memory_arg) => {
+                self.atomic_rmw(memory_arg, 4, |_, b| b)?
+            }
+            Instruction::I32AtomicRmwCmpxchg(memory_arg) => self.atomic_cmpxchg(memory_arg, 4)?,
+            Instruction::I64AtomicRmwCmpxchg(memory_arg) => self.atomic_cmpxchg(memory_arg, 8)?,
+            Instruction::I32AtomicRmw8CmpxchgU(memory_arg)
+            | Instruction::I64AtomicRmw8CmpxchgU(memory_arg) => {
+                self.atomic_cmpxchg(memory_arg, 1)?
+            }
+            Instruction::I32AtomicRmw16CmpxchgU(memory_arg)
+            | Instruction::I64AtomicRmw16CmpxchgU(memory_arg) => {
+                self.atomic_cmpxchg(memory_arg, 2)?
+            }
+            Instruction::I64AtomicRmw32CmpxchgU(memory_arg) => {
+                self.atomic_cmpxchg(memory_arg, 4)?
+            }
+
+            _ => self.execute_aggregate_instruction(instruction)?,
+        }
+
+        Ok(())
+    }
+
+    // https://webassembly.github.io/gc/core/exec/instructions.html#aggregate-reference-instructions
+    fn execute_aggregate_instruction(&mut self, instruction: &Instruction) -> ExecutionResult<()> {
+        match instruction {
+            Instruction::StructNew(type_index) => {
+                let field_count = self.get_struct_type(*type_index)?.len();
+                let fields = self.stack.split_off(self.stack.len() - field_count);
+                let type_address = self.module().type_addresses[*type_index as usize];
+                let reference = self.store.allocate_struct(type_address, fields);
+                self.push(Value::Ref(reference));
+            }
+            Instruction::StructNewDefault(type_index) => {
+                let fields = self
+                    .get_struct_type(*type_index)?
+                    .iter()
+                    .map(|field_type| aggregates::default(&field_type.storage_type))
+                    .collect();
+                let type_address = self.module().type_addresses[*type_index as usize];
+                let reference = self.store.allocate_struct(type_address, fields);
+                self.push(Value::Ref(reference));
+            }
+            Instruction::StructGet(type_index, field_index)
+            | Instruction::StructGetS(type_index, field_index)
+            | Instruction::StructGetU(type_index, field_index) => {
+                let field_type = self.get_struct_type(*type_index)?[*field_index as usize];
+                let address = self.pop_struct()?;
+                let value = self.store.structs[address].fields[*field_index as usize];
+                let is_signed = matches!(instruction, Instruction::StructGetS(_, _));
+                self.push(aggregates::unpack(
+                    &field_type.storage_type,
+                    value,
+                    is_signed,
+                ));
+            }
+            Instruction::StructSet(_, field_index) => {
+                let value = self.pop_value()?;
+                let address = self.pop_struct()?;
+                self.store.structs[address].fields[*field_index as usize] = value;
+            }
+            Instruction::ArrayNew(type_index) | Instruction::ArrayNewDefault(type_index) => {
+                let length = self.pop_index()? as usize;
+                let value = match instruction {
+                    Instruction::ArrayNew(_) => self.pop_value()?,
+                    _ => aggregates::default(&self.get_array_type(*type_index)?.storage_type),
+                };
+
+                let mut elements = Vec::new();
+                elements
+                    .try_reserve_exact(length)
+                    .map_err(|_| Trap::from("Unable to allocate array"))?;
+                elements.resize(length, value);
+                self.push_array(*type_index, elements);
+            }
+            Instruction::ArrayNewFixed(type_index, length) => {
+                let elements = self.stack.split_off(self.stack.len() - *length as usize);
+                self.push_array(*type_index, elements);
+            }
+            Instruction::ArrayNewData(type_index, data_index) => {
+                let length = self.pop_index()?;
+                let offset = self.pop_index()?;
+                let field_type = self.get_array_type(*type_index)?;
+                let elements = self
+                    .read_data(&field_type, *data_index, offset, length)
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsMemoryAccess))?;
+                self.push_array(*type_index, elements);
+            }
+            Instruction::ArrayNewElem(type_index, element_index) => {
+                let length = self.pop_index()?;
+                let offset = self.pop_index()?;
+                let elements = self
+                    .read_elements(*element_index, offset, length)
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsTableAccess))?;
+                self.push_array(*type_index, elements);
+            }
+            Instruction::ArrayGet(type_index)
+            | Instruction::ArrayGetS(type_index)
+            | Instruction::ArrayGetU(type_index) => {
+                let field_type = self.get_array_type(*type_index)?;
+                let (address, range) = self.pop_array_range(1)?;
+                let value = self.store.arrays[address].elements[range.start];
+                let is_signed = matches!(instruction, Instruction::ArrayGetS(_));
+                self.push(aggregates::unpack(
+                    &field_type.storage_type,
+                    value,
+                    is_signed,
+                ));
+            }
+            Instruction::ArraySet(_) => {
+                let value = self.pop_value()?;
+                let (address, range) = self.pop_array_range(1)?;
+                self.store.arrays[address].elements[range.start] = value;
+            }
+            Instruction::ArrayLen => {
+                let address = self.pop_array()?;
+                let length = self.store.arrays[address].elements.len();
+                self.push(length as i32);
+            }
+            Instruction::ArrayFill(_) => {
+                let length = self.pop_index()?;
+                let value = self.pop_value()?;
+                let (address, range) = self.pop_array_range(length)?;
+                self.store.arrays[address].elements[range].fill(value);
+            }
+            Instruction::ArrayCopy(_, _) => {
+                let length = self.pop_index()?;
+                let (source_address, source_range) = self.pop_array_range(length)?;
+                let (destination_address, destination_range) = self.pop_array_range(length)?;
+                let elements = self.store.arrays[source_address].elements[source_range].to_vec();
+                self.store.arrays[destination_address].elements[destination_range]
+                    .copy_from_slice(&elements);
+            }
+            Instruction::ArrayInitData(type_index, data_index) => {
+                let length = self.pop_index()?;
+                let source = self.pop_index()?;
+                let (address, range) = self.pop_array_range(length)?;
+                let field_type = self.get_array_type(*type_index)?;
+                let elements = self
+                    .read_data(&field_type, *data_index, source, length)
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsMemoryAccess))?;
+                self.store.arrays[address].elements[range].copy_from_slice(&elements);
+            }
+            Instruction::ArrayInitElem(_, element_index) => {
+                let length = self.pop_index()?;
+                let source = self.pop_index()?;
+                let (address, range) = self.pop_array_range(length)?;
+                let elements = self
+                    .read_elements(*element_index, source, length)
+                    .ok_or(Trap::new(TrapKind::OutOfBoundsTableAccess))?;
+                self.store.arrays[address].elements[range].copy_from_slice(&elements);
+            }
+
+            _ => {
+                return Err(Trap::from_string(format!(
+                    "Unsupported instruction {:?}",
+                    instruction
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,10 @@
+pub mod aggregates;
+pub mod constants;
+pub mod host;
+pub mod instance;
+mod interpreter;
+pub mod numerics;
+pub mod store;
+pub mod trap;
+pub mod values;
+pub mod vectors;
@@ -0,0 +1,496 @@
+use crate::execution::trap::{ExecutionResult, Trap, TrapKind};
+use crate::execution::values::{FromValue, Value};
+use crate::structure::Instruction;
+
+/// https://webassembly.github.io/spec/core/exec/numerics.html
+///
+/// Returns the number of operands popped by a numeric instruction, or `None` for the other
+/// instructions. Numeric instructions push a single result.
+pub fn get_operand_count(instruction: &Instruction) -> Option<usize> {
+    match instruction {
+        Instruction::I32Eqz
+        | Instruction::I64Eqz
+        | Instruction::I32Clz
+        | Instruction::I32Ctz
+        | Instruction::I32Popcnt
+        | Instruction::I64Clz
+        | Instruction::I64Ctz
+        | Instruction::I64Popcnt
+        | Instruction::F32Abs
+        | Instruction::F32Neg
+        | Instruction::F32Ceil
+        | Instruction::F32Floor
+        | Instruction::F32Trunc
+        | Instruction::F32Nearest
+        | Instruction::F32Sqrt
+        | Instruction::F64Abs
+        | Instruction::F64Neg
+        | Instruction::F64Ceil
+        | Instruction::F64Floor
+        | Instruction::F64Trunc
+        | Instruction::F64Nearest
+        | Instruction::F64Sqrt
+        | Instruction::I32WrapI64
+        | Instruction::I32TruncSF32
+        | Instruction::I32TruncUF32
+        | Instruction::I32TruncSF64
+        | Instruction::I32TruncUF64
+        | Instruction::I64ExtendSI32
+        | Instruction::I64ExtendUI32
+        | Instruction::I64TruncSF32
+        | Instruction::I64TruncUF32
+        | Instruction::I64TruncSF64
+        | Instruction::I64TruncUF64
+        | Instruction::F32ConvertSI32
+        | Instruction::F32ConvertUI32
+        | Instruction::F32ConvertSI64
+        | Instruction::F32ConvertUI64
+        | Instruction::F32DemoteF64
+        | Instruction::F64ConvertSI32
+        | Instruction::F64ConvertUI32
+        | Instruction::F64ConvertSI64
+        | Instruction::F64ConvertUI64
+        | Instruction::F64PromoteF32
+        | Instruction::I32ReinterpretF32
+        | Instruction::I64ReinterpretF64
+        | Instruction::F32ReinterpretI32
+        | Instruction::F64ReinterpretI64
+        | Instruction::I32Extend8S
+        | Instruction::I32Extend16S
+        | Instruction::I64Extend8S
+        | Instruction::I64Extend16S
+        | Instruction::I64Extend32S
+        | Instruction::I32TruncSatSF32
+        | Instruction::I32TruncSatUF32
+        | Instruction::I32TruncSatSF64
+        | Instruction::I32TruncSatUF64
+        | Instruction::I64TruncSatSF32
+        | Instruction::I64TruncSatUF32
+        | Instruction::I64TruncSatSF64
+        | Instruction::I64TruncSatUF64 => Some(1),
+
+        Instruction::I32Eq
+        | Instruction::I32Ne
+        | Instruction::I32LtS
+        | Instruction::I32LtU
+        | Instruction::I32GtS
+        | Instruction::I32GtU
+        | Instruction::I32LeS
+        | Instruction::I32LeU
+        | Instruction::I32GeS
+        | Instruction::I32GeU
+        | Instruction::I64Eq
+        | Instruction::I64Ne
+        | Instruction::I64LtS
+        | Instruction::I64LtU
+        | Instruction::I64GtS
+        | Instruction::I64GtU
+        | Instruction::I64LeS
+        | Instruction::I64LeU
+        | Instruction::I64GeS
+        | Instruction::I64GeU
+        | Instruction::F32Eq
+        | Instruction::F32Ne
+        | Instruction::F32Lt
+        | Instruction::F32Gt
+        | Instruction::F32Le
+        | Instruction::F32Ge
+        | Instruction::F64Eq
+        | Instruction::F64Ne
+        | Instruction::F64Lt
+        | Instruction::F64Gt
+        | Instruction::F64Le
+        | Instruction::F64Ge
+        | Instruction::I32Add
+        | Instruction::I32Sub
+        | Instruction::I32Mul
+        | Instruction::I32DivS
+        | Instruction::I32DivU
+        | Instruction::I32RemS
+        | Instruction::I32RemU
+        | Instruction::I32And
+        | Instruction::I32Or
+        | Instruction::I32Xor
+        | Instruction::I32Shl
+        | Instruction::I32ShrS
+        | Instruction::I32ShrU
+        | Instruction::I32Rotl
+        | Instruction::I32Rotr
+        | Instruction::I64Add
+        | Instruction::I64Sub
+        | Instruction::I64Mul
+        | Instruction::I64DivS
+        | Instruction::I64DivU
+        | Instruction::I64RemS
+        | Instruction::I64RemU
+        | Instruction::I64And
+        | Instruction::I64Or
+        | Instruction::I64Xor
+        | Instruction::I64Shl
+        | Instruction::I64ShrS
+        | Instruction::I64ShrU
+        | Instruction::I64Rotl
+        | Instruction::I64Rotr
+        | Instruction::F32Add
+        | Instruction::F32Sub
+        | Instruction::F32Mul
+        | Instruction::F32Div
+        | Instruction::F32Min
+        | Instruction::F32Max
+        | Instruction::F32CopySign
+        | Instruction::F64Add
+        | Instruction::F64Sub
+        | Instruction::F64Mul
+        | Instruction::F64Div
+        | Instruction::F64Min
+        | Instruction::F64Max
+        | Instruction::F64CopySign => Some(2),
+
+        _ => None,
+    }
+}
+
+/// https://webassembly.github.io/spec/core/exec/numerics.html
+///
+/// Applies a numeric instruction to its operands, ordered as they were pushed on the stack.
+/// Integer operations wrap around and shift counts are taken modulo the bit width, while
+/// divisions and truncations trap. Floating point operations follow IEEE 754, and return a quiet
+/// NaN when an operand is NaN.
+pub fn evaluate(instruction: &Instruction, operands: &[Value]) -> ExecutionResult<Value> {
+    match instruction {
+        // Comparison operators
+        Instruction::I32Eqz => unary(operands, |a: i32| a == 0),
+        Instruction::I32Eq => binary(operands, |a: i32, b| a == b),
+        Instruction::I32Ne => binary(operands, |a: i32, b| a != b),
+        Instruction::I32LtS => binary(operands, |a: i32, b| a < b),
+        Instruction::I32LtU => binary(operands, |a: i32, b| (a as u32) < (b as u32)),
+        Instruction::I32GtS => binary(operands, |a: i32, b| a > b),
+        Instruction::I32GtU => binary(operands, |a: i32, b| (a as u32) > (b as u32)),
+        Instruction::I32LeS => binary(operands, |a: i32, b| a <= b),
+        Instruction::I32LeU => binary(operands, |a: i32, b| (a as u32) <= (b as u32)),
+        Instruction::I32GeS => binary(operands, |a: i32, b| a >= b),
+        Instruction::I32GeU => binary(operands, |a: i32, b| (a as u32) >= (b as u32)),
+        Instruction::I64Eqz => unary(operands, |a: i64| a == 0),
+        Instruction::I64Eq => binary(operands, |a: i64, b| a == b),
+        Instruction::I64Ne => binary(operands, |a: i64, b| a != b),
+        Instruction::I64LtS => binary(operands, |a: i64, b| a < b),
+        Instruction::I64LtU => binary(operands, |a: i64, b| (a as u64) < (b as u64)),
+        Instruction::I64GtS => binary(operands, |a: i64, b| a > b),
+        Instruction::I64GtU => binary(operands, |a: i64, b| (a as u64) > (b as u64)),
+        Instruction::I64LeS => binary(operands, |a: i64, b| a <= b),
+        Instruction::I64LeU => binary(operands, |a: i64, b| (a as u64) <= (b as u64)),
+        Instruction::I64GeS => binary(operands, |a: i64, b| a >= b),
+        Instruction::I64GeU => binary(operands, |a: i64, b| (a as u64) >= (b as u64)),
+        Instruction::F32Eq => binary(operands, |a: f32, b| a == b),
+        Instruction::F32Ne => binary(operands, |a: f32, b| a != b),
+        Instruction::F32Lt => binary(operands, |a: f32, b| a < b),
+        Instruction::F32Gt => binary(operands, |a: f32, b| a > b),
+        Instruction::F32Le => binary(operands, |a: f32, b| a <= b),
+        Instruction::F32Ge => binary(operands, |a: f32, b| a >= b),
+        Instruction::F64Eq => binary(operands, |a: f64, b| a == b),
+        Instruction::F64Ne => binary(operands, |a: f64, b| a != b),
+        Instruction::F64Lt => binary(operands, |a: f64, b| a < b),
+        Instruction::F64Gt => binary(operands, |a: f64, b| a > b),
+        Instruction::F64Le => binary(operands, |a: f64, b| a <= b),
+        Instruction::F64Ge => binary(operands, |a: f64, b| a >= b),
+
+        // Numeric operators
+        Instruction::I32Clz => unary(operands, |a: i32| a.leading_zeros() as i32),
+        Instruction::I32Ctz => unary(operands, |a: i32| a.trailing_zeros() as i32),
+        Instruction::I32Popcnt => unary(operands, |a: i32| a.count_ones() as i32),
+        Instruction::I32Add => binary(operands, |a: i32, b| a.wrapping_add(b)),
+        Instruction::I32Sub => binary(operands, |a: i32, b| a.wrapping_sub(b)),
+        Instruction::I32Mul => binary(operands, |a: i32, b| a.wrapping_mul(b)),
+        Instruction::I32DivS => binary_trap(operands, |a: i32, b| {
+            if b == 0 {
+                Err(Trap::new(TrapKind::IntegerDivideByZero))
+            } else if a == i32::MIN && b == -1 {
+                Err(Trap::new(TrapKind::IntegerOverflow))
+            } else {
+                Ok(a / b)
+            }
+        }),
+        Instruction::I32DivU => binary_trap(operands, |a: i32, b| {
+            let quotient = (a as u32).checked_div(b as u32);
+            quotient
+                .map(|quotient| quotient as i32)
+                .ok_or(Trap::new(TrapKind::IntegerDivideByZero))
+        }),
+        Instruction::I32RemS => binary_trap(operands, |a: i32, b| {
+            if b == 0 {
+                Err(Trap::new(TrapKind::IntegerDivideByZero))
+            } else {
+                Ok(a.wrapping_rem(b))
+            }
+        }),
+        Instruction::I32RemU => binary_trap(operands, |a: i32, b| {
+            let remainder = (a as u32).checked_rem(b as u32);
+            remainder
+                .map(|remainder| remainder as i32)
+                .ok_or(Trap::new(TrapKind::IntegerDivideByZero))
+        }),
+        Instruction::I32And => binary(operands, |a: i32, b| a & b),
+        Instruction::I32Or => binary(operands, |a: i32, b| a | b),
+        Instruction::I32Xor => binary(operands, |a: i32, b| a ^ b),
+        Instruction::I32Shl => binary(operands, |a: i32, b| a.wrapping_shl(b as u32)),
+        Instruction::I32ShrS => binary(operands, |a: i32, b| a.wrapping_shr(b as u32)),
+        Instruction::I32ShrU => binary(operands, |a: i32, b| {
+            (a as u32).wrapping_shr(b as u32) as i32
+        }),
+        Instruction::I32Rotl => binary(operands, |a: i32, b| a.rotate_left(b as u32)),
+        Instruction::I32Rotr => binary(operands, |a: i32, b| a.rotate_right(b as u32)),
+        Instruction::I64Clz => unary(operands, |a: i64| i64::from(a.leading_zeros())),
+        Instruction::I64Ctz => unary(operands, |a: i64| i64::from(a.trailing_zeros())),
+        Instruction::I64Popcnt => unary(operands, |a: i64| i64::from(a.count_ones())),
+        Instruction::I64Add => binary(operands, |a: i64, b| a.wrapping_add(b)),
+        Instruction::I64Sub => binary(operands, |a: i64, b| a.wrapping_sub(b)),
+        Instruction::I64Mul => binary(operands, |a: i64, b| a.wrapping_mul(b)),
+        Instruction::I64DivS => binary_trap(operands, |a: i64, b| {
+            if b == 0 {
+                Err(Trap::new(TrapKind::IntegerDivideByZero))
+            } else if a == i64::MIN && b == -1 {
+                Err(Trap::new(TrapKind::IntegerOverflow))
+            } else {
+                Ok(a / b)
+            }
+        }),
+        Instruction::I64DivU => binary_trap(operands, |a: i64, b| {
+            let quotient = (a as u64).checked_div(b as u64);
+            quotient
+                .map(|quotient| quotient as i64)
+                .ok_or(Trap::new(TrapKind::IntegerDivideByZero))
+        }),
+        Instruction::I64RemS => binary_trap(operands, |a: i64, b| {
+            if b == 0 {
+                Err(Trap::new(TrapKind::IntegerDivideByZero))
+            } else {
+                Ok(a.wrapping_rem(b))
+            }
+        }),
+        Instruction::I64RemU => binary_trap(operands, |a: i64, b| {
+            let remainder = (a as u64).checked_rem(b as u64);
+            remainder
+                .map(|remainder| remainder as i64)
+                .ok_or(Trap::new(TrapKind::IntegerDivideByZero))
+        }),
+        Instruction::I64And => binary(operands, |a: i64, b| a & b),
+        Instruction::I64Or => binary(operands, |a: i64, b| a | b),
+        Instruction::I64Xor => binary(operands, |a: i64, b| a ^ b),
+        Instruction::I64Shl => binary(operands, |a: i64, b| a.wrapping_shl(b as u32)),
+        Instruction::I64ShrS => binary(operands, |a: i64, b| a.wrapping_shr(b as u32)),
+        Instruction::I64ShrU => binary(operands, |a: i64, b| {
+            (a as u64).wrapping_shr(b as u32) as i64
+        }),
+        Instruction::I64Rotl => binary(operands, |a: i64, b| a.rotate_left(b as u32)),
+        Instruction::I64Rotr => binary(operands, |a: i64, b| a.rotate_right(b as u32)),
+        Instruction::F32Abs => unary(operands, |a: f32| a.abs()),
+        Instruction::F32Neg => unary(operands, |a: f32| -a),
+        Instruction::F32Ceil => unary(operands, |a: f32| quiet_f32(a.ceil())),
+        Instruction::F32Floor => unary(operands, |a: f32| quiet_f32(a.floor())),
+        Instruction::F32Trunc => unary(operands, |a: f32| quiet_f32(a.trunc())),
+        Instruction::F32Nearest => unary(operands, |a: f32| quiet_f32(a.round_ties_even())),
+        Instruction::F32Sqrt => unary(operands, |a: f32| a.sqrt()),
+        Instruction::F32Add => binary(operands, |a: f32, b| a + b),
+        Instruction::F32Sub => binary(operands, |a: f32, b| a - b),
+        Instruction::F32Mul => binary(operands, |a: f32, b| a * b),
+        Instruction::F32Div => binary(operands, |a: f32, b| a / b),
+        Instruction::F32Min => binary(operands, min_f32),
+        Instruction::F32Max => binary(operands, max_f32),
+        Instruction::F32CopySign => binary(operands, |a: f32, b| a.copysign(b)),
+        Instruction::F64Abs => unary(operands, |a: f64| a.abs()),
+        Instruction::F64Neg => unary(operands, |a: f64| -a),
+        Instruction::F64Ceil => unary(operands, |a: f64| quiet_f64(a.ceil())),
+        Instruction::F64Floor => unary(operands, |a: f64| quiet_f64(a.floor())),
+        Instruction::F64Trunc => unary(operands, |a: f64| quiet_f64(a.trunc())),
+        Instruction::F64Nearest => unary(operands, |a: f64| quiet_f64(a.round_ties_even())),
+        Instruction::F64Sqrt => unary(operands, |a: f64| a.sqrt()),
+        Instruction::F64Add => binary(operands, |a: f64, b| a + b),
+        Instruction::F64Sub => binary(operands, |a: f64, b| a - b),
+        Instruction::F64Mul => binary(operands, |a: f64, b| a * b),
+        Instruction::F64Div => binary(operands, |a: f64, b| a / b),
+        Instruction::F64Min => binary(operands, min_f64),
+        Instruction::F64Max => binary(operands, max_f64),
+        Instruction::F64CopySign => binary(operands, |a: f64, b| a.copysign(b)),
+
+        // Conversions, where truncations trap on NaN and on values out of the integer range.
+        Instruction::I32WrapI64 => unary(operands, |a: i64| a as i32),
+        Instruction::I32TruncSF32 => unary_trap(operands, |a: f32| {
+            Ok(truncate(f64::from(a), -2147483648.0, 2147483648.0)? as i32)
+        }),
+        Instruction::I32TruncUF32 => unary_trap(operands, |a: f32| {
+            Ok(truncate(f64::from(a), 0.0, 4294967296.0)? as u32 as i32)
+        }),
+        Instruction::I32TruncSF64 => unary_trap(operands, |a: f64| {
+            Ok(truncate(a, -2147483648.0, 2147483648.0)? as i32)
+        }),
+        Instruction::I32TruncUF64 => unary_trap(operands, |a: f64| {
+            Ok(truncate(a, 0.0, 4294967296.0)? as u32 as i32)
+        }),
+        Instruction::I64ExtendSI32 => unary(operands, |a: i32| i64::from(a)),
+        Instruction::I64ExtendUI32 => unary(operands, |a: i32| i64::from(a as u32)),
+        Instruction::I64TruncSF32 => unary_trap(operands, |a: f32| {
+            Ok(truncate(f64::from(a), -9223372036854775808.0, 9223372036854775808.0)? as i64)
+        }),
+        Instruction::I64TruncUF32 => unary_trap(operands, |a: f32| {
+            Ok(truncate(f64::from(a), 0.0, 18446744073709551616.0)? as u64 as i64)
+        }),
+        Instruction::I64TruncSF64 => unary_trap(operands, |a: f64| {
+            Ok(truncate(a, -9223372036854775808.0, 9223372036854775808.0)? as i64)
+        }),
+        Instruction::I64TruncUF64 => unary_trap(operands, |a: f64| {
+            Ok(truncate(a, 0.0, 18446744073709551616.0)? as u64 as i64)
+        }),
+        Instruction::F32ConvertSI32 => unary(operands, |a: i32| a as f32),
+        Instruction::F32ConvertUI32 => unary(operands, |a: i32| a as u32 as f32),
+        Instruction::F32ConvertSI64 => unary(operands, |a: i64| a as f32),
+        Instruction::F32ConvertUI64 => unary(operands, |a: i64| a as u64 as f32),
+        Instruction::F32DemoteF64 => unary(operands, |a: f64| a as f32),
+        Instruction::F64ConvertSI32 => unary(operands, |a: i32| f64::from(a)),
+        Instruction::F64ConvertUI32 => unary(operands, |a: i32| f64::from(a as u32)),
+        Instruction::F64ConvertSI64 => unary(operands, |a: i64| a as f64),
+        Instruction::F64ConvertUI64 => unary(operands, |a: i64| a as u64 as f64),
+        Instruction::F64PromoteF32 => unary(operands, |a: f32| f64::from(a)),
+        Instruction::I32ReinterpretF32 => unary(operands, |a: f32| a.to_bits() as i32),
+        Instruction::I64ReinterpretF64 => unary(operands, |a: f64| a.to_bits() as i64),
+        Instruction::F32ReinterpretI32 => unary(operands, |a: i32| f32::from_bits(a as u32)),
+        Instruction::F64ReinterpretI64 => unary(operands, |a: i64| f64::from_bits(a as u64)),
+        Instruction::I32Extend8S => unary(operands, |a: i32| i32::from(a as i8)),
+        Instruction::I32Extend16S => unary(operands, |a: i32| i32::from(a as i16)),
+        Instruction::I64Extend8S => unary(operands, |a: i64| i64::from(a as i8)),
+        Instruction::I64Extend16S => unary(operands, |a: i64| i64::from(a as i16)),
+        Instruction::I64Extend32S => unary(operands, |a: i64| i64::from(a as i32)),
+
+        // Rust casts from floats to integers saturate, and convert NaN to 0.
+        Instruction::I32TruncSatSF32 => unary(operands, |a: f32| a as i32),
+        Instruction::I32TruncSatUF32 => unary(operands, |a: f32| a as u32 as i32),
+        Instruction::I32TruncSatSF64 => unary(operands, |a: f64| a as i32),
+        Instruction::I32TruncSatUF64 => unary(operands, |a: f64| a as u32 as i32),
+        Instruction::I64TruncSatSF32 => unary(operands, |a: f32| a as i64),
+        Instruction::I64TruncSatUF32 => unary(operands, |a: f32| a as u64 as i64),
+        Instruction::I64TruncSatSF64 => unary(operands, |a: f64| a as i64),
+        Instruction::I64TruncSatUF64 => unary(operands, |a: f64| a as u64 as i64),
+
+        _ => Err(Trap::from_string(format!(
+            "Unsupported numeric instruction {:?}",
+            instruction
+        ))),
+    }
+}
+
+fn get_operand<T: FromValue>(operand: Value) -> ExecutionResult<T> {
+    T::from_value(operand).ok_or(Trap::from("Mismatching operand type"))
+}
+
+fn unary<T: FromValue, R: Into<Value>>(
+    operands: &[Value],
+    operation: fn(T) -> R,
+) -> ExecutionResult<Value> {
+    unary_trap(operands, |operand| Ok(operation(operand)))
+}
+
+fn unary_trap<T: FromValue, R: Into<Value>>(
+    operands: &[Value],
+    operation: impl Fn(T) -> ExecutionResult<R>,
+) -> ExecutionResult<Value> {
+    match operands {
+        [operand] => Ok(operation(get_operand(*operand)?)?.into()),
+        _ => Err(Trap::from("Invalid operand count")),
+    }
+}
+
+fn binary<T: FromValue, R: Into<Value>>(
+    operands: &[Value],
+    operation: fn(T, T) -> R,
+) -> ExecutionResult<Value> {
+    binary_trap(operands, |lhs, rhs| Ok(operation(lhs, rhs)))
+}
+
+fn binary_trap<T: FromValue, R: Into<Value>>(
+    operands: &[Value],
+    operation: impl Fn(T, T) -> ExecutionResult<R>,
+) -> ExecutionResult<Value> {
+    match operands {
+        [lhs, rhs] => Ok(operation(get_operand(*lhs)?, get_operand(*rhs)?)?.into()),
+        _ => Err(Trap::from("Invalid operand count")),
+    }
+}
+
+// Rounding functions of the host may return signaling NaNs unchanged, while NaN results have to
+// be quiet, keeping the payload of the operand.
+//
+// https://webassembly.github.io/spec/core/exec/numerics.html#aux-nans
+pub fn quiet_f32(value: f32) -> f32 {
+    if value.is_nan() {
+        f32::from_bits(value.to_bits() | 0x0040_0000)
+    } else {
+        value
+    }
+}
+
+pub fn quiet_f64(value: f64) -> f64 {
+    if value.is_nan() {
+        f64::from_bits(value.to_bits() | 0x0008_0000_0000_0000)
+    } else {
+        value
+    }
+}
+
+// NaN operands are propagated, and zeros of opposite signs are ordered, -0 being the smallest.
+//
+// https://webassembly.github.io/spec/core/exec/numerics.html#op-fmin
+pub fn min_f32(a: f32, b: f32) -> f32 {
+    if a.is_nan() || b.is_nan() {
+        a + b
+    } else if a == b {
+        f32::from_bits(a.to_bits() | b.to_bits())
+    } else {
+        a.min(b)
+    }
+}
+
+// https://webassembly.github.io/spec/core/exec/numerics.html#op-fmax
+pub fn max_f32(a: f32, b: f32) -> f32 {
+    if a.is_nan() || b.is_nan() {
+        a + b
+    } else if a == b {
+        f32::from_bits(a.to_bits() & b.to_bits())
+    } else {
+        a.max(b)
+    }
+}
+
+pub fn min_f64(a: f64, b: f64) -> f64 {
+    if a.is_nan() || b.is_nan() {
+        a + b
+    } else if a == b {
+        f64::from_bits(a.to_bits() | b.to_bits())
+    } else {
+        a.min(b)
+    }
+}
+
+pub fn max_f64(a: f64, b: f64) -> f64 {
+    if a.is_nan() || b.is_nan() {
+        a + b
+    } else if a == b {
+        f64::from_bits(a.to_bits() & b.to_bits())
+    } else {
+        a.max(b)
+    }
+}
+
+// Truncates a float towards zero, the result having to be within `[min, max)`.
+//
+// https://webassembly.github.io/spec/core/exec/numerics.html#op-trunc-s
+fn truncate(value: f64, min: f64, max: f64) -> ExecutionResult<f64> {
+    if value.is_nan() {
+        return Err(Trap::new(TrapKind::InvalidConversionToInteger));
+    }
+
+    let truncated = value.trunc();
+    if truncated < min || truncated >= max {
+        return Err(Trap::new(TrapKind::IntegerOverflow));
+    }
+
+    Ok(truncated)
+}
@@ -0,0 +1,400 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::rc::Rc;
+use std::time::Instant;
+
+use crate::execution::host::HostFunction;
+use crate::execution::instance::ModuleInstance;
+use crate::execution::interpreter::Interpreter;
+use crate::execution::trap::{ExecutionResult, Trap, TrapKind};
+use crate::execution::values::{Reference, Value};
+use crate::structure::*;
+use crate::validation::types::roll_recursive_type;
+
+pub const PAGE_SIZE: u64 = 65536;
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#addresses
+pub type TypeAddress = usize;
+pub type FunctionAddress = usize;
+pub type TableAddress = usize;
+pub type MemoryAddress = usize;
+pub type GlobalAddress = usize;
+pub type TagAddress = usize;
+pub type StructAddress = usize;
+pub type ArrayAddress = usize;
+pub type ExceptionAddress = usize;
+pub type ElementAddress = usize;
+pub type DataAddress = usize;
+pub type ModuleAddress = usize;
+
+/// https://webassembly.github.io/gc/core/valid/conventions.html#defined-types
+///
+/// Types are allocated once per distinct recursion group, so that the equivalent types of
+/// different modules share the same address. Only the declared supertype is kept, to match types
+/// at runtime.
+#[derive(Debug)]
+pub struct TypeInstance {
+    pub supertype: Option<TypeAddress>,
+}
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#function-instances
+///
+/// The code of module functions is shared with the interpreter, which keeps it alive while the
+/// function executes.
+#[derive(Debug)]
+pub enum FunctionInstance {
+    Module {
+        function_type: FunctionType,
+        type_address: TypeAddress,
+        module: ModuleAddress,
+        code: Rc<Function>,
+    },
+    Host {
+        function_type: FunctionType,
+        type_address: TypeAddress,
+        host_function: HostFunction,
+    },
+}
+
+impl FunctionInstance {
+    pub fn function_type(&self) -> &FunctionType {
+        match self {
+            FunctionInstance::Module { function_type, .. } => function_type,
+            FunctionInstance::Host { function_type, .. } => function_type,
+        }
+    }
+
+    pub fn type_address(&self) -> TypeAddress {
+        match self {
+            FunctionInstance::Module { type_address, .. } => *type_address,
+            FunctionInstance::Host { type_address, .. } => *type_address,
+        }
+    }
+}
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#table-instances
+#[derive(Debug)]
+pub struct TableInstance {
+    pub element_type: RefType,
+    pub elements: Vec<Reference>,
+    pub max: Option<u64>,
+}
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#memory-instances
+#[derive(Debug)]
+pub struct MemoryInstance {
+    pub data: Vec<u8>,
+    pub max: Option<u64>,
+    pub address_type: AddressType,
+    pub shared: bool,
+}
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#global-instances
+#[derive(Debug)]
+pub struct GlobalInstance {
+    pub value_type: ValueType,
+    pub mutability: GlobalTypeMutability,
+    pub value: Value,
+}
+
+/// https://webassembly.github.io/exception-handling/core/exec/runtime.html#tag-instances
+#[derive(Debug)]
+pub struct TagInstance {
+    pub tag_type: FunctionType,
+    pub type_address: TypeAddress,
+}
+
+/// https://webassembly.github.io/gc/core/exec/runtime.html#structure-instances
+#[derive(Debug)]
+pub struct StructInstance {
+    pub type_address: TypeAddress,
+    pub fields: Vec<Value>,
+}
+
+/// https://webassembly.github.io/gc/core/exec/runtime.html#array-instances
+#[derive(Debug)]
+pub struct ArrayInstance {
+    pub type_address: TypeAddress,
+    pub elements: Vec<Value>,
+}
+
+/// https://webassembly.github.io/exception-handling/core/exec/runtime.html#exception-instances
+#[derive(Debug)]
+pub struct ExceptionInstance {
+    pub tag: TagAddress,
+    pub fields: Vec<Value>,
+}
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#store
+///
+/// The store holds the runtime state of every instantiated module, the instances being
+/// referenced by their address. Element and data instances are emptied when dropped.
+#[derive(Debug, Default)]
+pub struct Store {
+    pub types: Vec<TypeInstance>,
+    /// The distinct recursion groups, rolled with the addresses of the types they reference, and
+    /// the address of their first type.
+    pub type_groups: HashMap<RecursiveType, TypeAddress>,
+    pub functions: Vec<FunctionInstance>,
+    pub tables: Vec<TableInstance>,
+    pub memories: Vec<MemoryInstance>,
+    pub globals: Vec<GlobalInstance>,
+    pub tags: Vec<TagInstance>,
+    pub structs: Vec<StructInstance>,
+    pub arrays: Vec<ArrayInstance>,
+    pub exceptions: Vec<ExceptionInstance>,
+    pub elements: Vec<Vec<Reference>>,
+    pub data: Vec<Vec<u8>>,
+    pub modules: Vec<ModuleInstance>,
+    /// Executions running past the deadline trap, which bounds the time spent in code that
+    /// doesn't terminate.
+    pub deadline: Option<Instant>,
+}
+
+impl Store {
+    pub fn new() -> Store {
+        Store::default()
+    }
+
+    // https://webassembly.github.io/spec/core/exec/modules.html#invocation
+    pub fn invoke(
+        &mut self,
+        function_address: FunctionAddress,
+        arguments: &[Value],
+    ) -> ExecutionResult<Vec<Value>> {
+        check_arguments(self.functions[function_address].function_type(), arguments)?;
+
+        Interpreter::new(self).invoke(function_address, arguments)
+    }
+
+    // Returns the addresses of the types of a module, allocating the recursion groups that aren't
+    // in the store yet.
+    //
+    // https://webassembly.github.io/gc/core/exec/modules.html#alloc-module
+    pub fn allocate_types(&mut self, recursive_types: &[RecursiveType]) -> Vec<TypeAddress> {
+        let mut type_addresses: Vec<TypeAddress> = Vec::new();
+
+        for recursive_type in recursive_types {
+            let first_type_index = type_addresses.len() as u32;
+            let map_index = |type_index: u32| {
+                type_addresses
+                    .get(type_index as usize)
+                    .map_or(type_index, |type_address| *type_address as u32)
+            };
+            let group = roll_recursive_type(recursive_type, first_type_index, &map_index);
+
+            let first_type_address = match self.type_groups.get(&group) {
+                Some(first_type_address) => *first_type_address,
+                None => {
+                    let first_type_address = self.types.len();
+                    for sub_type in recursive_type {
+                        let supertype =
+                            sub_type.supertypes.first().map(|supertype| {
+                                match supertype.checked_sub(first_type_index) {
+                                    Some(offset) => first_type_address + offset as usize,
+                                    None => type_addresses[*supertype as usize],
+                                }
+                            });
+                        self.types.push(TypeInstance { supertype });
+                    }
+
+                    self.type_groups.insert(group, first_type_address);
+                    first_type_address
+                }
+            };
+
+            type_addresses.extend(first_type_address..first_type_address + recursive_type.len());
+        }
+
+        type_addresses
+    }
+
+    // A type matches another one when they are the same, or when its declared supertype
+    // transitively matches it. Supertypes are allocated before their sub types, which guarantees
+    // termination.
+    //
+    // https://webassembly.github.io/gc/core/valid/matching.html#heap-types
+    pub fn match_type(&self, actual: TypeAddress, expected: TypeAddress) -> bool {
+        let mut current = Some(actual);
+        while let Some(type_address) = current {
+            if type_address == expected {
+                return true;
+            }
+            current = self.types[type_address].supertype;
+        }
+
+        false
+    }
+
+    // Host function types can't refer to other types, so each one is allocated as a final type
+    // in its own recursion group.
+    //
+    // https://webassembly.github.io/spec/core/appendix/embedding.html#func-alloc
+    pub fn allocate_host_function(
+        &mut self,
+        function_type: FunctionType,
+        host_function: HostFunction,
+    ) -> FunctionAddress {
+        let sub_type = SubType {
+            is_final: true,
+            supertypes: Vec::new(),
+            composite_type: CompositeType::Function(function_type.clone()),
+        };
+        let type_address = self.allocate_types(&[vec![sub_type]])[0];
+
+        self.functions.push(FunctionInstance::Host {
+            function_type,
+            type_address,
+            host_function,
+        });
+        self.functions.len() - 1
+    }
+
+    // https://webassembly.github.io/spec/core/appendix/embedding.html#table-alloc
+    //
+    // Fails when the initial elements can't be allocated on the host.
+    pub fn allocate_table(
+        &mut self,
+        table_type: &TableType,
+        init: Reference,
+    ) -> Option<TableAddress> {
+        let size = usize::try_from(table_type.limits.min).ok()?;
+        let mut elements = Vec::new();
+        elements.try_reserve_exact(size).ok()?;
+        elements.resize(size, init);
+
+        self.tables.push(TableInstance {
+            element_type: table_type.element_type,
+            elements,
+            max: table_type.limits.max,
+        });
+        Some(self.tables.len() - 1)
+    }
+
+    // https://webassembly.github.io/spec/core/appendix/embedding.html#mem-alloc
+    //
+    // Fails when the initial size exceeds the memory limit, or can't be allocated on the host.
+    pub fn allocate_memory(&mut self, memory_type: &MemoryType) -> Option<MemoryAddress> {
+        let address_type = memory_type.address_type;
+        let size = memory_type
+            .limits
+            .min
+            .checked_mul(PAGE_SIZE)
+            .filter(|size| *size <= memory_limit(address_type) * PAGE_SIZE)?;
+
+        let mut data = Vec::new();
+        data.try_reserve_exact(size as usize).ok()?;
+        data.resize(size as usize, 0);
+
+        self.memories.push(MemoryInstance {
+            data,
+            max: memory_type.limits.max,
+            address_type,
+            shared: memory_type.shared,
+        });
+        Some(self.memories.len() - 1)
+    }
+
+    // https://webassembly.github.io/spec/core/appendix/embedding.html#global-alloc
+    pub fn allocate_global(&mut self, global_type: &GlobalType, value: Value) -> GlobalAddress {
+        self.globals.push(GlobalInstance {
+            value_type: global_type.value_type,
+            mutability: global_type.mutability,
+            value,
+        });
+        self.globals.len() - 1
+    }
+
+    // https://webassembly.github.io/gc/core/exec/modules.html#alloc-struct
+    pub fn allocate_struct(&mut self, type_address: TypeAddress, fields: Vec<Value>) -> Reference {
+        self.structs.push(StructInstance {
+            type_address,
+            fields,
+        });
+        Reference::Struct((self.structs.len() - 1) as u32)
+    }
+
+    // https://webassembly.github.io/gc/core/exec/modules.html#alloc-array
+    pub fn allocate_array(&mut self, type_address: TypeAddress, elements: Vec<Value>) -> Reference {
+        self.arrays.push(ArrayInstance {
+            type_address,
+            elements,
+        });
+        Reference::Array((self.arrays.len() - 1) as u32)
+    }
+
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-table-init
+    pub fn table_init(
+        &mut self,
+        table_address: TableAddress,
+        element_address: ElementAddress,
+        destination: u64,
+        source: u64,
+        length: u64,
+    ) -> ExecutionResult<()> {
+        let element = &self.elements[element_address];
+        let table = &mut self.tables[table_address];
+
+        let source_range = get_range(source, length, element.len())
+            .ok_or(Trap::new(TrapKind::OutOfBoundsTableAccess))?;
+        let destination_range = get_range(destination, length, table.elements.len())
+            .ok_or(Trap::new(TrapKind::OutOfBoundsTableAccess))?;
+
+        table.elements[destination_range].copy_from_slice(&element[source_range]);
+        Ok(())
+    }
+
+    // https://webassembly.github.io/spec/core/exec/instructions.html#exec-memory-init
+    pub fn memory_init(
+        &mut self,
+        memory_address: MemoryAddress,
+        data_address: DataAddress,
+        destination: u64,
+        source: u64,
+        length: u64,
+    ) -> ExecutionResult<()> {
+        let data = &self.data[data_address];
+        let memory = &mut self.memories[memory_address];
+
+        let source_range = get_range(source, length, data.len())
+            .ok_or(Trap::new(TrapKind::OutOfBoundsMemoryAccess))?;
+        let destination_range = get_range(destination, length, memory.data.len())
+            .ok_or(Trap::new(TrapKind::OutOfBoundsMemoryAccess))?;
+
+        memory.data[destination_range].copy_from_slice(&data[source_range]);
+        Ok(())
+    }
+}
+
+pub fn check_arguments(function_type: &FunctionType, arguments: &[Value]) -> ExecutionResult<()> {
+    let (params, _) = function_type;
+    let is_matching = params.len() == arguments.len()
+        && arguments
+            .iter()
+            .zip(params)
+            .all(|(argument, param)| argument.has_type(param));
+    if !is_matching {
+        return Err(Trap::from("Mismatching function arguments"));
+    }
+
+    Ok(())
+}
+
+// Memories are limited to 4GiB with 32 bits addresses, while 64 bits memories are limited to the
+// pages that can be addressed on the host.
+pub fn memory_limit(address_type: AddressType) -> u64 {
+    match address_type {
+        AddressType::I32 => 1 << 16,
+        AddressType::I64 => (usize::MAX as u64) / PAGE_SIZE,
+    }
+}
+
+// Returns the range of `length` items starting at `start`, if it fits within `size` items.
+pub fn get_range(start: u64, length: u64, size: usize) -> Option<std::ops::Range<usize>> {
+    let end = start.checked_add(length)?;
+    if end > size as u64 {
+        return None;
+    }
+
+    Some(start as usize..end as usize)
+}
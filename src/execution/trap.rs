@@ -0,0 +1,91 @@
+/// https://webassembly.github.io/spec/core/exec/runtime.html#administrative-instructions
+///
+/// Kinds of the traps raised by the execution semantics, other traps being raised by the host or
+/// on invalid use of the embedding interface.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TrapKind {
+    Unreachable,
+    IntegerDivideByZero,
+    IntegerOverflow,
+    InvalidConversionToInteger,
+    OutOfBoundsMemoryAccess,
+    OutOfBoundsTableAccess,
+    UndefinedElement,
+    UninitializedElement,
+    IndirectCallTypeMismatch,
+    NullFunctionReference,
+    NullReference,
+    NullExceptionReference,
+    NullStructureReference,
+    NullArrayReference,
+    NullI31Reference,
+    OutOfBoundsArrayAccess,
+    CastFailure,
+    CallStackExhausted,
+    UnalignedAtomic,
+    ExpectedSharedMemory,
+    UncaughtException,
+    Other,
+}
+
+impl TrapKind {
+    // Messages used by the reference interpreter, which the spec tests expect.
+    pub fn message(&self) -> &'static str {
+        match self {
+            TrapKind::Unreachable => "unreachable",
+            TrapKind::IntegerDivideByZero => "integer divide by zero",
+            TrapKind::IntegerOverflow => "integer overflow",
+            TrapKind::InvalidConversionToInteger => "invalid conversion to integer",
+            TrapKind::OutOfBoundsMemoryAccess => "out of bounds memory access",
+            TrapKind::OutOfBoundsTableAccess => "out of bounds table access",
+            TrapKind::UndefinedElement => "undefined element",
+            TrapKind::UninitializedElement => "uninitialized element",
+            TrapKind::IndirectCallTypeMismatch => "indirect call type mismatch",
+            TrapKind::NullFunctionReference => "null function reference",
+            TrapKind::NullReference => "null reference",
+            TrapKind::NullExceptionReference => "null exception reference",
+            TrapKind::NullStructureReference => "null structure reference",
+            TrapKind::NullArrayReference => "null array reference",
+            TrapKind::NullI31Reference => "null i31 reference",
+            TrapKind::OutOfBoundsArrayAccess => "out of bounds array access",
+            TrapKind::CastFailure => "cast failure",
+            TrapKind::CallStackExhausted => "call stack exhausted",
+            TrapKind::UnalignedAtomic => "unaligned atomic",
+            TrapKind::ExpectedSharedMemory => "expected shared memory",
+            TrapKind::UncaughtException => "uncaught exception",
+            TrapKind::Other => "trap",
+        }
+    }
+}
+
+/// Traps abort the execution and can't be handled by WebAssembly code.
+#[derive(Debug)]
+pub struct Trap {
+    pub kind: TrapKind,
+    pub message: String,
+}
+
+impl Trap {
+    pub fn new(kind: TrapKind) -> Trap {
+        Trap {
+            kind,
+            message: String::from(kind.message()),
+        }
+    }
+
+    pub fn from(message: &str) -> Trap {
+        Trap {
+            kind: TrapKind::Other,
+            message: String::from(message),
+        }
+    }
+
+    pub fn from_string(message: String) -> Trap {
+        Trap {
+            kind: TrapKind::Other,
+            message,
+        }
+    }
+}
+
+pub type ExecutionResult<T> = Result<T, Trap>;
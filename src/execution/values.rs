@@ -0,0 +1,203 @@
+use crate::structure::*;
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#values
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Value {
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    V128(u128),
+    Ref(Reference),
+}
+
+/// https://webassembly.github.io/spec/core/exec/runtime.html#values
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Reference {
+    Null(HeapType),
+    Function(u32),
+    Extern(u32),
+    I31(u32),
+    Struct(u32),
+    Array(u32),
+    Exception(u32),
+}
+
+impl Value {
+    // Non-nullable reference types have no default value.
+    //
+    // https://webassembly.github.io/function-references/core/exec/runtime.html#default-val
+    pub fn default(value_type: ValueType) -> Option<Value> {
+        match value_type {
+            ValueType::I32 => Some(Value::I32(0)),
+            ValueType::I64 => Some(Value::I64(0)),
+            ValueType::F32 => Some(Value::F32(0.0)),
+            ValueType::F64 => Some(Value::F64(0.0)),
+            ValueType::V128 => Some(Value::V128(0)),
+            ValueType::Ref(ref_type) if ref_type.nullable => {
+                Some(Value::Ref(Reference::Null(ref_type.heap_type)))
+            }
+            ValueType::Ref(_) => None,
+        }
+    }
+
+    // Whether the value can be stored in a local, global or table of the given type. References
+    // keep their representation when converted between the any and extern hierarchies, so host
+    // references are also of the any type, and the other ones of the extern type.
+    pub fn has_type(&self, value_type: &ValueType) -> bool {
+        match (self, value_type) {
+            (Value::I32(_), ValueType::I32)
+            | (Value::I64(_), ValueType::I64)
+            | (Value::F32(_), ValueType::F32)
+            | (Value::F64(_), ValueType::F64)
+            | (Value::V128(_), ValueType::V128) => true,
+            (Value::Ref(Reference::Null(_)), ValueType::Ref(ref_type)) => ref_type.nullable,
+            (Value::Ref(Reference::Function(_)), ValueType::Ref(ref_type)) => {
+                matches!(ref_type.heap_type, HeapType::Func | HeapType::Index(_))
+            }
+            (Value::Ref(Reference::Extern(_)), ValueType::Ref(ref_type)) => {
+                matches!(ref_type.heap_type, HeapType::Extern | HeapType::Any)
+            }
+            (Value::Ref(Reference::I31(_)), ValueType::Ref(ref_type)) => matches!(
+                ref_type.heap_type,
+                HeapType::Any | HeapType::Eq | HeapType::I31 | HeapType::Extern
+            ),
+            (Value::Ref(Reference::Struct(_)), ValueType::Ref(ref_type)) => matches!(
+                ref_type.heap_type,
+                HeapType::Any
+                    | HeapType::Eq
+                    | HeapType::Struct
+                    | HeapType::Index(_)
+                    | HeapType::Extern
+            ),
+            (Value::Ref(Reference::Array(_)), ValueType::Ref(ref_type)) => matches!(
+                ref_type.heap_type,
+                HeapType::Any
+                    | HeapType::Eq
+                    | HeapType::Array
+                    | HeapType::Index(_)
+                    | HeapType::Extern
+            ),
+            (Value::Ref(Reference::Exception(_)), ValueType::Ref(ref_type)) => {
+                ref_type.heap_type == HeapType::Exn
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Reference {
+    // Null references of the any and extern hierarchies are converted to the bottom type of the
+    // other one, while the other references are kept.
+    //
+    // https://webassembly.github.io/gc/core/exec/instructions.html#exec-any-convert-extern
+    pub fn convert_any(self) -> Reference {
+        match self {
+            Reference::Null(_) => Reference::Null(HeapType::None),
+            reference => reference,
+        }
+    }
+
+    // https://webassembly.github.io/gc/core/exec/instructions.html#exec-extern-convert-any
+    pub fn convert_extern(self) -> Reference {
+        match self {
+            Reference::Null(_) => Reference::Null(HeapType::NoExtern),
+            reference => reference,
+        }
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Value {
+        Value::I32(value)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Value {
+        Value::I64(value)
+    }
+}
+
+impl From<f32> for Value {
+    fn from(value: f32) -> Value {
+        Value::F32(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Value {
+        Value::F64(value)
+    }
+}
+
+impl From<u128> for Value {
+    fn from(value: u128) -> Value {
+        Value::V128(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Value {
+        Value::I32(value as i32)
+    }
+}
+
+/// Extracts the raw value of a given type, operands having been checked by the validation.
+pub trait FromValue: Sized {
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+impl FromValue for i32 {
+    fn from_value(value: Value) -> Option<i32> {
+        match value {
+            Value::I32(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: Value) -> Option<i64> {
+        match value {
+            Value::I64(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for f32 {
+    fn from_value(value: Value) -> Option<f32> {
+        match value {
+            Value::F32(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for f64 {
+    fn from_value(value: Value) -> Option<f64> {
+        match value {
+            Value::F64(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for u128 {
+    fn from_value(value: Value) -> Option<u128> {
+        match value {
+            Value::V128(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl FromValue for Reference {
+    fn from_value(value: Value) -> Option<Reference> {
+        match value {
+            Value::Ref(reference) => Some(reference),
+            _ => None,
+        }
+    }
+}
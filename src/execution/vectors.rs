@@ -0,0 +1,886 @@
+use std::convert::TryInto;
+
+use crate::execution::numerics::{max_f32, max_f64, min_f32, min_f64, quiet_f32, quiet_f64};
+use crate::execution::trap::{ExecutionResult, Trap};
+use crate::execution::values::{FromValue, Value};
+use crate::structure::Instruction;
+
+/// https://webassembly.github.io/spec/core/exec/numerics.html#vector-operations
+///
+/// Returns the number of operands popped by a vector instruction, or `None` for the other
+/// instructions. Memory accesses aside, vector instructions push a single result.
+pub fn get_operand_count(instruction: &Instruction) -> Option<usize> {
+    match instruction {
+        Instruction::I8x16Splat
+        | Instruction::I16x8Splat
+        | Instruction::I32x4Splat
+        | Instruction::I64x2Splat
+        | Instruction::F32x4Splat
+        | Instruction::F64x2Splat
+        | Instruction::I8x16ExtractLaneS(_)
+        | Instruction::I8x16ExtractLaneU(_)
+        | Instruction::I16x8ExtractLaneS(_)
+        | Instruction::I16x8ExtractLaneU(_)
+        | Instruction::I32x4ExtractLane(_)
+        | Instruction::I64x2ExtractLane(_)
+        | Instruction::F32x4ExtractLane(_)
+        | Instruction::F64x2ExtractLane(_)
+        | Instruction::V128Not
+        | Instruction::V128AnyTrue
+        | Instruction::F32x4DemoteF64x2Zero
+        | Instruction::F64x2PromoteLowF32x4
+        | Instruction::I8x16Abs
+        | Instruction::I8x16Neg
+        | Instruction::I8x16Popcnt
+        | Instruction::I8x16AllTrue
+        | Instruction::I8x16Bitmask
+        | Instruction::F32x4Ceil
+        | Instruction::F32x4Floor
+        | Instruction::F32x4Trunc
+        | Instruction::F32x4Nearest
+        | Instruction::F64x2Ceil
+        | Instruction::F64x2Floor
+        | Instruction::F64x2Trunc
+        | Instruction::F64x2Nearest
+        | Instruction::I16x8ExtAddPairwiseI8x16S
+        | Instruction::I16x8ExtAddPairwiseI8x16U
+        | Instruction::I32x4ExtAddPairwiseI16x8S
+        | Instruction::I32x4ExtAddPairwiseI16x8U
+        | Instruction::I16x8Abs
+        | Instruction::I16x8Neg
+        | Instruction::I16x8AllTrue
+        | Instruction::I16x8Bitmask
+        | Instruction::I16x8ExtendLowI8x16S
+        | Instruction::I16x8ExtendHighI8x16S
+        | Instruction::I16x8ExtendLowI8x16U
+        | Instruction::I16x8ExtendHighI8x16U
+        | Instruction::I32x4Abs
+        | Instruction::I32x4Neg
+        | Instruction::I32x4AllTrue
+        | Instruction::I32x4Bitmask
+        | Instruction::I32x4ExtendLowI16x8S
+        | Instruction::I32x4ExtendHighI16x8S
+        | Instruction::I32x4ExtendLowI16x8U
+        | Instruction::I32x4ExtendHighI16x8U
+        | Instruction::I64x2Abs
+        | Instruction::I64x2Neg
+        | Instruction::I64x2AllTrue
+        | Instruction::I64x2Bitmask
+        | Instruction::I64x2ExtendLowI32x4S
+        | Instruction::I64x2ExtendHighI32x4S
+        | Instruction::I64x2ExtendLowI32x4U
+        | Instruction::I64x2ExtendHighI32x4U
+        | Instruction::F32x4Abs
+        | Instruction::F32x4Neg
+        | Instruction::F32x4Sqrt
+        | Instruction::F64x2Abs
+        | Instruction::F64x2Neg
+        | Instruction::F64x2Sqrt
+        | Instruction::I32x4TruncSatF32x4S
+        | Instruction::I32x4TruncSatF32x4U
+        | Instruction::F32x4ConvertI32x4S
+        | Instruction::F32x4ConvertI32x4U
+        | Instruction::I32x4TruncSatF64x2SZero
+        | Instruction::I32x4TruncSatF64x2UZero
+        | Instruction::F64x2ConvertLowI32x4S
+        | Instruction::F64x2ConvertLowI32x4U
+        | Instruction::I32x4RelaxedTruncF32x4S
+        | Instruction::I32x4RelaxedTruncF32x4U
+        | Instruction::I32x4RelaxedTruncF64x2SZero
+        | Instruction::I32x4RelaxedTruncF64x2UZero => Some(1),
+
+        Instruction::I8x16Shuffle(_)
+        | Instruction::I8x16Swizzle
+        | Instruction::I8x16ReplaceLane(_)
+        | Instruction::I16x8ReplaceLane(_)
+        | Instruction::I32x4ReplaceLane(_)
+        | Instruction::I64x2ReplaceLane(_)
+        | Instruction::F32x4ReplaceLane(_)
+        | Instruction::F64x2ReplaceLane(_)
+        | Instruction::I8x16Eq
+        | Instruction::I8x16Ne
+        | Instruction::I8x16LtS
+        | Instruction::I8x16LtU
+        | Instruction::I8x16GtS
+        | Instruction::I8x16GtU
+        | Instruction::I8x16LeS
+        | Instruction::I8x16LeU
+        | Instruction::I8x16GeS
+        | Instruction::I8x16GeU
+        | Instruction::I16x8Eq
+        | Instruction::I16x8Ne
+        | Instruction::I16x8LtS
+        | Instruction::I16x8LtU
+        | Instruction::I16x8GtS
+        | Instruction::I16x8GtU
+        | Instruction::I16x8LeS
+        | Instruction::I16x8LeU
+        | Instruction::I16x8GeS
+        | Instruction::I16x8GeU
+        | Instruction::I32x4Eq
+        | Instruction::I32x4Ne
+        | Instruction::I32x4LtS
+        | Instruction::I32x4LtU
+        | Instruction::I32x4GtS
+        | Instruction::I32x4GtU
+        | Instruction::I32x4LeS
+        | Instruction::I32x4LeU
+        | Instruction::I32x4GeS
+        | Instruction::I32x4GeU
+        | Instruction::I64x2Eq
+        | Instruction::I64x2Ne
+        | Instruction::I64x2LtS
+        | Instruction::I64x2GtS
+        | Instruction::I64x2LeS
+        | Instruction::I64x2GeS
+        | Instruction::F32x4Eq
+        | Instruction::F32x4Ne
+        | Instruction::F32x4Lt
+        | Instruction::F32x4Gt
+        | Instruction::F32x4Le
+        | Instruction::F32x4Ge
+        | Instruction::F64x2Eq
+        | Instruction::F64x2Ne
+        | Instruction::F64x2Lt
+        | Instruction::F64x2Gt
+        | Instruction::F64x2Le
+        | Instruction::F64x2Ge
+        | Instruction::V128And
+        | Instruction::V128AndNot
+        | Instruction::V128Or
+        | Instruction::V128Xor
+        | Instruction::I8x16NarrowI16x8S
+        | Instruction::I8x16NarrowI16x8U
+        | Instruction::I8x16Shl
+        | Instruction::I8x16ShrS
+        | Instruction::I8x16ShrU
+        | Instruction::I8x16Add
+        | Instruction::I8x16AddSatS
+        | Instruction::I8x16AddSatU
+        | Instruction::I8x16Sub
+        | Instruction::I8x16SubSatS
+        | Instruction::I8x16SubSatU
+        | Instruction::I8x16MinS
+        | Instruction::I8x16MinU
+        | Instruction::I8x16MaxS
+        | Instruction::I8x16MaxU
+        | Instruction::I8x16AvgrU
+        | Instruction::I16x8Q15MulrSatS
+        | Instruction::I16x8NarrowI32x4S
+        | Instruction::I16x8NarrowI32x4U
+        | Instruction::I16x8Shl
+        | Instruction::I16x8ShrS
+        | Instruction::I16x8ShrU
+        | Instruction::I16x8Add
+        | Instruction::I16x8AddSatS
+        | Instruction::I16x8AddSatU
+        | Instruction::I16x8Sub
+        | Instruction::I16x8SubSatS
+        | Instruction::I16x8SubSatU
+        | Instruction::I16x8Mul
+        | Instruction::I16x8MinS
+        | Instruction::I16x8MinU
+        | Instruction::I16x8MaxS
+        | Instruction::I16x8MaxU
+        | Instruction::I16x8AvgrU
+        | Instruction::I16x8ExtMulLowI8x16S
+        | Instruction::I16x8ExtMulHighI8x16S
+        | Instruction::I16x8ExtMulLowI8x16U
+        | Instruction::I16x8ExtMulHighI8x16U
+        | Instruction::I32x4Shl
+        | Instruction::I32x4ShrS
+        | Instruction::I32x4ShrU
+        | Instruction::I32x4Add
+        | Instruction::I32x4Sub
+        | Instruction::I32x4Mul
+        | Instruction::I32x4MinS
+        | Instruction::I32x4MinU
+        | Instruction::I32x4MaxS
+        | Instruction::I32x4MaxU
+        | Instruction::I32x4DotI16x8S
+        | Instruction::I32x4ExtMulLowI16x8S
+        | Instruction::I32x4ExtMulHighI16x8S
+        | Instruction::I32x4ExtMulLowI16x8U
+        | Instruction::I32x4ExtMulHighI16x8U
+        | Instruction::I64x2Shl
+        | Instruction::I64x2ShrS
+        | Instruction::I64x2ShrU
+        | Instruction::I64x2Add
+        | Instruction::I64x2Sub
+        | Instruction::I64x2Mul
+        | Instruction::I64x2ExtMulLowI32x4S
+        | Instruction::I64x2ExtMulHighI32x4S
+        | Instruction::I64x2ExtMulLowI32x4U
+        | Instruction::I64x2ExtMulHighI32x4U
+        | Instruction::F32x4Add
+        | Instruction::F32x4Sub
+        | Instruction::F32x4Mul
+        | Instruction::F32x4Div
+        | Instruction::F32x4Min
+        | Instruction::F32x4Max
+        | Instruction::F32x4PMin
+        | Instruction::F32x4PMax
+        | Instruction::F64x2Add
+        | Instruction::F64x2Sub
+        | Instruction::F64x2Mul
+        | Instruction::F64x2Div
+        | Instruction::F64x2Min
+        | Instruction::F64x2Max
+        | Instruction::F64x2PMin
+        | Instruction::F64x2PMax
+        | Instruction::I8x16RelaxedSwizzle
+        | Instruction::F32x4RelaxedMin
+        | Instruction::F32x4RelaxedMax
+        | Instruction::F64x2RelaxedMin
+        | Instruction::F64x2RelaxedMax
+        | Instruction::I16x8RelaxedQ15MulrS
+        | Instruction::I16x8RelaxedDotI8x16I7x16S => Some(2),
+
+        Instruction::V128Bitselect
+        | Instruction::F32x4RelaxedMadd
+        | Instruction::F32x4RelaxedNmadd
+        | Instruction::F64x2RelaxedMadd
+        | Instruction::F64x2RelaxedNmadd
+        | Instruction::I8x16RelaxedLaneselect
+        | Instruction::I16x8RelaxedLaneselect
+        | Instruction::I32x4RelaxedLaneselect
+        | Instruction::I64x2RelaxedLaneselect
+        | Instruction::I32x4RelaxedDotI8x16I7x16AddS => Some(3),
+
+        _ => None,
+    }
+}
+
+/// https://webassembly.github.io/spec/core/exec/instructions.html#vector-instructions
+///
+/// Relaxed instructions are evaluated as their matching non-relaxed instructions.
+pub fn evaluate(instruction: &Instruction, operands: &[Value]) -> ExecutionResult<Value> {
+    match instruction {
+        // Lane instructions
+        Instruction::I8x16Shuffle(indices) => binary(operands, |a, b| {
+            let bytes: Vec<u8> = lanes(a).into_iter().chain(lanes(b)).collect();
+            from_lanes(indices.iter().map(|index| bytes[*index as usize]))
+        }),
+        Instruction::I8x16Swizzle | Instruction::I8x16RelaxedSwizzle => binary(operands, swizzle),
+        Instruction::I8x16Splat => splat(operands, |a: i32| a as i8),
+        Instruction::I16x8Splat => splat(operands, |a: i32| a as i16),
+        Instruction::I32x4Splat => splat(operands, |a: i32| a),
+        Instruction::I64x2Splat => splat(operands, |a: i64| a),
+        Instruction::F32x4Splat => splat(operands, |a: f32| a),
+        Instruction::F64x2Splat => splat(operands, |a: f64| a),
+        Instruction::I8x16ExtractLaneS(lane) => extract(operands, *lane, |a: i8| i32::from(a)),
+        Instruction::I8x16ExtractLaneU(lane) => extract(operands, *lane, |a: u8| i32::from(a)),
+        Instruction::I16x8ExtractLaneS(lane) => extract(operands, *lane, |a: i16| i32::from(a)),
+        Instruction::I16x8ExtractLaneU(lane) => extract(operands, *lane, |a: u16| i32::from(a)),
+        Instruction::I32x4ExtractLane(lane) => extract(operands, *lane, |a: i32| a),
+        Instruction::I64x2ExtractLane(lane) => extract(operands, *lane, |a: i64| a),
+        Instruction::F32x4ExtractLane(lane) => extract(operands, *lane, |a: f32| a),
+        Instruction::F64x2ExtractLane(lane) => extract(operands, *lane, |a: f64| a),
+        Instruction::I8x16ReplaceLane(lane) => replace(operands, *lane, |a: i32| a as i8),
+        Instruction::I16x8ReplaceLane(lane) => replace(operands, *lane, |a: i32| a as i16),
+        Instruction::I32x4ReplaceLane(lane) => replace(operands, *lane, |a: i32| a),
+        Instruction::I64x2ReplaceLane(lane) => replace(operands, *lane, |a: i64| a),
+        Instruction::F32x4ReplaceLane(lane) => replace(operands, *lane, |a: f32| a),
+        Instruction::F64x2ReplaceLane(lane) => replace(operands, *lane, |a: f64| a),
+
+        // Comparison instructions
+        Instruction::I8x16Eq => compare(operands, |a: i8, b| a == b),
+        Instruction::I8x16Ne => compare(operands, |a: i8, b| a != b),
+        Instruction::I8x16LtS => compare(operands, |a: i8, b| a < b),
+        Instruction::I8x16LtU => compare(operands, |a: u8, b| a < b),
+        Instruction::I8x16GtS => compare(operands, |a: i8, b| a > b),
+        Instruction::I8x16GtU => compare(operands, |a: u8, b| a > b),
+        Instruction::I8x16LeS => compare(operands, |a: i8, b| a <= b),
+        Instruction::I8x16LeU => compare(operands, |a: u8, b| a <= b),
+        Instruction::I8x16GeS => compare(operands, |a: i8, b| a >= b),
+        Instruction::I8x16GeU => compare(operands, |a: u8, b| a >= b),
+        Instruction::I16x8Eq => compare(operands, |a: i16, b| a == b),
+        Instruction::I16x8Ne => compare(operands, |a: i16, b| a != b),
+        Instruction::I16x8LtS => compare(operands, |a: i16, b| a < b),
+        Instruction::I16x8LtU => compare(operands, |a: u16, b| a < b),
+        Instruction::I16x8GtS => compare(operands, |a: i16, b| a > b),
+        Instruction::I16x8GtU => compare(operands, |a: u16, b| a > b),
+        Instruction::I16x8LeS => compare(operands, |a: i16, b| a <= b),
+        Instruction::I16x8LeU => compare(operands, |a: u16, b| a <= b),
+        Instruction::I16x8GeS => compare(operands, |a: i16, b| a >= b),
+        Instruction::I16x8GeU => compare(operands, |a: u16, b| a >= b),
+        Instruction::I32x4Eq => compare(operands, |a: i32, b| a == b),
+        Instruction::I32x4Ne => compare(operands, |a: i32, b| a != b),
+        Instruction::I32x4LtS => compare(operands, |a: i32, b| a < b),
+        Instruction::I32x4LtU => compare(operands, |a: u32, b| a < b),
+        Instruction::I32x4GtS => compare(operands, |a: i32, b| a > b),
+        Instruction::I32x4GtU => compare(operands, |a: u32, b| a > b),
+        Instruction::I32x4LeS => compare(operands, |a: i32, b| a <= b),
+        Instruction::I32x4LeU => compare(operands, |a: u32, b| a <= b),
+        Instruction::I32x4GeS => compare(operands, |a: i32, b| a >= b),
+        Instruction::I32x4GeU => compare(operands, |a: u32, b| a >= b),
+        Instruction::I64x2Eq => compare(operands, |a: i64, b| a == b),
+        Instruction::I64x2Ne => compare(operands, |a: i64, b| a != b),
+        Instruction::I64x2LtS => compare(operands, |a: i64, b| a < b),
+        Instruction::I64x2GtS => compare(operands, |a: i64, b| a > b),
+        Instruction::I64x2LeS => compare(operands, |a: i64, b| a <= b),
+        Instruction::I64x2GeS => compare(operands, |a: i64, b| a >= b),
+        Instruction::F32x4Eq => compare(operands, |a: f32, b| a == b),
+        Instruction::F32x4Ne => compare(operands, |a: f32, b| a != b),
+        Instruction::F32x4Lt => compare(operands, |a: f32, b| a < b),
+        Instruction::F32x4Gt => compare(operands, |a: f32, b| a > b),
+        Instruction::F32x4Le => compare(operands, |a: f32, b| a <= b),
+        Instruction::F32x4Ge => compare(operands, |a: f32, b| a >= b),
+        Instruction::F64x2Eq => compare(operands, |a: f64, b| a == b),
+        Instruction::F64x2Ne => compare(operands, |a: f64, b| a != b),
+        Instruction::F64x2Lt => compare(operands, |a: f64, b| a < b),
+        Instruction::F64x2Gt => compare(operands, |a: f64, b| a > b),
+        Instruction::F64x2Le => compare(operands, |a: f64, b| a <= b),
+        Instruction::F64x2Ge => compare(operands, |a: f64, b| a >= b),
+
+        // Bitwise instructions
+        Instruction::V128Not => unary(operands, |a| !a),
+        Instruction::V128And => binary(operands, |a, b| a & b),
+        Instruction::V128AndNot => binary(operands, |a, b| a & !b),
+        Instruction::V128Or => binary(operands, |a, b| a | b),
+        Instruction::V128Xor => binary(operands, |a, b| a ^ b),
+        Instruction::V128Bitselect
+        | Instruction::I8x16RelaxedLaneselect
+        | Instruction::I16x8RelaxedLaneselect
+        | Instruction::I32x4RelaxedLaneselect
+        | Instruction::I64x2RelaxedLaneselect => ternary(operands, |a, b, c| (a & c) | (b & !c)),
+        Instruction::V128AnyTrue => unary(operands, |a| a != 0),
+
+        // Integer instructions
+        Instruction::I8x16Abs => lanewise_unary(operands, |a: i8| a.wrapping_abs()),
+        Instruction::I8x16Neg => lanewise_unary(operands, |a: i8| a.wrapping_neg()),
+        Instruction::I8x16Popcnt => lanewise_unary(operands, |a: u8| a.count_ones() as u8),
+        Instruction::I8x16AllTrue => unary(operands, all_true::<i8>),
+        Instruction::I8x16Bitmask => unary(operands, bitmask::<i8>),
+        Instruction::I8x16NarrowI16x8S => binary(operands, |a, b| {
+            narrow(a, b, |a: i16| a.clamp(i8::MIN.into(), i8::MAX.into()) as i8)
+        }),
+        Instruction::I8x16NarrowI16x8U => binary(operands, |a, b| {
+            narrow(a, b, |a: i16| a.clamp(u8::MIN.into(), u8::MAX.into()) as u8)
+        }),
+        Instruction::I8x16Shl => shift(operands, |a: i8, b| a.wrapping_shl(b)),
+        Instruction::I8x16ShrS => shift(operands, |a: i8, b| a.wrapping_shr(b)),
+        Instruction::I8x16ShrU => shift(operands, |a: u8, b| a.wrapping_shr(b)),
+        Instruction::I8x16Add => lanewise_binary(operands, |a: i8, b| a.wrapping_add(b)),
+        Instruction::I8x16AddSatS => lanewise_binary(operands, |a: i8, b| a.saturating_add(b)),
+        Instruction::I8x16AddSatU => lanewise_binary(operands, |a: u8, b| a.saturating_add(b)),
+        Instruction::I8x16Sub => lanewise_binary(operands, |a: i8, b| a.wrapping_sub(b)),
+        Instruction::I8x16SubSatS => lanewise_binary(operands, |a: i8, b| a.saturating_sub(b)),
+        Instruction::I8x16SubSatU => lanewise_binary(operands, |a: u8, b| a.saturating_sub(b)),
+        Instruction::I8x16MinS => lanewise_binary(operands, |a: i8, b| a.min(b)),
+        Instruction::I8x16MinU => lanewise_binary(operands, |a: u8, b| a.min(b)),
+        Instruction::I8x16MaxS => lanewise_binary(operands, |a: i8, b| a.max(b)),
+        Instruction::I8x16MaxU => lanewise_binary(operands, |a: u8, b| a.max(b)),
+        Instruction::I8x16AvgrU => lanewise_binary(operands, |a: u8, b| {
+            (u16::from(a) + u16::from(b)).div_ceil(2) as u8
+        }),
+        Instruction::I16x8ExtAddPairwiseI8x16S => unary(operands, |a| {
+            from_lanes(
+                lanes::<i8>(a)
+                    .chunks(2)
+                    .map(|pair| i16::from(pair[0]) + i16::from(pair[1])),
+            )
+        }),
+        Instruction::I16x8ExtAddPairwiseI8x16U => unary(operands, |a| {
+            from_lanes(
+                lanes::<u8>(a)
+                    .chunks(2)
+                    .map(|pair| u16::from(pair[0]) + u16::from(pair[1])),
+            )
+        }),
+        Instruction::I32x4ExtAddPairwiseI16x8S => unary(operands, |a| {
+            from_lanes(
+                lanes::<i16>(a)
+                    .chunks(2)
+                    .map(|pair| i32::from(pair[0]) + i32::from(pair[1])),
+            )
+        }),
+        Instruction::I32x4ExtAddPairwiseI16x8U => unary(operands, |a| {
+            from_lanes(
+                lanes::<u16>(a)
+                    .chunks(2)
+                    .map(|pair| u32::from(pair[0]) + u32::from(pair[1])),
+            )
+        }),
+        Instruction::I16x8Abs => lanewise_unary(operands, |a: i16| a.wrapping_abs()),
+        Instruction::I16x8Neg => lanewise_unary(operands, |a: i16| a.wrapping_neg()),
+        Instruction::I16x8Q15MulrSatS | Instruction::I16x8RelaxedQ15MulrS => {
+            lanewise_binary(operands, |a: i16, b| {
+                let product = (i32::from(a) * i32::from(b) + 0x4000) >> 15;
+                product.clamp(i16::MIN.into(), i16::MAX.into()) as i16
+            })
+        }
+        Instruction::I16x8AllTrue => unary(operands, all_true::<i16>),
+        Instruction::I16x8Bitmask => unary(operands, bitmask::<i16>),
+        Instruction::I16x8NarrowI32x4S => binary(operands, |a, b| {
+            narrow(a, b, |a: i32| {
+                a.clamp(i16::MIN.into(), i16::MAX.into()) as i16
+            })
+        }),
+        Instruction::I16x8NarrowI32x4U => binary(operands, |a, b| {
+            narrow(a, b, |a: i32| {
+                a.clamp(u16::MIN.into(), u16::MAX.into()) as u16
+            })
+        }),
+        Instruction::I16x8ExtendLowI8x16S => {
+            unary(operands, |a| extend(a, false, |a: i8| i16::from(a)))
+        }
+        Instruction::I16x8ExtendHighI8x16S => {
+            unary(operands, |a| extend(a, true, |a: i8| i16::from(a)))
+        }
+        Instruction::I16x8ExtendLowI8x16U => {
+            unary(operands, |a| extend(a, false, |a: u8| u16::from(a)))
+        }
+        Instruction::I16x8ExtendHighI8x16U => {
+            unary(operands, |a| extend(a, true, |a: u8| u16::from(a)))
+        }
+        Instruction::I16x8Shl => shift(operands, |a: i16, b| a.wrapping_shl(b)),
+        Instruction::I16x8ShrS => shift(operands, |a: i16, b| a.wrapping_shr(b)),
+        Instruction::I16x8ShrU => shift(operands, |a: u16, b| a.wrapping_shr(b)),
+        Instruction::I16x8Add => lanewise_binary(operands, |a: i16, b| a.wrapping_add(b)),
+        Instruction::I16x8AddSatS => lanewise_binary(operands, |a: i16, b| a.saturating_add(b)),
+        Instruction::I16x8AddSatU => lanewise_binary(operands, |a: u16, b| a.saturating_add(b)),
+        Instruction::I16x8Sub => lanewise_binary(operands, |a: i16, b| a.wrapping_sub(b)),
+        Instruction::I16x8SubSatS => lanewise_binary(operands, |a: i16, b| a.saturating_sub(b)),
+        Instruction::I16x8SubSatU => lanewise_binary(operands, |a: u16, b| a.saturating_sub(b)),
+        Instruction::I16x8Mul => lanewise_binary(operands, |a: i16, b| a.wrapping_mul(b)),
+        Instruction::I16x8MinS => lanewise_binary(operands, |a: i16, b| a.min(b)),
+        Instruction::I16x8MinU => lanewise_binary(operands, |a: u16, b| a.min(b)),
+        Instruction::I16x8MaxS => lanewise_binary(operands, |a: i16, b| a.max(b)),
+        Instruction::I16x8MaxU => lanewise_binary(operands, |a: u16, b| a.max(b)),
+        Instruction::I16x8AvgrU => lanewise_binary(operands, |a: u16, b| {
+            (u32::from(a) + u32::from(b)).div_ceil(2) as u16
+        }),
+        Instruction::I16x8ExtMulLowI8x16S => binary(operands, |a, b| {
+            multiply_extended(a, b, false, |a: i8, b: i8| i16::from(a) * i16::from(b))
+        }),
+        Instruction::I16x8ExtMulHighI8x16S => binary(operands, |a, b| {
+            multiply_extended(a, b, true, |a: i8, b: i8| i16::from(a) * i16::from(b))
+        }),
+        Instruction::I16x8ExtMulLowI8x16U => binary(operands, |a, b| {
+            multiply_extended(a, b, false, |a: u8, b: u8| u16::from(a) * u16::from(b))
+        }),
+        Instruction::I16x8ExtMulHighI8x16U => binary(operands, |a, b| {
+            multiply_extended(a, b, true, |a: u8, b: u8| u16::from(a) * u16::from(b))
+        }),
+        Instruction::I32x4Abs => lanewise_unary(operands, |a: i32| a.wrapping_abs()),
+        Instruction::I32x4Neg => lanewise_unary(operands, |a: i32| a.wrapping_neg()),
+        Instruction::I32x4AllTrue => unary(operands, all_true::<i32>),
+        Instruction::I32x4Bitmask => unary(operands, bitmask::<i32>),
+        Instruction::I32x4ExtendLowI16x8S => {
+            unary(operands, |a| extend(a, false, |a: i16| i32::from(a)))
+        }
+        Instruction::I32x4ExtendHighI16x8S => {
+            unary(operands, |a| extend(a, true, |a: i16| i32::from(a)))
+        }
+        Instruction::I32x4ExtendLowI16x8U => {
+            unary(operands, |a| extend(a, false, |a: u16| u32::from(a)))
+        }
+        Instruction::I32x4ExtendHighI16x8U => {
+            unary(operands, |a| extend(a, true, |a: u16| u32::from(a)))
+        }
+        Instruction::I32x4Shl => shift(operands, |a: i32, b| a.wrapping_shl(b)),
+        Instruction::I32x4ShrS => shift(operands, |a: i32, b| a.wrapping_shr(b)),
+        Instruction::I32x4ShrU => shift(operands, |a: u32, b| a.wrapping_shr(b)),
+        Instruction::I32x4Add => lanewise_binary(operands, |a: i32, b| a.wrapping_add(b)),
+        Instruction::I32x4Sub => lanewise_binary(operands, |a: i32, b| a.wrapping_sub(b)),
+        Instruction::I32x4Mul => lanewise_binary(operands, |a: i32, b| a.wrapping_mul(b)),
+        Instruction::I32x4MinS => lanewise_binary(operands, |a: i32, b| a.min(b)),
+        Instruction::I32x4MinU => lanewise_binary(operands, |a: u32, b| a.min(b)),
+        Instruction::I32x4MaxS => lanewise_binary(operands, |a: i32, b| a.max(b)),
+        Instruction::I32x4MaxU => lanewise_binary(operands, |a: u32, b| a.max(b)),
+        Instruction::I32x4DotI16x8S => binary(operands, |a, b| {
+            let products = lanes::<i16>(a)
+                .into_iter()
+                .zip(lanes::<i16>(b))
+                .map(|(a, b)| i32::from(a) * i32::from(b))
+                .collect::<Vec<_>>();
+            from_lanes(products.chunks(2).map(|pair| pair[0].wrapping_add(pair[1])))
+        }),
+        Instruction::I32x4ExtMulLowI16x8S => binary(operands, |a, b| {
+            multiply_extended(a, b, false, |a: i16, b: i16| i32::from(a) * i32::from(b))
+        }),
+        Instruction::I32x4ExtMulHighI16x8S => binary(operands, |a, b| {
+            multiply_extended(a, b, true, |a: i16, b: i16| i32::from(a) * i32::from(b))
+        }),
+        Instruction::I32x4ExtMulLowI16x8U => binary(operands, |a, b| {
+            multiply_extended(a, b, false, |a: u16, b: u16| u32::from(a) * u32::from(b))
+        }),
+        Instruction::I32x4ExtMulHighI16x8U => binary(operands, |a, b| {
+            multiply_extended(a, b, true, |a: u16, b: u16| u32::from(a) * u32::from(b))
+        }),
+        Instruction::I64x2Abs => lanewise_unary(operands, |a: i64| a.wrapping_abs()),
+        Instruction::I64x2Neg => lanewise_unary(operands, |a: i64| a.wrapping_neg()),
+        Instruction::I64x2AllTrue => unary(operands, all_true::<i64>),
+        Instruction::I64x2Bitmask => unary(operands, bitmask::<i64>),
+        Instruction::I64x2ExtendLowI32x4S => {
+            unary(operands, |a| extend(a, false, |a: i32| i64::from(a)))
+        }
+        Instruction::I64x2ExtendHighI32x4S => {
+            unary(operands, |a| extend(a, true, |a: i32| i64::from(a)))
+        }
+        Instruction::I64x2ExtendLowI32x4U => {
+            unary(operands, |a| extend(a, false, |a: u32| u64::from(a)))
+        }
+        Instruction::I64x2ExtendHighI32x4U => {
+            unary(operands, |a| extend(a, true, |a: u32| u64::from(a)))
+        }
+        Instruction::I64x2Shl => shift(operands, |a: i64, b| a.wrapping_shl(b)),
+        Instruction::I64x2ShrS => shift(operands, |a: i64, b| a.wrapping_shr(b)),
+        Instruction::I64x2ShrU => shift(operands, |a: u64, b| a.wrapping_shr(b)),
+        Instruction::I64x2Add => lanewise_binary(operands, |a: i64, b| a.wrapping_add(b)),
+        Instruction::I64x2Sub => lanewise_binary(operands, |a: i64, b| a.wrapping_sub(b)),
+        Instruction::I64x2Mul => lanewise_binary(operands, |a: i64, b| a.wrapping_mul(b)),
+        Instruction::I64x2ExtMulLowI32x4S => binary(operands, |a, b| {
+            multiply_extended(a, b, false, |a: i32, b: i32| i64::from(a) * i64::from(b))
+        }),
+        Instruction::I64x2ExtMulHighI32x4S => binary(operands, |a, b| {
+            multiply_extended(a, b, true, |a: i32, b: i32| i64::from(a) * i64::from(b))
+        }),
+        Instruction::I64x2ExtMulLowI32x4U => binary(operands, |a, b| {
+            multiply_extended(a, b, false, |a: u32, b: u32| u64::from(a) * u64::from(b))
+        }),
+        Instruction::I64x2ExtMulHighI32x4U => binary(operands, |a, b| {
+            multiply_extended(a, b, true, |a: u32, b: u32| u64::from(a) * u64::from(b))
+        }),
+
+        // The second operand of the relaxed dot products is treated as signed, and the pairwise
+        // sums of the products saturate.
+        Instruction::I16x8RelaxedDotI8x16I7x16S => binary(operands, dot_i8x16_i7x16),
+        Instruction::I32x4RelaxedDotI8x16I7x16AddS => ternary(operands, |a, b, c| {
+            let sums = lanes::<i16>(dot_i8x16_i7x16(a, b))
+                .chunks(2)
+                .map(|pair| i32::from(pair[0]) + i32::from(pair[1]))
+                .collect::<Vec<_>>();
+            from_lanes(
+                sums.into_iter()
+                    .zip(lanes::<i32>(c))
+                    .map(|(a, b)| a.wrapping_add(b)),
+            )
+        }),
+
+        // Floating point instructions
+        Instruction::F32x4Ceil => lanewise_unary(operands, |a: f32| quiet_f32(a.ceil())),
+        Instruction::F32x4Floor => lanewise_unary(operands, |a: f32| quiet_f32(a.floor())),
+        Instruction::F32x4Trunc => lanewise_unary(operands, |a: f32| quiet_f32(a.trunc())),
+        Instruction::F32x4Nearest => {
+            lanewise_unary(operands, |a: f32| quiet_f32(a.round_ties_even()))
+        }
+        Instruction::F32x4Abs => lanewise_unary(operands, |a: f32| a.abs()),
+        Instruction::F32x4Neg => lanewise_unary(operands, |a: f32| -a),
+        Instruction::F32x4Sqrt => lanewise_unary(operands, |a: f32| a.sqrt()),
+        Instruction::F32x4Add => lanewise_binary(operands, |a: f32, b| a + b),
+        Instruction::F32x4Sub => lanewise_binary(operands, |a: f32, b| a - b),
+        Instruction::F32x4Mul => lanewise_binary(operands, |a: f32, b| a * b),
+        Instruction::F32x4Div => lanewise_binary(operands, |a: f32, b| a / b),
+        Instruction::F32x4Min | Instruction::F32x4RelaxedMin => lanewise_binary(operands, min_f32),
+        Instruction::F32x4Max | Instruction::F32x4RelaxedMax => lanewise_binary(operands, max_f32),
+        Instruction::F32x4PMin => lanewise_binary(operands, |a: f32, b| if b < a { b } else { a }),
+        Instruction::F32x4PMax => lanewise_binary(operands, |a: f32, b| if a < b { b } else { a }),
+        Instruction::F32x4RelaxedMadd => lanewise_ternary(operands, |a: f32, b, c| a * b + c),
+        Instruction::F32x4RelaxedNmadd => lanewise_ternary(operands, |a: f32, b, c| -(a * b) + c),
+        Instruction::F64x2Ceil => lanewise_unary(operands, |a: f64| quiet_f64(a.ceil())),
+        Instruction::F64x2Floor => lanewise_unary(operands, |a: f64| quiet_f64(a.floor())),
+        Instruction::F64x2Trunc => lanewise_unary(operands, |a: f64| quiet_f64(a.trunc())),
+        Instruction::F64x2Nearest => {
+            lanewise_unary(operands, |a: f64| quiet_f64(a.round_ties_even()))
+        }
+        Instruction::F64x2Abs => lanewise_unary(operands, |a: f64| a.abs()),
+        Instruction::F64x2Neg => lanewise_unary(operands, |a: f64| -a),
+        Instruction::F64x2Sqrt => lanewise_unary(operands, |a: f64| a.sqrt()),
+        Instruction::F64x2Add => lanewise_binary(operands, |a: f64, b| a + b),
+        Instruction::F64x2Sub => lanewise_binary(operands, |a: f64, b| a - b),
+        Instruction::F64x2Mul => lanewise_binary(operands, |a: f64, b| a * b),
+        Instruction::F64x2Div => lanewise_binary(operands, |a: f64, b| a / b),
+        Instruction::F64x2Min | Instruction::F64x2RelaxedMin => lanewise_binary(operands, min_f64),
+        Instruction::F64x2Max | Instruction::F64x2RelaxedMax => lanewise_binary(operands, max_f64),
+        Instruction::F64x2PMin => lanewise_binary(operands, |a: f64, b| if b < a { b } else { a }),
+        Instruction::F64x2PMax => lanewise_binary(operands, |a: f64, b| if a < b { b } else { a }),
+        Instruction::F64x2RelaxedMadd => lanewise_ternary(operands, |a: f64, b, c| a * b + c),
+        Instruction::F64x2RelaxedNmadd => lanewise_ternary(operands, |a: f64, b, c| -(a * b) + c),
+
+        // Conversion instructions, the saturating truncations relying on the casts of Rust.
+        Instruction::F32x4DemoteF64x2Zero => unary(operands, |a| {
+            from_lanes(lanes::<f64>(a).into_iter().map(|a| a as f32))
+        }),
+        Instruction::F64x2PromoteLowF32x4 => {
+            unary(operands, |a| extend(a, false, |a: f32| f64::from(a)))
+        }
+        Instruction::I32x4TruncSatF32x4S | Instruction::I32x4RelaxedTruncF32x4S => {
+            unary(operands, |a| {
+                from_lanes(lanes::<f32>(a).into_iter().map(|a| a as i32))
+            })
+        }
+        Instruction::I32x4TruncSatF32x4U | Instruction::I32x4RelaxedTruncF32x4U => {
+            unary(operands, |a| {
+                from_lanes(lanes::<f32>(a).into_iter().map(|a| a as u32))
+            })
+        }
+        Instruction::I32x4TruncSatF64x2SZero | Instruction::I32x4RelaxedTruncF64x2SZero => {
+            unary(operands, |a| {
+                from_lanes(lanes::<f64>(a).into_iter().map(|a| a as i32))
+            })
+        }
+        Instruction::I32x4TruncSatF64x2UZero | Instruction::I32x4RelaxedTruncF64x2UZero => {
+            unary(operands, |a| {
+                from_lanes(lanes::<f64>(a).into_iter().map(|a| a as u32))
+            })
+        }
+        Instruction::F32x4ConvertI32x4S => unary(operands, |a| {
+            from_lanes(lanes::<i32>(a).into_iter().map(|a| a as f32))
+        }),
+        Instruction::F32x4ConvertI32x4U => unary(operands, |a| {
+            from_lanes(lanes::<u32>(a).into_iter().map(|a| a as f32))
+        }),
+        Instruction::F64x2ConvertLowI32x4S => {
+            unary(operands, |a| extend(a, false, |a: i32| f64::from(a)))
+        }
+        Instruction::F64x2ConvertLowI32x4U => {
+            unary(operands, |a| extend(a, false, |a: u32| f64::from(a)))
+        }
+
+        _ => Err(Trap::from_string(format!(
+            "Unsupported vector instruction {:?}",
+            instruction
+        ))),
+    }
+}
+
+/// Scalar type of the lanes of a vector, the lanes being stored in little endian order.
+pub trait Lane: Copy {
+    const WIDTH: usize;
+
+    fn from_bytes(bytes: &[u8]) -> Self;
+
+    fn to_bytes(self, bytes: &mut [u8]);
+}
+
+macro_rules! impl_lane {
+    ($($lane_type:ty),*) => {
+        $(
+            impl Lane for $lane_type {
+                const WIDTH: usize = std::mem::size_of::<$lane_type>();
+
+                fn from_bytes(bytes: &[u8]) -> $lane_type {
+                    <$lane_type>::from_le_bytes(bytes.try_into().unwrap_or_default())
+                }
+
+                fn to_bytes(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes());
+                }
+            }
+        )*
+    };
+}
+
+impl_lane!(i8, u8, i16, u16, i32, u32, i64, u64, f32, f64);
+
+// https://webassembly.github.io/spec/core/exec/numerics.html#aux-lanes
+pub fn lanes<T: Lane>(vector: u128) -> Vec<T> {
+    vector
+        .to_le_bytes()
+        .chunks(T::WIDTH)
+        .map(T::from_bytes)
+        .collect()
+}
+
+// Missing lanes are zero, as for the conversions of two lanes into a vector of four.
+pub fn from_lanes<T: Lane>(lanes: impl IntoIterator<Item = T>) -> u128 {
+    let mut bytes = [0; 16];
+    for (chunk, lane) in bytes.chunks_mut(T::WIDTH).zip(lanes) {
+        lane.to_bytes(chunk);
+    }
+    u128::from_le_bytes(bytes)
+}
+
+// Lanes of the low or high half of the vector, converted into lanes twice as wide.
+pub fn extend<T: Lane, R: Lane>(vector: u128, is_high: bool, convert: impl Fn(T) -> R) -> u128 {
+    let lanes = lanes::<T>(vector);
+    let half = lanes.len() / 2;
+    let start = if is_high { half } else { 0 };
+    from_lanes(lanes[start..start + half].iter().map(|lane| convert(*lane)))
+}
+
+fn multiply_extended<T: Lane, R: Lane>(
+    lhs: u128,
+    rhs: u128,
+    is_high: bool,
+    multiply: impl Fn(T, T) -> R,
+) -> u128 {
+    let lhs = lanes::<T>(lhs);
+    let rhs = lanes::<T>(rhs);
+    let half = lhs.len() / 2;
+    let start = if is_high { half } else { 0 };
+    from_lanes((start..start + half).map(|index| multiply(lhs[index], rhs[index])))
+}
+
+fn narrow<T: Lane, R: Lane>(lhs: u128, rhs: u128, saturate: impl Fn(T) -> R) -> u128 {
+    from_lanes(lanes::<T>(lhs).into_iter().chain(lanes(rhs)).map(saturate))
+}
+
+// Indices out of range select zero.
+fn swizzle(vector: u128, indices: u128) -> u128 {
+    let bytes = lanes::<u8>(vector);
+    from_lanes(
+        lanes::<u8>(indices)
+            .into_iter()
+            .map(|index| bytes.get(index as usize).copied().unwrap_or(0)),
+    )
+}
+
+fn dot_i8x16_i7x16(lhs: u128, rhs: u128) -> u128 {
+    let products = lanes::<i8>(lhs)
+        .into_iter()
+        .zip(lanes::<i8>(rhs))
+        .map(|(a, b)| i16::from(a) * i16::from(b))
+        .collect::<Vec<_>>();
+    from_lanes(
+        products
+            .chunks(2)
+            .map(|pair| pair[0].saturating_add(pair[1])),
+    )
+}
+
+fn all_true<T: Lane + Default + PartialEq>(vector: u128) -> bool {
+    lanes::<T>(vector)
+        .into_iter()
+        .all(|lane| lane != T::default())
+}
+
+// The bits of the mask are the sign bits of the lanes.
+fn bitmask<T: Lane + Default + PartialOrd>(vector: u128) -> i32 {
+    lanes::<T>(vector)
+        .into_iter()
+        .enumerate()
+        .filter(|(_, lane)| *lane < T::default())
+        .fold(0, |mask, (index, _)| mask | (1 << index))
+}
+
+fn get_operand<T: FromValue>(operand: Value) -> ExecutionResult<T> {
+    T::from_value(operand).ok_or(Trap::from("Mismatching operand type"))
+}
+
+fn unary<R: Into<Value>>(
+    operands: &[Value],
+    operation: impl Fn(u128) -> R,
+) -> ExecutionResult<Value> {
+    match operands {
+        [operand] => Ok(operation(get_operand(*operand)?).into()),
+        _ => Err(Trap::from("Invalid operand count")),
+    }
+}
+
+fn binary(operands: &[Value], operation: impl Fn(u128, u128) -> u128) -> ExecutionResult<Value> {
+    match operands {
+        [lhs, rhs] => Ok(operation(get_operand(*lhs)?, get_operand(*rhs)?).into()),
+        _ => Err(Trap::from("Invalid operand count")),
+    }
+}
+
+fn ternary(
+    operands: &[Value],
+    operation: impl Fn(u128, u128, u128) -> u128,
+) -> ExecutionResult<Value> {
+    match operands {
+        [first, second, third] => Ok(operation(
+            get_operand(*first)?,
+            get_operand(*second)?,
+            get_operand(*third)?,
+        )
+        .into()),
+        _ => Err(Trap::from("Invalid operand count")),
+    }
+}
+
+fn lanewise_unary<T: Lane>(
+    operands: &[Value],
+    operation: impl Fn(T) -> T,
+) -> ExecutionResult<Value> {
+    unary(operands, |a| {
+        from_lanes(lanes(a).into_iter().map(&operation))
+    })
+}
+
+fn lanewise_binary<T: Lane>(
+    operands: &[Value],
+    operation: impl Fn(T, T) -> T,
+) -> ExecutionResult<Value> {
+    binary(operands, |a, b| {
+        let lanes = lanes(a).into_iter().zip(lanes(b));
+        from_lanes(lanes.map(|(a, b)| operation(a, b)))
+    })
+}
+
+fn lanewise_ternary<T: Lane>(
+    operands: &[Value],
+    operation: impl Fn(T, T, T) -> T,
+) -> ExecutionResult<Value> {
+    ternary(operands, |a, b, c| {
+        let lanes = lanes(a).into_iter().zip(lanes(b)).zip(lanes(c));
+        from_lanes(lanes.map(|((a, b), c)| operation(a, b, c)))
+    })
+}
+
+// Lanes of the result are all ones where the comparison holds, and zeros elsewhere.
+fn compare<T: Lane>(
+    operands: &[Value],
+    operation: impl Fn(T, T) -> bool,
+) -> ExecutionResult<Value> {
+    binary(operands, |a, b| {
+        let mut bytes = [0; 16];
+        let lanes = lanes::<T>(a).into_iter().zip(lanes(b));
+        for (chunk, (a, b)) in bytes.chunks_mut(T::WIDTH).zip(lanes) {
+            if operation(a, b) {
+                chunk.fill(0xff);
+            }
+        }
+        u128::from_le_bytes(bytes)
+    })
+}
+
+// The shift count is taken modulo the bit width of the lanes.
+fn shift<T: Lane>(operands: &[Value], operation: impl Fn(T, u32) -> T) -> ExecutionResult<Value> {
+    match operands {
+        [vector, count] => {
+            let count = get_operand::<i32>(*count)? as u32 % (T::WIDTH as u32 * 8);
+            lanewise_unary(&[*vector], |a| operation(a, count))
+        }
+        _ => Err(Trap::from("Invalid operand count")),
+    }
+}
+
+fn splat<S: FromValue, T: Lane>(
+    operands: &[Value],
+    convert: impl Fn(S) -> T,
+) -> ExecutionResult<Value> {
+    match operands {
+        [operand] => {
+            let lane = convert(get_operand(*operand)?);
+            Ok(from_lanes(std::iter::repeat(lane)).into())
+        }
+        _ => Err(Trap::from("Invalid operand count")),
+    }
+}
+
+fn extract<T: Lane, R: Into<Value>>(
+    operands: &[Value],
+    lane: u8,
+    convert: impl Fn(T) -> R,
+) -> ExecutionResult<Value> {
+    unary(operands, |a| convert(lanes::<T>(a)[lane as usize]))
+}
+
+fn replace<S: FromValue, T: Lane>(
+    operands: &[Value],
+    lane: u8,
+    convert: impl Fn(S) -> T,
+) -> ExecutionResult<Value> {
+    match operands {
+        [vector, operand] => {
+            let mut lanes = lanes::<T>(get_operand(*vector)?);
+            lanes[lane as usize] = convert(get_operand(*operand)?);
+            Ok(from_lanes(lanes).into())
+        }
+        _ => Err(Trap::from("Invalid operand count")),
+    }
+}
@@ -1,4 +1,5 @@
 pub mod structure;
 pub mod decoder;
 pub mod validation;
+pub mod execution;
 pub mod test_runner;
\ No newline at end of file
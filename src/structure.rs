@@ -1,44 +1,154 @@
 // TODO: Understand why Copy and Clone are always applied at the same time.
 // More details: https://doc.rust-lang.org/std/marker/trait.Copy.html
 /// https://webassembly.github.io/spec/core/syntax/types.html#value-types
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum ValueType {
     I32,
     I64,
     F32,
     F64,
+    V128,
+    Ref(RefType),
 }
 
 /// https://webassembly.github.io/spec/core/syntax/types.html#function-types
 pub type FunctionType = (Vec<ValueType>, Vec<ValueType>);
 
+/// https://webassembly.github.io/gc/core/syntax/types.html#aggregate-types
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StorageType {
+    Value(ValueType),
+    I8,
+    I16,
+}
+
+impl StorageType {
+    // Packed fields are read and written as i32 values.
+    //
+    // https://webassembly.github.io/gc/core/syntax/types.html#aux-unpacktype
+    pub fn unpack(&self) -> ValueType {
+        match self {
+            StorageType::Value(value_type) => *value_type,
+            StorageType::I8 | StorageType::I16 => ValueType::I32,
+        }
+    }
+}
+
+/// https://webassembly.github.io/gc/core/syntax/types.html#aggregate-types
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FieldType {
+    pub storage_type: StorageType,
+    pub mutability: GlobalTypeMutability,
+}
+
+/// https://webassembly.github.io/gc/core/syntax/types.html#composite-types
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum CompositeType {
+    Function(FunctionType),
+    Struct(Vec<FieldType>),
+    Array(FieldType),
+}
+
+/// https://webassembly.github.io/gc/core/syntax/types.html#recursive-types
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SubType {
+    pub is_final: bool,
+    pub supertypes: Vec<u32>,
+    pub composite_type: CompositeType,
+}
+
+/// Types within a recursion group can refer to each other, and share a single index space with
+/// the types of the other groups.
+///
+/// https://webassembly.github.io/gc/core/syntax/types.html#recursive-types
+pub type RecursiveType = Vec<SubType>;
+
 /// https://webassembly.github.io/spec/core/syntax/types.html#limits
 #[derive(Debug)]
 pub struct Limits {
-    pub min: u32,
-    pub max: Option<u32>,
+    pub min: u64,
+    pub max: Option<u64>,
 }
 
 /// https://webassembly.github.io/spec/core/syntax/types.html#memory-types
 #[derive(Debug)]
 pub struct MemoryType {
     pub limits: Limits,
+    pub shared: bool,
+    pub address_type: AddressType,
 }
 
-#[derive(Debug)]
-pub enum ElementType {
-    FuncRef,
+/// https://webassembly.github.io/memory64/core/syntax/types.html#address-types
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AddressType {
+    I32,
+    I64,
+}
+
+impl From<AddressType> for ValueType {
+    fn from(address_type: AddressType) -> ValueType {
+        match address_type {
+            AddressType::I32 => ValueType::I32,
+            AddressType::I64 => ValueType::I64,
+        }
+    }
+}
+
+/// https://webassembly.github.io/gc/core/syntax/types.html#heap-types
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HeapType {
+    Func,
+    NoFunc,
+    Extern,
+    NoExtern,
+    Exn,
+    NoExn,
+    Any,
+    Eq,
+    I31,
+    Struct,
+    Array,
+    None,
+    Index(u32),
+}
+
+/// https://webassembly.github.io/function-references/core/syntax/types.html#reference-types
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RefType {
+    pub nullable: bool,
+    pub heap_type: HeapType,
+}
+
+impl RefType {
+    pub const FUNCREF: RefType = RefType {
+        nullable: true,
+        heap_type: HeapType::Func,
+    };
+    pub const EXTERNREF: RefType = RefType {
+        nullable: true,
+        heap_type: HeapType::Extern,
+    };
+    pub const EXNREF: RefType = RefType {
+        nullable: true,
+        heap_type: HeapType::Exn,
+    };
+}
+
+impl From<RefType> for ValueType {
+    fn from(ref_type: RefType) -> ValueType {
+        ValueType::Ref(ref_type)
+    }
 }
 
 /// https://webassembly.github.io/spec/core/syntax/types.html#table-types
 #[derive(Debug)]
 pub struct TableType {
     pub limits: Limits,
-    pub element_type: ElementType,
+    pub element_type: RefType,
 }
 
 /// https://webassembly.github.io/spec/core/syntax/types.html#global-types
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum GlobalTypeMutability {
     Const,
     Var,
@@ -49,16 +159,28 @@ pub struct GlobalType {
     pub mutability: GlobalTypeMutability,
 }
 
+/// https://webassembly.github.io/spec/core/syntax/instructions.html#control-instructions
 #[derive(Debug, Copy, Clone)]
 pub enum BlockType {
     Void,
     Return(ValueType),
+    TypeIndex(u32),
+}
+
+/// https://webassembly.github.io/exception-handling/core/syntax/instructions.html#control-instructions
+#[derive(Debug, Copy, Clone)]
+pub enum Catch {
+    Catch(u32, u32),
+    CatchRef(u32, u32),
+    CatchAll(u32),
+    CatchAllRef(u32),
 }
 
 #[derive(Debug, Copy, Clone)]
 pub struct MemoryArg {
     pub align: u32,
-    pub offset: u32,
+    pub offset: u64,
+    pub memory: u32,
 }
 
 /// https://webassembly.github.io/spec/core/syntax/instructions.html#expressions
@@ -73,16 +195,64 @@ pub enum Instruction {
     Block(BlockType, Vec<Instruction>),
     Loop(BlockType, Vec<Instruction>),
     If(BlockType, Vec<Instruction>, Option<Vec<Instruction>>),
+    TryTable(BlockType, Vec<Catch>, Vec<Instruction>),
+    Throw(u32),
+    ThrowRef,
     Br(u32),
     BrIf(u32),
     BrTable(Vec<u32>, u32),
     Return,
     Call(u32),
-    CallIndirect(u32),
+    CallIndirect(u32, u32),
+    CallRef(u32),
+    ReturnCall(u32),
+    ReturnCallIndirect(u32, u32),
+    ReturnCallRef(u32),
+    BrOnNull(u32),
+    BrOnNonNull(u32),
+    BrOnCast(u32, RefType, RefType),
+    BrOnCastFail(u32, RefType, RefType),
+
+    // Reference instructions
+    RefNull(HeapType),
+    RefIsNull,
+    RefFunc(u32),
+    RefAsNonNull,
+    RefEq,
+    RefTest(RefType),
+    RefCast(RefType),
+    RefI31,
+    I31GetS,
+    I31GetU,
+    AnyConvertExtern,
+    ExternConvertAny,
+
+    // Aggregate instructions
+    StructNew(u32),
+    StructNewDefault(u32),
+    StructGet(u32, u32),
+    StructGetS(u32, u32),
+    StructGetU(u32, u32),
+    StructSet(u32, u32),
+    ArrayNew(u32),
+    ArrayNewDefault(u32),
+    ArrayNewFixed(u32, u32),
+    ArrayNewData(u32, u32),
+    ArrayNewElem(u32, u32),
+    ArrayGet(u32),
+    ArrayGetS(u32),
+    ArrayGetU(u32),
+    ArraySet(u32),
+    ArrayLen,
+    ArrayFill(u32),
+    ArrayCopy(u32, u32),
+    ArrayInitData(u32, u32),
+    ArrayInitElem(u32, u32),
 
     // Parametric instructions
     Drop,
     Select,
+    SelectTyped(Vec<ValueType>),
 
     // Variable instructions
     LocalGet(u32),
@@ -115,8 +285,22 @@ pub enum Instruction {
     I64Store8(MemoryArg),
     I64Store16(MemoryArg),
     I64Store32(MemoryArg),
-    MemorySize,
-    MemoryGrow,
+    MemorySize(u32),
+    MemoryGrow(u32),
+    MemoryInit { memory: u32, data: u32 },
+    DataDrop(u32),
+    MemoryCopy(u32, u32),
+    MemoryFill(u32),
+
+    // Table instructions
+    TableGet(u32),
+    TableSet(u32),
+    TableSize(u32),
+    TableGrow(u32),
+    TableFill(u32),
+    TableInit { table: u32, element: u32 },
+    ElemDrop(u32),
+    TableCopy(u32, u32),
 
     // Constants instructions
     I32Const(i32),
@@ -254,10 +438,364 @@ pub enum Instruction {
     I64ReinterpretF64,
     F32ReinterpretI32,
     F64ReinterpretI64,
+
+    // Sign extension operators
+    I32Extend8S,
+    I32Extend16S,
+    I64Extend8S,
+    I64Extend16S,
+    I64Extend32S,
+
+    // Saturating truncations
+    I32TruncSatSF32,
+    I32TruncSatUF32,
+    I32TruncSatSF64,
+    I32TruncSatUF64,
+    I64TruncSatSF32,
+    I64TruncSatUF32,
+    I64TruncSatSF64,
+    I64TruncSatUF64,
+
+    // Vector instructions
+    V128Load(MemoryArg),
+    V128Load8x8S(MemoryArg),
+    V128Load8x8U(MemoryArg),
+    V128Load16x4S(MemoryArg),
+    V128Load16x4U(MemoryArg),
+    V128Load32x2S(MemoryArg),
+    V128Load32x2U(MemoryArg),
+    V128Load8Splat(MemoryArg),
+    V128Load16Splat(MemoryArg),
+    V128Load32Splat(MemoryArg),
+    V128Load64Splat(MemoryArg),
+    V128Store(MemoryArg),
+    V128Const(u128),
+    I8x16Shuffle([u8; 16]),
+    I8x16Swizzle,
+    I8x16Splat,
+    I16x8Splat,
+    I32x4Splat,
+    I64x2Splat,
+    F32x4Splat,
+    F64x2Splat,
+    I8x16ExtractLaneS(u8),
+    I8x16ExtractLaneU(u8),
+    I8x16ReplaceLane(u8),
+    I16x8ExtractLaneS(u8),
+    I16x8ExtractLaneU(u8),
+    I16x8ReplaceLane(u8),
+    I32x4ExtractLane(u8),
+    I32x4ReplaceLane(u8),
+    I64x2ExtractLane(u8),
+    I64x2ReplaceLane(u8),
+    F32x4ExtractLane(u8),
+    F32x4ReplaceLane(u8),
+    F64x2ExtractLane(u8),
+    F64x2ReplaceLane(u8),
+    I8x16Eq,
+    I8x16Ne,
+    I8x16LtS,
+    I8x16LtU,
+    I8x16GtS,
+    I8x16GtU,
+    I8x16LeS,
+    I8x16LeU,
+    I8x16GeS,
+    I8x16GeU,
+    I16x8Eq,
+    I16x8Ne,
+    I16x8LtS,
+    I16x8LtU,
+    I16x8GtS,
+    I16x8GtU,
+    I16x8LeS,
+    I16x8LeU,
+    I16x8GeS,
+    I16x8GeU,
+    I32x4Eq,
+    I32x4Ne,
+    I32x4LtS,
+    I32x4LtU,
+    I32x4GtS,
+    I32x4GtU,
+    I32x4LeS,
+    I32x4LeU,
+    I32x4GeS,
+    I32x4GeU,
+    F32x4Eq,
+    F32x4Ne,
+    F32x4Lt,
+    F32x4Gt,
+    F32x4Le,
+    F32x4Ge,
+    F64x2Eq,
+    F64x2Ne,
+    F64x2Lt,
+    F64x2Gt,
+    F64x2Le,
+    F64x2Ge,
+    V128Not,
+    V128And,
+    V128AndNot,
+    V128Or,
+    V128Xor,
+    V128Bitselect,
+    V128AnyTrue,
+    V128Load8Lane(MemoryArg, u8),
+    V128Load16Lane(MemoryArg, u8),
+    V128Load32Lane(MemoryArg, u8),
+    V128Load64Lane(MemoryArg, u8),
+    V128Store8Lane(MemoryArg, u8),
+    V128Store16Lane(MemoryArg, u8),
+    V128Store32Lane(MemoryArg, u8),
+    V128Store64Lane(MemoryArg, u8),
+    V128Load32Zero(MemoryArg),
+    V128Load64Zero(MemoryArg),
+    F32x4DemoteF64x2Zero,
+    F64x2PromoteLowF32x4,
+    I8x16Abs,
+    I8x16Neg,
+    I8x16Popcnt,
+    I8x16AllTrue,
+    I8x16Bitmask,
+    I8x16NarrowI16x8S,
+    I8x16NarrowI16x8U,
+    F32x4Ceil,
+    F32x4Floor,
+    F32x4Trunc,
+    F32x4Nearest,
+    I8x16Shl,
+    I8x16ShrS,
+    I8x16ShrU,
+    I8x16Add,
+    I8x16AddSatS,
+    I8x16AddSatU,
+    I8x16Sub,
+    I8x16SubSatS,
+    I8x16SubSatU,
+    F64x2Ceil,
+    F64x2Floor,
+    I8x16MinS,
+    I8x16MinU,
+    I8x16MaxS,
+    I8x16MaxU,
+    F64x2Trunc,
+    I8x16AvgrU,
+    I16x8ExtAddPairwiseI8x16S,
+    I16x8ExtAddPairwiseI8x16U,
+    I32x4ExtAddPairwiseI16x8S,
+    I32x4ExtAddPairwiseI16x8U,
+    I16x8Abs,
+    I16x8Neg,
+    I16x8Q15MulrSatS,
+    I16x8AllTrue,
+    I16x8Bitmask,
+    I16x8NarrowI32x4S,
+    I16x8NarrowI32x4U,
+    I16x8ExtendLowI8x16S,
+    I16x8ExtendHighI8x16S,
+    I16x8ExtendLowI8x16U,
+    I16x8ExtendHighI8x16U,
+    I16x8Shl,
+    I16x8ShrS,
+    I16x8ShrU,
+    I16x8Add,
+    I16x8AddSatS,
+    I16x8AddSatU,
+    I16x8Sub,
+    I16x8SubSatS,
+    I16x8SubSatU,
+    F64x2Nearest,
+    I16x8Mul,
+    I16x8MinS,
+    I16x8MinU,
+    I16x8MaxS,
+    I16x8MaxU,
+    I16x8AvgrU,
+    I16x8ExtMulLowI8x16S,
+    I16x8ExtMulHighI8x16S,
+    I16x8ExtMulLowI8x16U,
+    I16x8ExtMulHighI8x16U,
+    I32x4Abs,
+    I32x4Neg,
+    I32x4AllTrue,
+    I32x4Bitmask,
+    I32x4ExtendLowI16x8S,
+    I32x4ExtendHighI16x8S,
+    I32x4ExtendLowI16x8U,
+    I32x4ExtendHighI16x8U,
+    I32x4Shl,
+    I32x4ShrS,
+    I32x4ShrU,
+    I32x4Add,
+    I32x4Sub,
+    I32x4Mul,
+    I32x4MinS,
+    I32x4MinU,
+    I32x4MaxS,
+    I32x4MaxU,
+    I32x4DotI16x8S,
+    I32x4ExtMulLowI16x8S,
+    I32x4ExtMulHighI16x8S,
+    I32x4ExtMulLowI16x8U,
+    I32x4ExtMulHighI16x8U,
+    I64x2Abs,
+    I64x2Neg,
+    I64x2AllTrue,
+    I64x2Bitmask,
+    I64x2ExtendLowI32x4S,
+    I64x2ExtendHighI32x4S,
+    I64x2ExtendLowI32x4U,
+    I64x2ExtendHighI32x4U,
+    I64x2Shl,
+    I64x2ShrS,
+    I64x2ShrU,
+    I64x2Add,
+    I64x2Sub,
+    I64x2Mul,
+    I64x2Eq,
+    I64x2Ne,
+    I64x2LtS,
+    I64x2GtS,
+    I64x2LeS,
+    I64x2GeS,
+    I64x2ExtMulLowI32x4S,
+    I64x2ExtMulHighI32x4S,
+    I64x2ExtMulLowI32x4U,
+    I64x2ExtMulHighI32x4U,
+    F32x4Abs,
+    F32x4Neg,
+    F32x4Sqrt,
+    F32x4Add,
+    F32x4Sub,
+    F32x4Mul,
+    F32x4Div,
+    F32x4Min,
+    F32x4Max,
+    F32x4PMin,
+    F32x4PMax,
+    F64x2Abs,
+    F64x2Neg,
+    F64x2Sqrt,
+    F64x2Add,
+    F64x2Sub,
+    F64x2Mul,
+    F64x2Div,
+    F64x2Min,
+    F64x2Max,
+    F64x2PMin,
+    F64x2PMax,
+    I32x4TruncSatF32x4S,
+    I32x4TruncSatF32x4U,
+    F32x4ConvertI32x4S,
+    F32x4ConvertI32x4U,
+    I32x4TruncSatF64x2SZero,
+    I32x4TruncSatF64x2UZero,
+    F64x2ConvertLowI32x4S,
+    F64x2ConvertLowI32x4U,
+
+    // Relaxed vector instructions
+    //
+    // Their results may depend on the platform for some inputs, such as NaNs, out of range
+    // conversions or non-boolean lane masks. Execution always picks the results of the matching
+    // non-relaxed instructions: swizzle, saturating truncations, unfused multiply and add,
+    // bitselect, min and max, and q15mulr_sat. The dot products treat their second operand as
+    // signed.
+    //
+    // https://github.com/WebAssembly/relaxed-simd/blob/main/proposals/relaxed-simd/Overview.md
+    I8x16RelaxedSwizzle,
+    I32x4RelaxedTruncF32x4S,
+    I32x4RelaxedTruncF32x4U,
+    I32x4RelaxedTruncF64x2SZero,
+    I32x4RelaxedTruncF64x2UZero,
+    F32x4RelaxedMadd,
+    F32x4RelaxedNmadd,
+    F64x2RelaxedMadd,
+    F64x2RelaxedNmadd,
+    I8x16RelaxedLaneselect,
+    I16x8RelaxedLaneselect,
+    I32x4RelaxedLaneselect,
+    I64x2RelaxedLaneselect,
+    F32x4RelaxedMin,
+    F32x4RelaxedMax,
+    F64x2RelaxedMin,
+    F64x2RelaxedMax,
+    I16x8RelaxedQ15MulrS,
+    I16x8RelaxedDotI8x16I7x16S,
+    I32x4RelaxedDotI8x16I7x16AddS,
+
+    // Atomic instructions
+    MemoryAtomicNotify(MemoryArg),
+    MemoryAtomicWait32(MemoryArg),
+    MemoryAtomicWait64(MemoryArg),
+    AtomicFence,
+    I32AtomicLoad(MemoryArg),
+    I64AtomicLoad(MemoryArg),
+    I32AtomicLoad8U(MemoryArg),
+    I32AtomicLoad16U(MemoryArg),
+    I64AtomicLoad8U(MemoryArg),
+    I64AtomicLoad16U(MemoryArg),
+    I64AtomicLoad32U(MemoryArg),
+    I32AtomicStore(MemoryArg),
+    I64AtomicStore(MemoryArg),
+    I32AtomicStore8(MemoryArg),
+    I32AtomicStore16(MemoryArg),
+    I64AtomicStore8(MemoryArg),
+    I64AtomicStore16(MemoryArg),
+    I64AtomicStore32(MemoryArg),
+    I32AtomicRmwAdd(MemoryArg),
+    I64AtomicRmwAdd(MemoryArg),
+    I32AtomicRmw8AddU(MemoryArg),
+    I32AtomicRmw16AddU(MemoryArg),
+    I64AtomicRmw8AddU(MemoryArg),
+    I64AtomicRmw16AddU(MemoryArg),
+    I64AtomicRmw32AddU(MemoryArg),
+    I32AtomicRmwSub(MemoryArg),
+    I64AtomicRmwSub(MemoryArg),
+    I32AtomicRmw8SubU(MemoryArg),
+    I32AtomicRmw16SubU(MemoryArg),
+    I64AtomicRmw8SubU(MemoryArg),
+    I64AtomicRmw16SubU(MemoryArg),
+    I64AtomicRmw32SubU(MemoryArg),
+    I32AtomicRmwAnd(MemoryArg),
+    I64AtomicRmwAnd(MemoryArg),
+    I32AtomicRmw8AndU(MemoryArg),
+    I32AtomicRmw16AndU(MemoryArg),
+    I64AtomicRmw8AndU(MemoryArg),
+    I64AtomicRmw16AndU(MemoryArg),
+    I64AtomicRmw32AndU(MemoryArg),
+    I32AtomicRmwOr(MemoryArg),
+    I64AtomicRmwOr(MemoryArg),
+    I32AtomicRmw8OrU(MemoryArg),
+    I32AtomicRmw16OrU(MemoryArg),
+    I64AtomicRmw8OrU(MemoryArg),
+    I64AtomicRmw16OrU(MemoryArg),
+    I64AtomicRmw32OrU(MemoryArg),
+    I32AtomicRmwXor(MemoryArg),
+    I64AtomicRmwXor(MemoryArg),
+    I32AtomicRmw8XorU(MemoryArg),
+    I32AtomicRmw16XorU(MemoryArg),
+    I64AtomicRmw8XorU(MemoryArg),
+    I64AtomicRmw16XorU(MemoryArg),
+    I64AtomicRmw32XorU(MemoryArg),
+    I32AtomicRmwXchg(MemoryArg),
+    I64AtomicRmwXchg(MemoryArg),
+    I32AtomicRmw8XchgU(MemoryArg),
+    I32AtomicRmw16XchgU(MemoryArg),
+    I64AtomicRmw8XchgU(MemoryArg),
+    I64AtomicRmw16XchgU(MemoryArg),
+    I64AtomicRmw32XchgU(MemoryArg),
+    I32AtomicRmwCmpxchg(MemoryArg),
+    I64AtomicRmwCmpxchg(MemoryArg),
+    I32AtomicRmw8CmpxchgU(MemoryArg),
+    I32AtomicRmw16CmpxchgU(MemoryArg),
+    I64AtomicRmw8CmpxchgU(MemoryArg),
+    I64AtomicRmw16CmpxchgU(MemoryArg),
+    I64AtomicRmw32CmpxchgU(MemoryArg),
 }
 
 /// https://webassembly.github.io/spec/core/syntax/modules.html#functions
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Function {
     pub function_type: u32,
     pub locals: Vec<(u32, ValueType)>,
@@ -268,6 +806,7 @@ pub struct Function {
 #[derive(Debug)]
 pub struct Table {
     pub table_type: TableType,
+    pub init: Option<Expression>,
 }
 
 /// https://webassembly.github.io/spec/core/syntax/modules.html#memories
@@ -276,6 +815,12 @@ pub struct Memory {
     pub memory_type: MemoryType,
 }
 
+/// https://webassembly.github.io/exception-handling/core/syntax/modules.html#tags
+#[derive(Debug)]
+pub struct Tag {
+    pub tag_type: u32,
+}
+
 /// https://webassembly.github.io/spec/core/syntax/modules.html#globals
 #[derive(Debug)]
 pub struct Global {
@@ -286,18 +831,31 @@ pub struct Global {
 /// https://webassembly.github.io/spec/core/syntax/modules.html#element-segments
 #[derive(Debug)]
 pub struct Element {
-    pub table: u32,
-    pub offset: Expression,
-    pub init: Vec<u32>,
+    pub element_type: RefType,
+    pub mode: ElementMode,
+    pub init: Vec<Expression>,
+}
+/// Active segments are copied into a table at instantiation, passive ones by table.init, and
+/// declarative ones only declare the functions referenced by ref.func.
+#[derive(Debug)]
+pub enum ElementMode {
+    Passive,
+    Active { table: u32, offset: Expression },
+    Declarative,
 }
 
 /// https://webassembly.github.io/spec/core/syntax/modules.html#data-segments
 #[derive(Debug)]
 pub struct Data {
-    pub data: u32,
-    pub offset: Expression,
+    pub mode: DataMode,
     pub init: Vec<u8>,
 }
+/// Active segments are copied into a memory at instantiation, passive ones by memory.init.
+#[derive(Debug)]
+pub enum DataMode {
+    Passive,
+    Active { memory: u32, offset: Expression },
+}
 
 /// https://webassembly.github.io/spec/core/syntax/modules.html#start-function
 #[derive(Debug)]
@@ -306,17 +864,18 @@ pub struct StartFunction {
 }
 
 /// https://webassembly.github.io/spec/core/syntax/modules.html#exports
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Export {
     pub name: String,
     pub descriptor: ExportDescriptor,
 }
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone)]
 pub enum ExportDescriptor {
     Function(u32),
     Table(u32),
     Memory(u32),
-    Global(u32)
+    Global(u32),
+    Tag(u32)
 }
 
 /// https://webassembly.github.io/spec/core/syntax/modules.html#imports
@@ -331,7 +890,8 @@ pub enum ImportDescriptor {
     Function(u32),
     Table(TableType),
     Memory(MemoryType),
-    Global(GlobalType)
+    Global(GlobalType),
+    Tag(u32)
 }
 
 pub type CustomSection<'a> = (String, &'a [u8]);
@@ -340,13 +900,15 @@ pub type CustomSection<'a> = (String, &'a [u8]);
 #[derive(Debug)]
 pub struct Module<'a> {
     pub custom_sections: Vec<CustomSection<'a>>,
-    pub function_types: Vec<FunctionType>,
+    pub types: Vec<RecursiveType>,
     pub functions: Vec<Function>,
     pub tables: Vec<Table>,
     pub memories: Vec<Memory>,
+    pub tags: Vec<Tag>,
     pub globals: Vec<Global>,
     pub elements: Vec<Element>,
     pub data: Vec<Data>,
+    pub data_count: Option<u32>,
     pub start: Option<StartFunction>,
     pub imports: Vec<Import>,
     pub exports: Vec<Export>,
@@ -46,6 +46,42 @@ pub enum Command {
     Register(CommandRegister),
 }
 
+impl Command {
+    pub fn get_type(&self) -> &'static str {
+        match self {
+            Command::Action(_) => "action",
+            Command::AssertExhaustion(_) => "assert_exhaustion",
+            Command::AssertInvalid(_) => "assert_invalid",
+            Command::AssertMalformed(_) => "assert_malformed",
+            Command::AssertTrap(_) => "assert_trap",
+            Command::AssertUninstantiable(_) => "assert_uninstantiable",
+            Command::AssertUnlinkable(_) => "assert_unlinkable",
+            Command::AssertReturn(_) => "assert_return",
+            Command::AssertReturnArithmeticNan(_) => "assert_return_arithmetic_nan",
+            Command::AssertReturnCanonicalNan(_) => "assert_return_canonical_nan",
+            Command::Module(_) => "module",
+            Command::Register(_) => "register",
+        }
+    }
+
+    pub fn get_line(&self) -> u32 {
+        match self {
+            Command::Action(command) => command.line,
+            Command::AssertExhaustion(command) => command.line,
+            Command::AssertInvalid(command) => command.line,
+            Command::AssertMalformed(command) => command.line,
+            Command::AssertTrap(command) => command.line,
+            Command::AssertUninstantiable(command) => command.line,
+            Command::AssertUnlinkable(command) => command.line,
+            Command::AssertReturn(command) => command.line,
+            Command::AssertReturnArithmeticNan(command) => command.line,
+            Command::AssertReturnCanonicalNan(command) => command.line,
+            Command::Module(command) => command.line,
+            Command::Register(command) => command.line,
+        }
+    }
+}
+
 #[derive(Deserialize, Debug)]
 pub struct CommandAction {
     pub line: u32,
@@ -121,6 +157,7 @@ pub struct CommandAssertMalformed {
 #[derive(Deserialize, Debug)]
 pub struct CommandModule {
     pub line: u32,
+    pub name: Option<String>,
     pub filename: String,
 }
 
@@ -136,7 +173,11 @@ pub struct CommandRegister {
 #[serde(tag = "type")]
 pub enum Action {
     #[serde(rename = "invoke")]
-    Invoke { field: String, args: Vec<Value> },
+    Invoke {
+        field: String,
+        module: Option<String>,
+        args: Vec<Value>,
+    },
 
     #[serde(rename = "get")]
     Get {
@@ -1,17 +1,58 @@
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::fs::File;
 use std::io::BufReader;
-use std::path::Path;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use colored::*;
 
 mod manifest;
-use manifest::{Command, CommandAssertMalformed, CommandModule, Manifest};
+use manifest::{
+    Action, Command, CommandAction, CommandAssertInvalid, CommandAssertMalformed, CommandModule,
+    CommandRegister, Manifest,
+};
+
+mod report;
+
+mod spectest;
+
+mod values;
+use values::{ExpectedValue, NanKind};
 
 use crate::decoder::modules::decode;
+use crate::execution::instance::{
+    instantiate, ExternalValue, InstantiationError, InstantiationErrorKind,
+};
+use crate::execution::store::{ModuleAddress, Store};
+use crate::execution::trap::{ExecutionResult, Trap, TrapKind};
+use crate::execution::values::Value;
+use crate::structure::Module;
+use crate::validation::modules::validate;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Junit,
+    Tap,
+}
+
+/// Selects the commands of a suite, named after its wast file, or only the command at `line`.
+pub struct Filter {
+    pub suite: String,
+    pub line: Option<u32>,
+}
 
 pub struct RunnerConfig {
     pub dirname: String,
+    pub filters: Vec<Filter>,
+    pub expected_failures: Option<String>,
+    pub format: OutputFormat,
+    /// Executions of a command running longer than the timeout trap.
+    pub timeout: Option<Duration>,
 }
 
 #[derive(Debug)]
@@ -40,147 +81,517 @@ impl TestResult {
             state: TestState::Fail { message },
         }
     }
-
-    fn ignore(test_name: String, file_name: String, line: u32) -> TestResult {
-        TestResult {
-            test_name,
-            line,
-            file_name,
-            state: TestState::Ignore,
-        }
-    }
 }
 
 #[derive(Debug, PartialEq)]
 enum TestState {
     Pass,
     Fail { message: String },
-    Ignore,
 }
 
+/// State of a test compared to the expected failures. Only failures and unexpected passes fail
+/// the run.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Outcome {
+    Pass,
+    Fail,
+    ExpectedFailure,
+    UnexpectedPass,
+}
+
+impl Outcome {
+    fn new(state: &TestState, is_expected_failure: bool) -> Outcome {
+        match (state, is_expected_failure) {
+            (TestState::Pass, false) => Outcome::Pass,
+            (TestState::Pass, true) => Outcome::UnexpectedPass,
+            (TestState::Fail { .. }, false) => Outcome::Fail,
+            (TestState::Fail { .. }, true) => Outcome::ExpectedFailure,
+        }
+    }
+
+    fn is_failing(&self) -> bool {
+        *self == Outcome::Fail || *self == Outcome::UnexpectedPass
+    }
+}
+
+struct CommandReport {
+    command_type: &'static str,
+    result: TestResult,
+    outcome: Outcome,
+}
+
+struct SuiteReport {
+    file_name: String,
+    name: String,
+    commands: Vec<CommandReport>,
+}
+
+// Expected failures are identified by the name of their suite and the line of their command.
+type ExpectedFailures = HashSet<(String, u32)>;
+
+// Manifests are kept with their path, which names their suite when they can't be read.
+type ManifestEntry = (PathBuf, Result<Manifest, String>);
+
+enum Selection {
+    Suite,
+    Commands(Vec<u32>),
+}
+
+/// Runtime state shared by the commands of a suite. Actions without a module name target the
+/// last instantiated module, and modules import from the registered ones.
+struct SuiteContext {
+    store: Store,
+    current_module: Option<ModuleAddress>,
+    named_modules: HashMap<String, ModuleAddress>,
+    registered_modules: HashMap<String, ModuleAddress>,
+}
+
+impl SuiteContext {
+    fn new() -> SuiteContext {
+        let mut store = Store::new();
+        let mut registered_modules = HashMap::new();
+        registered_modules.insert(
+            String::from("spectest"),
+            spectest::instantiate_spectest(&mut store),
+        );
+
+        SuiteContext {
+            store,
+            current_module: None,
+            named_modules: HashMap::new(),
+            registered_modules,
+        }
+    }
+
+    fn get_module(&self, name: &Option<String>) -> Result<ModuleAddress, String> {
+        match name {
+            Some(name) => self
+                .named_modules
+                .get(name)
+                .copied()
+                .ok_or(format!("Unknown module {}", name)),
+            None => self
+                .current_module
+                .ok_or(String::from("No module instantiated")),
+        }
+    }
+}
+
+/// Runs the selected suites and prints their report. Returns the exit code of the run: 1 when a
+/// test fails or passes unexpectedly, and 2 when the run can't be configured.
 pub fn run(config: &RunnerConfig) -> i32 {
-    let manifests = get_manifests(config);
+    let expected_failures = match &config.expected_failures {
+        Some(path) => match read_expected_failures(path) {
+            Ok(expected_failures) => expected_failures,
+            Err(message) => {
+                eprintln!("{}", message);
+                return 2;
+            }
+        },
+        None => ExpectedFailures::new(),
+    };
+
+    let manifests = match get_manifests(config) {
+        Ok(manifests) => manifests,
+        Err(message) => {
+            eprintln!("{}", message);
+            return 2;
+        }
+    };
 
-    let report: Vec<(Manifest, Vec<TestResult>)> = manifests
+    let report: Vec<SuiteReport> = manifests
         .into_iter()
-        .map(|manifest| {
-            let results = run_suite(&manifest, config);
-            (manifest, results)
+        .filter_map(|(path, manifest)| {
+            let name = match &manifest {
+                Ok(manifest) => get_suite_name(manifest),
+                Err(_) => get_file_stem(&path),
+            };
+            let selection = get_selection(&config.filters, &name)?;
+            Some(match manifest {
+                Ok(manifest) => run_suite(&manifest, name, &selection, &expected_failures, config),
+                Err(message) => {
+                    report_manifest_error(&path, name, message, &expected_failures, config)
+                }
+            })
         })
         .collect();
 
-    print_report(&report);
+    if report.iter().all(|suite| suite.commands.is_empty()) {
+        eprintln!("No test matches the filters");
+        return 2;
+    }
+
+    match config.format {
+        OutputFormat::Text => report::print_text(&report),
+        OutputFormat::Json => report::print_json(&report),
+        OutputFormat::Junit => report::print_junit(&report),
+        OutputFormat::Tap => report::print_tap(&report),
+    }
 
-    report
+    let is_failing = report
         .iter()
-        .flat_map(|(_, results)| results)
-        .filter(|r| match &r.state {
-            TestState::Fail { message: _message } => true,
-            _ => false,
-        })
-        .count() as i32
-}
-
-fn get_manifests(config: &RunnerConfig) -> Vec<Manifest> {
-    let mut manifests: Vec<Manifest> = fs::read_dir(config.dirname.clone())
-        .unwrap()
-        .map(|f| f.unwrap().path())
-        .filter(|f| f.extension().unwrap() == "json")
-        .map(|f| {
-            let reader = BufReader::new(File::open(f).unwrap());
-            let manifest: Manifest = serde_json::from_reader(reader).unwrap();
-            manifest
+        .flat_map(|suite| &suite.commands)
+        .any(|command| command.outcome.is_failing());
+    if is_failing {
+        1
+    } else {
+        0
+    }
+}
+
+// Each line holds a `<suite>:<line>` entry, comments starting with `#`.
+fn read_expected_failures(path: &str) -> Result<ExpectedFailures, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|err| format!("Unable to read expected failures {}: {}", path, err))?;
+
+    let mut expected_failures = ExpectedFailures::new();
+    for (index, line) in content.lines().enumerate() {
+        let entry = line.split('#').next().unwrap_or("").trim();
+        if entry.is_empty() {
+            continue;
+        }
+
+        let (suite, line) = entry
+            .split_once(':')
+            .and_then(|(suite, line)| Some((suite, line.parse::<u32>().ok()?)))
+            .ok_or(format!(
+                "Invalid expected failure \"{}\" ({}:{})",
+                entry,
+                path,
+                index + 1
+            ))?;
+        expected_failures.insert((String::from(suite), line));
+    }
+
+    Ok(expected_failures)
+}
+
+fn get_file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn get_suite_name(manifest: &Manifest) -> String {
+    get_file_stem(Path::new(&manifest.source_filename))
+}
+
+fn get_selection(filters: &[Filter], suite: &str) -> Option<Selection> {
+    if filters.is_empty() {
+        return Some(Selection::Suite);
+    }
+
+    let mut lines = Vec::new();
+    for filter in filters.iter().filter(|filter| filter.suite == suite) {
+        match filter.line {
+            Some(line) => lines.push(line),
+            None => return Some(Selection::Suite),
+        }
+    }
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(Selection::Commands(lines))
+    }
+}
+
+// Only an unreadable directory fails the run, manifests that can't be read or parsed being
+// reported as failing suites.
+fn get_manifests(config: &RunnerConfig) -> Result<Vec<ManifestEntry>, String> {
+    let directory_error =
+        |err| format!("Unable to read test directory {}: {}", config.dirname, err);
+
+    let mut paths = fs::read_dir(&config.dirname)
+        .map_err(directory_error)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<PathBuf>, _>>()
+        .map_err(directory_error)?;
+    paths.retain(|path| {
+        path.extension()
+            .is_some_and(|extension| extension == "json")
+    });
+    paths.sort();
+
+    Ok(paths
+        .into_iter()
+        .map(|path| {
+            let manifest = read_manifest(&path);
+            (path, manifest)
         })
-        .collect();
+        .collect())
+}
+
+fn read_manifest(path: &Path) -> Result<Manifest, String> {
+    let file = File::open(path)
+        .map_err(|err| format!("Unable to read manifest {}: {}", path.display(), err))?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|err| format!("Unable to parse manifest {}: {}", path.display(), err))
+}
+
+// The manifest is reported as a single failing command, which can be expected to fail at line 0.
+fn report_manifest_error(
+    path: &Path,
+    name: String,
+    message: String,
+    expected_failures: &ExpectedFailures,
+    config: &RunnerConfig,
+) -> SuiteReport {
+    let file_name = path
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let result = TestResult::fail(
+        format!("Manifest: {}", file_name),
+        String::from(""),
+        0,
+        message,
+    );
 
-    manifests.sort_by(|a, b| a.source_filename.cmp(&b.source_filename));
+    let is_expected_failure = expected_failures.contains(&(name.clone(), 0));
+    let command = CommandReport {
+        command_type: "manifest",
+        outcome: Outcome::new(&result.state, is_expected_failure),
+        result,
+    };
+    if config.format == OutputFormat::Text {
+        println!("{}", file_name.bold());
+        report::print_text_command(&command);
+    }
 
-    manifests
+    SuiteReport {
+        file_name,
+        name,
+        commands: vec![command],
+    }
 }
 
-fn run_suite(manifest: &Manifest, config: &RunnerConfig) -> Vec<TestResult> {
-    println!("{}", manifest.source_filename.bold());
+fn get_panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message
+    } else {
+        "unknown panic"
+    }
+}
 
-    manifest
+// Selected commands still depend on the modules of the previous ones, so every command up to the
+// last selected one is run.
+fn run_suite(
+    manifest: &Manifest,
+    name: String,
+    selection: &Selection,
+    expected_failures: &ExpectedFailures,
+    config: &RunnerConfig,
+) -> SuiteReport {
+    let file_name = Path::new(&manifest.source_filename)
+        .file_name()
+        .map(|file_name| file_name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let (last_line, lines) = match selection {
+        Selection::Suite => (u32::MAX, None),
+        Selection::Commands(lines) => (lines.iter().copied().max().unwrap_or(0), Some(lines)),
+    };
+
+    if config.format == OutputFormat::Text {
+        println!("{}", file_name.bold());
+    }
+
+    let mut context = SuiteContext::new();
+
+    let commands = manifest
         .commands
         .iter()
         .enumerate()
-        .map(|(index, command)| {
-            let result: TestResult = match command {
-                Command::Module(command) => test_module_instantiation(command, index, config),
+        .take_while(|(_, command)| command.get_line() <= last_line)
+        .filter_map(|(index, command)| {
+            context.store.deadline = config.timeout.map(|timeout| Instant::now() + timeout);
+
+            // A panic only fails its command, the state of the suite being kept as it is.
+            let result = panic::catch_unwind(AssertUnwindSafe(|| match command {
+                Command::Module(command) => {
+                    test_module_instantiation(command, index, config, &mut context)
+                }
                 Command::AssertMalformed(command) => test_module_malformed(command, index, config),
 
-                Command::Action(command) => {
-                    TestResult::ignore(format!("#{} Action", index), String::from(""), command.line)
-                }
+                Command::Action(command) => test_action(command, index, &mut context),
 
-                Command::AssertExhaustion(command) => TestResult::ignore(
+                Command::AssertExhaustion(command) => test_action_trap(
                     format!("#{} Exhaustion: {}", index, command.text),
-                    String::from(""),
                     command.line,
+                    &command.action,
+                    &command.text,
+                    &mut context,
                 ),
 
-                Command::AssertInvalid(command) => TestResult::ignore(
-                    format!("#{} Invalid module: {}", index, command.text),
-                    command.filename.clone(),
-                    command.line,
-                ),
+                Command::AssertInvalid(command) => test_module_invalid(command, index, config),
 
-                Command::AssertTrap(command) => TestResult::ignore(
+                Command::AssertTrap(command) => test_action_trap(
                     format!("#{} Trap: {}", index, command.text),
-                    String::from(""),
                     command.line,
+                    &command.action,
+                    &command.text,
+                    &mut context,
                 ),
 
-                Command::AssertUninstantiable(command) => TestResult::ignore(
-                    format!("#{} Uninstantiable  module: {}", index, command.text),
-                    command.filename.clone().unwrap_or(String::from("")),
+                Command::AssertUninstantiable(command) => test_module_instantiation_error(
+                    format!("#{} Uninstantiable module: {}", index, command.text),
                     command.line,
+                    &command.filename,
+                    &command.text,
+                    InstantiationErrorKind::Uninstantiable,
+                    config,
+                    &mut context,
                 ),
 
-                Command::AssertUnlinkable(command) => TestResult::ignore(
+                Command::AssertUnlinkable(command) => test_module_instantiation_error(
                     format!("#{} Unlinkable module: {}", index, command.text),
-                    command.filename.clone().unwrap_or(String::from("")),
                     command.line,
+                    &command.filename,
+                    &command.text,
+                    InstantiationErrorKind::Unlinkable,
+                    config,
+                    &mut context,
                 ),
 
                 Command::AssertReturn(command) => {
-                    TestResult::ignore(format!("#{} Return", index), String::from(""), command.line)
+                    let expected = command
+                        .expected
+                        .iter()
+                        .map(values::parse_expected_value)
+                        .collect();
+                    test_action_results(
+                        format!("#{} Return: {}", index, get_field(&command.action)),
+                        command.line,
+                        &command.action,
+                        expected,
+                        &mut context,
+                    )
                 }
 
-                Command::AssertReturnArithmeticNan(command) => TestResult::ignore(
-                    format!("#{} Return arithmetic NaN", index),
-                    String::from(""),
-                    command.line,
-                ),
+                Command::AssertReturnArithmeticNan(command) => {
+                    let expected = command
+                        .expected
+                        .iter()
+                        .map(|value| values::parse_expected_nan(value, NanKind::Arithmetic))
+                        .collect();
+                    test_action_results(
+                        format!(
+                            "#{} Return arithmetic NaN: {}",
+                            index,
+                            get_field(&command.action)
+                        ),
+                        command.line,
+                        &command.action,
+                        expected,
+                        &mut context,
+                    )
+                }
 
-                Command::AssertReturnCanonicalNan(command) => TestResult::ignore(
-                    format!("#{} Return canonical NaN", index),
-                    String::from(""),
-                    command.line,
-                ),
+                Command::AssertReturnCanonicalNan(command) => {
+                    let expected = command
+                        .expected
+                        .iter()
+                        .map(|value| values::parse_expected_nan(value, NanKind::Canonical))
+                        .collect();
+                    test_action_results(
+                        format!(
+                            "#{} Return canonical NaN: {}",
+                            index,
+                            get_field(&command.action)
+                        ),
+                        command.line,
+                        &command.action,
+                        expected,
+                        &mut context,
+                    )
+                }
 
-                Command::Register(command) => TestResult::ignore(
-                    format!("#{} Register", index),
+                Command::Register(command) => test_register(command, index, &mut context),
+            }))
+            .unwrap_or_else(|payload| {
+                TestResult::fail(
+                    format!("#{} {}", index, command.get_type()),
                     String::from(""),
-                    command.line,
-                ),
-            };
+                    command.get_line(),
+                    format!("Panicked: {}", get_panic_message(payload.as_ref())),
+                )
+            });
 
-            match &result.state {
-                TestState::Pass => {
-                    println!("  {}", result.test_name.green());
-                }
-                TestState::Ignore => {
-                    println!("  {}", result.test_name.cyan());
-                }
-                TestState::Fail { message } => {
-                    println!("  {}", result.test_name.red());
-                    println!("    {}", message.bright_black());
+            if let Some(lines) = lines {
+                if !lines.contains(&result.line) {
+                    return None;
                 }
+            }
+
+            let is_expected_failure = expected_failures.contains(&(name.clone(), result.line));
+            let command = CommandReport {
+                command_type: command.get_type(),
+                outcome: Outcome::new(&result.state, is_expected_failure),
+                result,
             };
+            if config.format == OutputFormat::Text {
+                report::print_text_command(&command);
+            }
 
-            result
+            Some(command)
+        })
+        .collect();
+
+    SuiteReport {
+        file_name,
+        name,
+        commands,
+    }
+}
+
+fn read_module(filename: &str, config: &RunnerConfig) -> Result<Vec<u8>, String> {
+    let module_path = Path::new(&config.dirname).join(filename);
+    fs::read(&module_path)
+        .map_err(|err| format!("Unable to read module {}: {}", module_path.display(), err))
+}
+
+// Decodes and validates a module, which is then linked against the registered modules.
+fn instantiate_module(
+    filename: &str,
+    config: &RunnerConfig,
+    context: &mut SuiteContext,
+) -> Result<Result<ModuleAddress, InstantiationError>, String> {
+    let file = read_module(filename, config)?;
+
+    let module = decode(&file[..]).map_err(|err| {
+        format!(
+            "decoding error: {} (offset: {}, file: {})",
+            err.message, err.offset, filename
+        )
+    })?;
+    validate(&module)
+        .map_err(|err| format!("validation error: {} (file: {})", err.message, filename))?;
+
+    Ok(resolve_imports(&module, context)
+        .and_then(|imports| instantiate(&mut context.store, &module, &imports)))
+}
+
+// https://webassembly.github.io/spec/core/exec/modules.html#exec-instantiation
+fn resolve_imports(
+    module: &Module,
+    context: &SuiteContext,
+) -> Result<Vec<ExternalValue>, InstantiationError> {
+    module
+        .imports
+        .iter()
+        .map(|import| {
+            context
+                .registered_modules
+                .get(&import.module)
+                .and_then(|module_address| {
+                    context.store.modules[*module_address].get_export(&import.name)
+                })
+                .ok_or(InstantiationError {
+                    kind: InstantiationErrorKind::Unlinkable,
+                    message: format!("unknown import {}.{}", import.module, import.name),
+                })
         })
         .collect()
 }
@@ -189,29 +600,217 @@ fn test_module_instantiation(
     command: &CommandModule,
     index: usize,
     config: &RunnerConfig,
+    context: &mut SuiteContext,
 ) -> TestResult {
     let test_name = format!("#{} Instantiate module", index);
 
-    let module_path = Path::new(&config.dirname)
-        .join(&command.filename)
-        .into_os_string();
-    let file = fs::read(module_path).unwrap();
+    // Later actions fail rather than running against a previous module.
+    context.current_module = None;
 
-    match decode(&file[..]) {
-        Err(err) => {
+    let message = match instantiate_module(&command.filename, config, context) {
+        Ok(Ok(module_address)) => {
+            context.current_module = Some(module_address);
+            if let Some(name) = &command.name {
+                context.named_modules.insert(name.clone(), module_address);
+            }
+            return TestResult::pass(test_name, command.filename.to_string(), command.line);
+        }
+        Ok(Err(err)) => format!(
+            "Expected module to instantiate but received error: {} (file: {})",
+            err.message, command.filename
+        ),
+        Err(message) => format!("Expected module to instantiate but received {}", message),
+    };
+
+    TestResult::fail(
+        test_name,
+        command.filename.to_string(),
+        command.line,
+        message,
+    )
+}
+
+fn test_module_instantiation_error(
+    test_name: String,
+    line: u32,
+    filename: &Option<String>,
+    text: &str,
+    kind: InstantiationErrorKind,
+    config: &RunnerConfig,
+    context: &mut SuiteContext,
+) -> TestResult {
+    let filename = match filename {
+        Some(filename) => filename,
+        None => {
+            let message = String::from("Missing module file");
+            return TestResult::fail(test_name, String::from(""), line, message);
+        }
+    };
+
+    let message = match instantiate_module(filename, config, context) {
+        Ok(Err(err)) if err.kind == kind && err.message.starts_with(text) => {
+            return TestResult::pass(test_name, filename.to_string(), line);
+        }
+        Ok(Err(err)) => format!(
+            "Expected {:?} module ({}) but received {:?} error: {} (file: {})",
+            kind, text, err.kind, err.message, filename
+        ),
+        Ok(Ok(_)) => format!(
+            "Expected {:?} module ({}) but instantiated properly (file: {})",
+            kind, text, filename
+        ),
+        Err(message) => format!(
+            "Expected {:?} module ({}) but received {}",
+            kind, text, message
+        ),
+    };
+
+    TestResult::fail(test_name, filename.to_string(), line, message)
+}
+
+// https://github.com/WebAssembly/spec/tree/main/interpreter#scripts
+//
+// Registered modules can be imported by the later modules under their alias.
+fn test_register(
+    command: &CommandRegister,
+    index: usize,
+    context: &mut SuiteContext,
+) -> TestResult {
+    let test_name = format!("#{} Register: {}", index, command.alias);
+
+    match context.get_module(&command.name) {
+        Ok(module_address) => {
+            context
+                .registered_modules
+                .insert(command.alias.clone(), module_address);
+            TestResult::pass(test_name, String::from(""), command.line)
+        }
+        Err(message) => TestResult::fail(test_name, String::from(""), command.line, message),
+    }
+}
+
+fn get_field(action: &Action) -> &str {
+    match action {
+        Action::Invoke { field, .. } => field,
+        Action::Get { field, .. } => field,
+    }
+}
+
+// https://github.com/WebAssembly/spec/tree/main/interpreter#scripts
+//
+// Invokes an exported function or reads an exported global. Traps are returned apart from the
+// errors of the action itself.
+fn run_action(
+    action: &Action,
+    context: &mut SuiteContext,
+) -> Result<ExecutionResult<Vec<Value>>, String> {
+    match action {
+        Action::Invoke {
+            field,
+            module,
+            args,
+        } => {
+            let module_address = context.get_module(module)?;
+            let arguments = args
+                .iter()
+                .map(values::parse_value)
+                .collect::<Result<Vec<Value>, String>>()?;
+
+            match context.store.modules[module_address].get_export(field) {
+                Some(ExternalValue::Function(function_address)) => {
+                    Ok(context.store.invoke(function_address, &arguments))
+                }
+                _ => Err(format!("Unknown function export {}", field)),
+            }
+        }
+        Action::Get { field, module } => {
+            let module_address = context.get_module(module)?;
+
+            match context.store.modules[module_address].get_export(field) {
+                Some(ExternalValue::Global(global_address)) => {
+                    Ok(Ok(vec![context.store.globals[global_address].value]))
+                }
+                _ => Err(format!("Unknown global export {}", field)),
+            }
+        }
+    }
+}
+
+fn test_action(command: &CommandAction, index: usize, context: &mut SuiteContext) -> TestResult {
+    let test_name = format!("#{} Action: {}", index, get_field(&command.action));
+
+    match run_action(&command.action, context) {
+        Ok(Ok(_)) => TestResult::pass(test_name, String::from(""), command.line),
+        Ok(Err(trap)) => TestResult::fail(
+            test_name,
+            String::from(""),
+            command.line,
+            format!("Expected action to succeed but trapped: {}", trap.message),
+        ),
+        Err(message) => TestResult::fail(test_name, String::from(""), command.line, message),
+    }
+}
+
+fn test_action_results(
+    test_name: String,
+    line: u32,
+    action: &Action,
+    expected: Result<Vec<ExpectedValue>, String>,
+    context: &mut SuiteContext,
+) -> TestResult {
+    let expected = match expected {
+        Ok(expected) => expected,
+        Err(message) => return TestResult::fail(test_name, String::from(""), line, message),
+    };
+
+    let results = match run_action(action, context) {
+        Ok(Ok(results)) => results,
+        Ok(Err(trap)) => {
             let message = format!(
-                "Expected module to instantiate but received error: {} (offset: {}, file: {})",
-                err.message, err.offset, command.filename
+                "Expected results {:?} but trapped: {}",
+                expected, trap.message
             );
-            TestResult::fail(
-                test_name,
-                command.filename.to_string(),
-                command.line,
-                message,
-            )
+            return TestResult::fail(test_name, String::from(""), line, message);
         }
-        _ => TestResult::pass(test_name, command.filename.to_string(), command.line),
+        Err(message) => return TestResult::fail(test_name, String::from(""), line, message),
+    };
+
+    let is_matching = results.len() == expected.len()
+        && results
+            .iter()
+            .zip(&expected)
+            .all(|(result, expected)| values::match_value(result, expected));
+    if !is_matching {
+        let message = format!("Expected results {:?} but received {:?}", expected, results);
+        return TestResult::fail(test_name, String::from(""), line, message);
     }
+
+    TestResult::pass(test_name, String::from(""), line)
+}
+
+// Spec texts can be shortened, or followed by details such as the index of an element.
+fn match_trap(trap: &Trap, text: &str) -> bool {
+    let message = trap.kind.message();
+    trap.kind != TrapKind::Other && (message.starts_with(text) || text.starts_with(message))
+}
+
+fn test_action_trap(
+    test_name: String,
+    line: u32,
+    action: &Action,
+    text: &str,
+    context: &mut SuiteContext,
+) -> TestResult {
+    let message = match run_action(action, context) {
+        Ok(Err(trap)) if match_trap(&trap, text) => {
+            return TestResult::pass(test_name, String::from(""), line)
+        }
+        Ok(Err(trap)) => format!("Expected trap ({}) but trapped: {}", text, trap.message),
+        Ok(Ok(results)) => format!("Expected trap ({}) but received {:?}", text, results),
+        Err(message) => message,
+    };
+
+    TestResult::fail(test_name, String::from(""), line, message)
 }
 
 fn test_module_malformed(
@@ -221,10 +820,17 @@ fn test_module_malformed(
 ) -> TestResult {
     let test_name = format!("#{} Malformed module: {}", index, command.text);
 
-    let module_path = Path::new(&config.dirname)
-        .join(&command.filename)
-        .into_os_string();
-    let file = fs::read(module_path).unwrap();
+    let file = match read_module(&command.filename, config) {
+        Ok(file) => file,
+        Err(message) => {
+            return TestResult::fail(
+                test_name,
+                command.filename.to_string(),
+                command.line,
+                message,
+            )
+        }
+    };
 
     match decode(&file[..]) {
         Ok(_) => {
@@ -243,50 +849,46 @@ fn test_module_malformed(
     }
 }
 
-fn print_report(report: &Vec<(Manifest, Vec<TestResult>)>) {
-    let results: Vec<&TestResult> = report.iter().flat_map(|(_, results)| results).collect();
-
-    let passing_count = results
-        .iter()
-        .filter(|r| r.state == TestState::Pass)
-        .count();
-    let failing_count = results
-        .iter()
-        .filter(|r| match &r.state {
-            TestState::Fail { message: _message } => true,
-            _ => false,
-        })
-        .count();
-    let ignored_count = results
-        .iter()
-        .filter(|r| r.state == TestState::Ignore)
-        .count();
-
-    print!("\n");
-    println!(
-        "    {}",
-        format!("{} passing", passing_count).bold().green()
-    );
-    println!("    {}", format!("{} failing", failing_count).bold().red());
-    println!("    {}", format!("{} ingored", ignored_count).bold().cyan());
-    print!("\n");
-
-    for (manifest, results) in report {
-        let message = results
-            .iter()
-            .fold(String::new(), |acc, result| match &result.state {
-                TestState::Fail { message } => format!(
-                    "{}\n  {}\n    {}\n",
-                    acc,
-                    result.test_name.red(),
-                    message.bright_black()
-                ),
-                _ => acc,
-            });
+fn test_module_invalid(
+    command: &CommandAssertInvalid,
+    index: usize,
+    config: &RunnerConfig,
+) -> TestResult {
+    let test_name = format!("#{} Invalid module: {}", index, command.text);
 
-        if !message.is_empty() {
-            println!("{}", manifest.source_filename.bold());
-            println!("{}", message);
+    let file = match read_module(&command.filename, config) {
+        Ok(file) => file,
+        Err(message) => {
+            return TestResult::fail(
+                test_name,
+                command.filename.to_string(),
+                command.line,
+                message,
+            )
         }
-    }
+    };
+
+    // Invalid modules are well-formed, so they have to be rejected by the validator.
+    let message = match decode(&file[..]) {
+        Ok(module) => match validate(&module) {
+            Ok(_) => format!(
+                "Expected module to be invalid ({}) but validated properly (file: {})",
+                command.text, command.filename
+            ),
+            Err(_) => {
+                return TestResult::pass(test_name, command.filename.to_string(), command.line)
+            }
+        },
+        Err(err) => format!(
+            "Expected module to be invalid ({}) but received decoding error: {} (offset: {}, file: {})",
+            command.text, err.message, err.offset, command.filename
+        ),
+    };
+
+    TestResult::fail(
+        test_name,
+        command.filename.to_string(),
+        command.line,
+        message,
+    )
 }
@@ -0,0 +1,330 @@
+use colored::*;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::{CommandReport, Outcome, SuiteReport, TestState};
+
+impl Outcome {
+    fn get_status(&self) -> &'static str {
+        match self {
+            Outcome::Pass => "pass",
+            Outcome::Fail => "fail",
+            Outcome::ExpectedFailure => "expected_failure",
+            Outcome::UnexpectedPass => "unexpected_pass",
+        }
+    }
+}
+
+const UNEXPECTED_PASS_MESSAGE: &str = "Expected to fail but passed";
+
+fn get_message(command: &CommandReport) -> Option<&str> {
+    match (&command.result.state, command.outcome) {
+        (_, Outcome::UnexpectedPass) => Some(UNEXPECTED_PASS_MESSAGE),
+        (TestState::Fail { message }, _) => Some(message),
+        (TestState::Pass, _) => None,
+    }
+}
+
+fn get_module_name(command: &CommandReport) -> Option<&str> {
+    Some(&command.result.file_name[..]).filter(|file_name| !file_name.is_empty())
+}
+
+fn count(report: &[SuiteReport], outcome: Outcome) -> usize {
+    report
+        .iter()
+        .flat_map(|suite| &suite.commands)
+        .filter(|command| command.outcome == outcome)
+        .count()
+}
+
+pub fn print_text_command(command: &CommandReport) {
+    let test_name = &command.result.test_name;
+    match command.outcome {
+        Outcome::Pass => println!("  {}", test_name.green()),
+        Outcome::ExpectedFailure => println!("  {}", test_name.yellow()),
+        Outcome::Fail | Outcome::UnexpectedPass => println!("  {}", test_name.red()),
+    }
+
+    if let Some(message) = get_message(command) {
+        println!("    {}", message.bright_black());
+    }
+}
+
+pub fn print_text(report: &[SuiteReport]) {
+    let expected_failure_count = count(report, Outcome::ExpectedFailure);
+    let unexpected_pass_count = count(report, Outcome::UnexpectedPass);
+
+    println!();
+    println!(
+        "    {}",
+        format!("{} passing", count(report, Outcome::Pass))
+            .bold()
+            .green()
+    );
+    println!(
+        "    {}",
+        format!("{} failing", count(report, Outcome::Fail))
+            .bold()
+            .red()
+    );
+    if expected_failure_count > 0 {
+        println!(
+            "    {}",
+            format!("{} expected failures", expected_failure_count)
+                .bold()
+                .yellow()
+        );
+    }
+    if unexpected_pass_count > 0 {
+        println!(
+            "    {}",
+            format!("{} unexpected passes", unexpected_pass_count)
+                .bold()
+                .red()
+        );
+    }
+    println!();
+
+    for suite in report {
+        let message = suite
+            .commands
+            .iter()
+            .filter(|command| command.outcome.is_failing())
+            .fold(String::new(), |acc, command| {
+                format!(
+                    "{}\n  {}\n    {}\n",
+                    acc,
+                    command.result.test_name.red(),
+                    get_message(command).unwrap_or_default().bright_black()
+                )
+            });
+
+        if !message.is_empty() {
+            println!("{}", suite.file_name.bold());
+            println!("{}", message);
+        }
+    }
+}
+
+struct JsonReport<'a> {
+    passing: usize,
+    failing: usize,
+    expected_failures: usize,
+    unexpected_passes: usize,
+    results: Vec<JsonResult<'a>>,
+}
+
+struct JsonResult<'a> {
+    file: &'a str,
+    line: u32,
+    command_type: &'a str,
+    name: &'a str,
+    module: Option<&'a str>,
+    status: &'a str,
+    message: Option<&'a str>,
+}
+
+// The implementations are written by hand, as the derived ones trip the `non_local_definitions`
+// lint with the version of serde the crate depends on.
+impl Serialize for JsonReport<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut report = serializer.serialize_struct("JsonReport", 5)?;
+        report.serialize_field("passing", &self.passing)?;
+        report.serialize_field("failing", &self.failing)?;
+        report.serialize_field("expected_failures", &self.expected_failures)?;
+        report.serialize_field("unexpected_passes", &self.unexpected_passes)?;
+        report.serialize_field("results", &self.results)?;
+        report.end()
+    }
+}
+
+impl Serialize for JsonResult<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut result = serializer.serialize_struct("JsonResult", 7)?;
+        result.serialize_field("file", self.file)?;
+        result.serialize_field("line", &self.line)?;
+        result.serialize_field("type", self.command_type)?;
+        result.serialize_field("name", self.name)?;
+        if let Some(module) = self.module {
+            result.serialize_field("module", module)?;
+        }
+        result.serialize_field("status", self.status)?;
+        if let Some(message) = self.message {
+            result.serialize_field("message", message)?;
+        }
+        result.end()
+    }
+}
+
+pub fn print_json(report: &[SuiteReport]) {
+    let results = report
+        .iter()
+        .flat_map(|suite| {
+            suite.commands.iter().map(move |command| JsonResult {
+                file: &suite.file_name,
+                line: command.result.line,
+                command_type: command.command_type,
+                name: &command.result.test_name,
+                module: get_module_name(command),
+                status: command.outcome.get_status(),
+                message: get_message(command),
+            })
+        })
+        .collect();
+
+    let json_report = JsonReport {
+        passing: count(report, Outcome::Pass),
+        failing: count(report, Outcome::Fail),
+        expected_failures: count(report, Outcome::ExpectedFailure),
+        unexpected_passes: count(report, Outcome::UnexpectedPass),
+        results,
+    };
+
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&json_report).expect("The report is serializable")
+    );
+}
+
+// XML can't represent control characters and a few noncharacters, even escaped, so they are
+// written as Rust escapes.
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push_str(&format!("&#{};", c as u32)),
+            c if c.is_control() || c == '\u{fffe}' || c == '\u{ffff}' => {
+                escaped.extend(c.escape_default())
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// https://github.com/testmoapp/junitxml
+//
+// Expected failures are reported as skipped tests.
+pub fn print_junit(report: &[SuiteReport]) {
+    let test_count: usize = report.iter().map(|suite| suite.commands.len()).sum();
+    let failure_count = count(report, Outcome::Fail) + count(report, Outcome::UnexpectedPass);
+
+    println!(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    println!(
+        r#"<testsuites tests="{}" failures="{}" skipped="{}">"#,
+        test_count,
+        failure_count,
+        count(report, Outcome::ExpectedFailure)
+    );
+
+    for suite in report {
+        let suite_failure_count = suite
+            .commands
+            .iter()
+            .filter(|command| command.outcome.is_failing())
+            .count();
+        let suite_skipped_count = suite
+            .commands
+            .iter()
+            .filter(|command| command.outcome == Outcome::ExpectedFailure)
+            .count();
+        println!(
+            r#"  <testsuite name="{}" file="{}" tests="{}" failures="{}" skipped="{}">"#,
+            escape_xml(&suite.name),
+            escape_xml(&suite.file_name),
+            suite.commands.len(),
+            suite_failure_count,
+            suite_skipped_count
+        );
+
+        for command in &suite.commands {
+            let attributes = format!(
+                r#"classname="{}" name="{}" file="{}" line="{}" type="{}""#,
+                escape_xml(&suite.name),
+                escape_xml(&command.result.test_name),
+                escape_xml(&suite.file_name),
+                command.result.line,
+                command.command_type
+            );
+            let message = escape_xml(get_message(command).unwrap_or_default());
+
+            match command.outcome {
+                Outcome::Pass => println!("    <testcase {}/>", attributes),
+                Outcome::ExpectedFailure => {
+                    println!("    <testcase {}>", attributes);
+                    println!(
+                        r#"      <skipped message="Expected failure: {}"/>"#,
+                        message
+                    );
+                    println!("    </testcase>");
+                }
+                Outcome::Fail | Outcome::UnexpectedPass => {
+                    println!("    <testcase {}>", attributes);
+                    println!(r#"      <failure message="{}"/>"#, message);
+                    println!("    </testcase>");
+                }
+            }
+        }
+
+        println!("  </testsuite>");
+    }
+
+    println!("</testsuites>");
+}
+
+// Descriptions end at the first unescaped `#`, which starts a directive.
+fn escape_tap(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '#' => escaped.push_str("\\#"),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.extend(c.escape_default()),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// https://testanything.org/tap-version-13-specification.html
+//
+// Expected failures are reported as failing TODO tests, which don't fail the run.
+pub fn print_tap(report: &[SuiteReport]) {
+    let test_count: usize = report.iter().map(|suite| suite.commands.len()).sum();
+
+    println!("TAP version 13");
+    println!("1..{}", test_count);
+
+    let commands = report
+        .iter()
+        .flat_map(|suite| suite.commands.iter().map(move |command| (suite, command)));
+    for (index, (suite, command)) in commands.enumerate() {
+        let description = escape_tap(&format!(
+            "{}:{} {} {}",
+            suite.file_name, command.result.line, command.command_type, command.result.test_name
+        ));
+
+        match command.outcome {
+            Outcome::Pass => println!("ok {} - {}", index + 1, description),
+            Outcome::Fail | Outcome::UnexpectedPass => {
+                println!("not ok {} - {}", index + 1, description)
+            }
+            Outcome::ExpectedFailure => println!(
+                "not ok {} - {} # TODO expected failure",
+                index + 1,
+                description
+            ),
+        }
+
+        if let Some(message) = get_message(command) {
+            let message = serde_json::to_string(message).expect("Strings are serializable");
+            println!("  ---");
+            println!("  message: {}", message);
+            println!("  ...");
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use crate::execution::host::HostFunction;
+use crate::execution::instance::{ExportInstance, ExternalValue, ModuleInstance};
+use crate::execution::store::{ModuleAddress, Store};
+use crate::execution::values::{Reference, Value};
+use crate::structure::*;
+
+fn allocate_print_function(store: &mut Store, params: Vec<ValueType>) -> ExternalValue {
+    // Printed values would be interleaved with the report, so they are discarded.
+    let host_function = HostFunction::new(|_, _| Ok(Vec::new()));
+    ExternalValue::Function(store.allocate_host_function((params, Vec::new()), host_function))
+}
+
+fn allocate_constant_global(
+    store: &mut Store,
+    value_type: ValueType,
+    value: Value,
+) -> ExternalValue {
+    let global_type = GlobalType {
+        value_type,
+        mutability: GlobalTypeMutability::Const,
+    };
+    ExternalValue::Global(store.allocate_global(&global_type, value))
+}
+
+/// https://github.com/WebAssembly/spec/tree/main/interpreter#spectest-host-module
+///
+/// Allocates the host module the spec tests import from, and returns the address of its
+/// instance.
+pub fn instantiate_spectest(store: &mut Store) -> ModuleAddress {
+    let table_type = TableType {
+        limits: Limits {
+            min: 10,
+            max: Some(20),
+        },
+        element_type: RefType::FUNCREF,
+    };
+    let memory_type = MemoryType {
+        limits: Limits {
+            min: 1,
+            max: Some(2),
+        },
+        shared: false,
+        address_type: AddressType::I32,
+    };
+
+    let table_address = store
+        .allocate_table(&table_type, Reference::Null(HeapType::Func))
+        .expect("The spectest table can be allocated");
+    let memory_address = store
+        .allocate_memory(&memory_type)
+        .expect("The spectest memory can be allocated");
+
+    let exports = vec![
+        ("print", allocate_print_function(store, vec![])),
+        (
+            "print_i32",
+            allocate_print_function(store, vec![ValueType::I32]),
+        ),
+        (
+            "print_i64",
+            allocate_print_function(store, vec![ValueType::I64]),
+        ),
+        (
+            "print_f32",
+            allocate_print_function(store, vec![ValueType::F32]),
+        ),
+        (
+            "print_f64",
+            allocate_print_function(store, vec![ValueType::F64]),
+        ),
+        (
+            "print_i32_f32",
+            allocate_print_function(store, vec![ValueType::I32, ValueType::F32]),
+        ),
+        (
+            "print_f64_f64",
+            allocate_print_function(store, vec![ValueType::F64, ValueType::F64]),
+        ),
+        (
+            "global_i32",
+            allocate_constant_global(store, ValueType::I32, Value::I32(666)),
+        ),
+        (
+            "global_i64",
+            allocate_constant_global(store, ValueType::I64, Value::I64(666)),
+        ),
+        (
+            "global_f32",
+            allocate_constant_global(store, ValueType::F32, Value::F32(666.6)),
+        ),
+        (
+            "global_f64",
+            allocate_constant_global(store, ValueType::F64, Value::F64(666.6)),
+        ),
+        ("table", ExternalValue::Table(table_address)),
+        ("memory", ExternalValue::Memory(memory_address)),
+    ];
+
+    store.modules.push(ModuleInstance {
+        exports: exports
+            .into_iter()
+            .map(|(name, value)| ExportInstance {
+                name: String::from(name),
+                value,
+            })
+            .collect(),
+        ..ModuleInstance::default()
+    });
+    store.modules.len() - 1
+}
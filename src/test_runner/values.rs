@@ -0,0 +1,112 @@
+use crate::execution::values::Value;
+
+use super::manifest;
+
+const F32_CANONICAL_NAN: u32 = 0x7fc0_0000;
+const F64_CANONICAL_NAN: u64 = 0x7ff8_0000_0000_0000;
+
+#[derive(Debug, Copy, Clone)]
+pub enum NanKind {
+    Canonical,
+    Arithmetic,
+}
+
+/// Expected result of an action, where NaN results are only classified.
+#[derive(Debug)]
+pub enum ExpectedValue {
+    Exact(Value),
+    F32Nan(NanKind),
+    F64Nan(NanKind),
+}
+
+// Numbers are written as their bit pattern in decimal, which keeps the payload of NaN floats.
+fn parse_bits(value: &str) -> Result<u64, String> {
+    value
+        .parse::<u64>()
+        .or_else(|_| value.parse::<i64>().map(|value| value as u64))
+        .map_err(|_| format!("Invalid value {}", value))
+}
+
+fn get_string(value: &Option<String>) -> Result<&str, String> {
+    value
+        .as_deref()
+        .ok_or(String::from("Missing value in manifest"))
+}
+
+pub fn parse_value(value: &manifest::Value) -> Result<Value, String> {
+    match value {
+        manifest::Value::I32 { value } => Ok(Value::I32(parse_bits(get_string(value)?)? as i32)),
+        manifest::Value::I64 { value } => Ok(Value::I64(parse_bits(get_string(value)?)? as i64)),
+        manifest::Value::F32 { value } => Ok(Value::F32(f32::from_bits(parse_bits(get_string(
+            value,
+        )?)? as u32))),
+        manifest::Value::F64 { value } => {
+            Ok(Value::F64(f64::from_bits(parse_bits(get_string(value)?)?)))
+        }
+    }
+}
+
+fn parse_nan_kind(value: &Option<String>) -> Option<NanKind> {
+    match value.as_deref()? {
+        "nan:canonical" => Some(NanKind::Canonical),
+        "nan:arithmetic" => Some(NanKind::Arithmetic),
+        _ => None,
+    }
+}
+
+pub fn parse_expected_value(value: &manifest::Value) -> Result<ExpectedValue, String> {
+    match value {
+        manifest::Value::F32 { value } => {
+            if let Some(kind) = parse_nan_kind(value) {
+                return Ok(ExpectedValue::F32Nan(kind));
+            }
+        }
+        manifest::Value::F64 { value } => {
+            if let Some(kind) = parse_nan_kind(value) {
+                return Ok(ExpectedValue::F64Nan(kind));
+            }
+        }
+        _ => {}
+    }
+
+    Ok(ExpectedValue::Exact(parse_value(value)?))
+}
+
+// The NaN assertions only give the type of their results.
+pub fn parse_expected_nan(value: &manifest::Value, kind: NanKind) -> Result<ExpectedValue, String> {
+    match value {
+        manifest::Value::F32 { .. } => Ok(ExpectedValue::F32Nan(kind)),
+        manifest::Value::F64 { .. } => Ok(ExpectedValue::F64Nan(kind)),
+        _ => Err(String::from("Expected NaN result of integer type")),
+    }
+}
+
+/// https://webassembly.github.io/spec/core/syntax/values.html#floating-point
+///
+/// Floats are compared by their bits, canonical NaNs having an empty payload except for the most
+/// significant bit, and arithmetic NaNs having this bit set.
+pub fn match_value(actual: &Value, expected: &ExpectedValue) -> bool {
+    match (expected, actual) {
+        (ExpectedValue::Exact(Value::F32(expected)), Value::F32(actual)) => {
+            expected.to_bits() == actual.to_bits()
+        }
+        (ExpectedValue::Exact(Value::F64(expected)), Value::F64(actual)) => {
+            expected.to_bits() == actual.to_bits()
+        }
+        (ExpectedValue::Exact(expected), actual) => expected == actual,
+
+        (ExpectedValue::F32Nan(NanKind::Canonical), Value::F32(actual)) => {
+            actual.to_bits() & !(1 << 31) == F32_CANONICAL_NAN
+        }
+        (ExpectedValue::F64Nan(NanKind::Canonical), Value::F64(actual)) => {
+            actual.to_bits() & !(1 << 63) == F64_CANONICAL_NAN
+        }
+        (ExpectedValue::F32Nan(NanKind::Arithmetic), Value::F32(actual)) => {
+            actual.to_bits() & F32_CANONICAL_NAN == F32_CANONICAL_NAN
+        }
+        (ExpectedValue::F64Nan(NanKind::Arithmetic), Value::F64(actual)) => {
+            actual.to_bits() & F64_CANONICAL_NAN == F64_CANONICAL_NAN
+        }
+        _ => false,
+    }
+}
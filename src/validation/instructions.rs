@@ -1,4 +1,8 @@
 use crate::structure::*;
+use crate::validation::types::{
+    is_defaultable, match_reference_type, match_result_type, match_storage_type, match_value_type,
+    top_heap_type, validate_heap_type, validate_value_type,
+};
 use crate::validation::validation::{Context, ValidationError, ValidationResult};
 
 const BASE: u32 = 2;
@@ -9,25 +13,60 @@ enum Operand {
     Unknown,
 }
 
+impl Operand {
+    fn is_reference(&self) -> bool {
+        matches!(self, Operand::Value(ValueType::Ref(_)))
+    }
+}
+
+/// https://webassembly.github.io/spec/core/appendix/algorithm.html#data-structures
 #[derive(Debug)]
 struct ControlFrame {
-    label_types: Vec<ValueType>,
+    is_loop: bool,
+    start_types: Vec<ValueType>,
     end_types: Vec<ValueType>,
     height: usize,
+    initializations_height: usize,
     unreachable: bool,
 }
 
+impl ControlFrame {
+    // Branching to a loop jumps back to its beginning, while branching to any other block jumps
+    // to its end.
+    fn label_types(&self) -> &Vec<ValueType> {
+        if self.is_loop {
+            &self.start_types
+        } else {
+            &self.end_types
+        }
+    }
+}
+
+/// Locals initialized within a block are only considered initialized until the end of the block,
+/// so their indexes are kept on a stack that is unwound when the frame is popped.
+///
+/// https://webassembly.github.io/function-references/core/valid/conventions.html#local-types
 #[derive(Debug)]
-struct ExpressionContext {
+struct ExpressionContext<'a> {
+    context: &'a Context<'a>,
     operands: Vec<Operand>,
     frames: Vec<ControlFrame>,
+    initialized_locals: Vec<bool>,
+    initializations: Vec<u32>,
 }
 
-impl ExpressionContext {
-    fn new() -> ExpressionContext {
+impl<'a> ExpressionContext<'a> {
+    fn new(context: &'a Context<'a>) -> ExpressionContext<'a> {
         ExpressionContext {
+            context,
             operands: vec![],
             frames: vec![],
+            initialized_locals: context
+                .locals
+                .iter()
+                .map(|(_, initialized)| *initialized)
+                .collect(),
+            initializations: vec![],
         }
     }
 
@@ -37,6 +76,16 @@ impl ExpressionContext {
             .ok_or(ValidationError::from("Unexpected empty frame stack"))
     }
 
+    fn get_frame(&self, label_index: u32) -> Result<&ControlFrame, ValidationError> {
+        let label_index = label_index as usize;
+
+        if label_index >= self.frames.len() {
+            return Err(ValidationError::from("Invalid label reference"));
+        }
+
+        Ok(&self.frames[self.frames.len() - 1 - label_index])
+    }
+
     fn push_operand(&mut self, operand: Operand) {
         self.operands.push(operand);
     }
@@ -56,11 +105,8 @@ impl ExpressionContext {
     fn pop_operand_expected(&mut self, expected: &Operand) -> Result<Operand, ValidationError> {
         let actual = self.pop_operand()?;
 
-        if actual == Operand::Unknown {
-            Ok(expected.clone())
-        } else if *expected == Operand::Unknown {
-            Ok(actual)
-        } else if actual != *expected {
+        // An unknown operand is returned as is, so that it stays unknown when pushed back.
+        if !self.match_operand(&actual, expected) {
             Err(ValidationError::from_string(format!(
                 "Mismatching type. Expected {:?} but received {:?}",
                 expected, actual
@@ -70,57 +116,122 @@ impl ExpressionContext {
         }
     }
 
-    // fn push_operands(&mut self, operands: Vec<Operand>) {
-    //     for operand in operands {
-    //         self.operands.push(operand);
-    //     }
-    // }
+    fn match_operand(&self, actual: &Operand, expected: &Operand) -> bool {
+        match (actual, expected) {
+            (Operand::Value(actual), Operand::Value(expected)) => {
+                match_value_type(self.context, actual, expected)
+            }
+            _ => true,
+        }
+    }
 
-    fn pop_operands(&mut self, operands: &Vec<Operand>) -> ValidationResult {
-        let mut clone = operands.clone();
-        clone.reverse();
+    fn push_operands(&mut self, value_types: &[ValueType]) {
+        for value_type in value_types {
+            self.operands.push(Operand::Value(*value_type));
+        }
+    }
 
-        for operand in clone {
-            self.pop_operand_expected(&operand)?;
+    fn pop_operands(&mut self, value_types: &[ValueType]) -> ValidationResult {
+        for value_type in value_types.iter().rev() {
+            self.pop_operand_expected(&Operand::Value(*value_type))?;
         }
 
         Ok(())
     }
 
-    // fn push_control(&mut self, label_types: Vec<ValueType>, end_types: Vec<ValueType>) {
-    //     self.frames.push(ControlFrame {
-    //         label_types,
-    //         end_types,
-    //         height: self.operands.len(),
-    //         unreachable: false,
-    //     });
-    // }
+    fn push_control(
+        &mut self,
+        is_loop: bool,
+        start_types: Vec<ValueType>,
+        end_types: Vec<ValueType>,
+    ) {
+        let height = self.operands.len();
+        let initializations_height = self.initializations.len();
 
-    // fn pop_control(&mut self) -> Result<Vec<ValueType>, ValidationError> {
-    //     let frame = self.top_frame()?;
-    //     let operand: Vec<Operand> = frame.end_types.iter().map(|t| Operand::Value(*t)).collect();
-    //     self.pop_operands(&operand)?;
+        self.push_operands(&start_types);
+        self.frames.push(ControlFrame {
+            is_loop,
+            start_types,
+            end_types,
+            height,
+            initializations_height,
+            unreachable: false,
+        });
+    }
 
-    //     if self.operands.len() != self.top_frame()?.height {
-    //         return Err(ValidationError::from("Mismatching frame height"))
-    //     }
+    fn pop_control(&mut self) -> Result<ControlFrame, ValidationError> {
+        let end_types = self.top_frame()?.end_types.clone();
+        self.pop_operands(&end_types)?;
 
-    //     // Should be safe to pop the top frame since it has been checked when accessing the top
-    //     // frame.
-    //     // self.frames.pop().unwrap();
-    //     Ok(frame.end_types.clone())
-    // }
+        if self.operands.len() != self.top_frame()?.height {
+            return Err(ValidationError::from("Mismatching frame height"));
+        }
+
+        let frame = self
+            .frames
+            .pop()
+            .ok_or(ValidationError::from("Unexpected empty frame stack"))?;
+
+        for local_index in self.initializations.drain(frame.initializations_height..) {
+            self.initialized_locals[local_index as usize] = false;
+        }
+
+        Ok(frame)
+    }
+
+    fn is_local_initialized(&self, local_index: u32) -> bool {
+        self.initialized_locals
+            .get(local_index as usize)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    fn initialize_local(&mut self, local_index: u32) {
+        if !self.is_local_initialized(local_index) {
+            self.initialized_locals[local_index as usize] = true;
+            self.initializations.push(local_index);
+        }
+    }
 
     fn unreachable(&mut self) -> ValidationResult {
-        // let frame = self.frames.last().unwrap();
+        let frame = self
+            .frames
+            .last_mut()
+            .ok_or(ValidationError::from("Unexpected empty frame stack"))?;
 
-        // self.operands.resize(frame.height, Operand::Unknown);
-        // frame.unreachable = true;
+        self.operands.truncate(frame.height);
+        frame.unreachable = true;
 
         Ok(())
     }
 }
 
+// The alignment is expressed as the exponent of a power of 2, and must not be larger than the
+// natural alignment of the accessed bit width.
+fn validate_alignment(memory_args: &MemoryArg, bit_width: u32) -> ValidationResult {
+    match BASE.checked_pow(memory_args.align) {
+        Some(alignment) if alignment <= bit_width / 8 => Ok(()),
+        _ => Err(ValidationError::from("Invalid memory alignment")),
+    }
+}
+
+// https://webassembly.github.io/memory64/core/valid/instructions.html#memory-instructions
+//
+// Memories are addressed with values of their address type, and offsets into memories using 32
+// bits addresses must fit in 32 bits.
+fn validate_memory_arg(
+    context: &Context,
+    memory_args: &MemoryArg,
+) -> Result<ValueType, ValidationError> {
+    let memory_type = context.get_memory(memory_args.memory)?;
+
+    if memory_type.address_type == AddressType::I32 && memory_args.offset > u64::from(u32::MAX) {
+        return Err(ValidationError::from("Invalid memory offset"));
+    }
+
+    Ok(memory_type.address_type.into())
+}
+
 // https://webassembly.github.io/spec/core/valid/instructions.html#valid-load
 fn validate_load_instruction(
     context: &Context,
@@ -128,18 +239,18 @@ fn validate_load_instruction(
     memory_args: &MemoryArg,
     value_type: ValueType,
 ) -> ValidationResult {
-    context.get_memory(0)?;
+    let address_type = validate_memory_arg(context, memory_args)?;
 
     let bit_width = match value_type {
         ValueType::I32 | ValueType::F32 => 32,
         ValueType::I64 | ValueType::F64 => 64,
+        ValueType::V128 => 128,
+        _ => return Err(ValidationError::from("Invalid memory access value type")),
     };
 
-    if BASE.pow(memory_args.align) > bit_width / 8 {
-        return Err(ValidationError::from("Invalid memory alignment"));
-    }
+    validate_alignment(memory_args, bit_width)?;
 
-    expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+    expression_context.pop_operand_expected(&Operand::Value(address_type))?;
     expression_context.push_operand(Operand::Value(value_type));
     Ok(())
 }
@@ -152,13 +263,11 @@ fn validate_load_instruction_n(
     value_type: ValueType,
     n: u32,
 ) -> ValidationResult {
-    context.get_memory(0)?;
+    let address_type = validate_memory_arg(context, memory_args)?;
 
-    if BASE.pow(memory_args.align) > (n / 8).into() {
-        return Err(ValidationError::from("Invalid memory alignment"));
-    }
+    validate_alignment(memory_args, n)?;
 
-    expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+    expression_context.pop_operand_expected(&Operand::Value(address_type))?;
     expression_context.push_operand(Operand::Value(value_type));
     Ok(())
 }
@@ -170,19 +279,19 @@ fn validate_store_instruction(
     memory_args: &MemoryArg,
     value_type: ValueType,
 ) -> ValidationResult {
-    context.get_memory(0)?;
+    let address_type = validate_memory_arg(context, memory_args)?;
 
     let bit_width = match value_type {
         ValueType::I32 | ValueType::F32 => 32,
         ValueType::I64 | ValueType::F64 => 64,
+        ValueType::V128 => 128,
+        _ => return Err(ValidationError::from("Invalid memory access value type")),
     };
 
-    if BASE.pow(memory_args.align) > bit_width / 8 {
-        return Err(ValidationError::from("Invalid memory alignment"));
-    }
+    validate_alignment(memory_args, bit_width)?;
 
-    expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
     expression_context.pop_operand_expected(&Operand::Value(value_type))?;
+    expression_context.pop_operand_expected(&Operand::Value(address_type))?;
     Ok(())
 }
 
@@ -194,14 +303,80 @@ fn validate_store_instruction_n(
     value_type: ValueType,
     n: u32,
 ) -> ValidationResult {
-    context.get_memory(0)?;
+    let address_type = validate_memory_arg(context, memory_args)?;
 
-    if BASE.pow(memory_args.align) > (n / 8).into() {
-        return Err(ValidationError::from("Invalid memory alignment"));
-    }
+    validate_alignment(memory_args, n)?;
 
-    expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
     expression_context.pop_operand_expected(&Operand::Value(value_type))?;
+    expression_context.pop_operand_expected(&Operand::Value(address_type))?;
+    Ok(())
+}
+
+// https://webassembly.github.io/spec/core/valid/instructions.html#valid-load-lane
+fn validate_load_lane_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    memory_args: &MemoryArg,
+    lane: u8,
+    n: u32,
+) -> ValidationResult {
+    let address_type = validate_memory_arg(context, memory_args)?;
+
+    validate_lane_index(lane, 128 / n)?;
+    validate_alignment(memory_args, n)?;
+
+    expression_context.pop_operands(&[address_type, ValueType::V128])?;
+    expression_context.push_operand(Operand::Value(ValueType::V128));
+    Ok(())
+}
+
+// https://webassembly.github.io/spec/core/valid/instructions.html#valid-store-lane
+fn validate_store_lane_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    memory_args: &MemoryArg,
+    lane: u8,
+    n: u32,
+) -> ValidationResult {
+    let address_type = validate_memory_arg(context, memory_args)?;
+
+    validate_lane_index(lane, 128 / n)?;
+    validate_alignment(memory_args, n)?;
+
+    expression_context.pop_operands(&[address_type, ValueType::V128])?;
+    Ok(())
+}
+
+// https://webassembly.github.io/threads/core/valid/instructions.html#atomic-memory-instructions
+//
+// The parameters exclude the memory address, which always comes first.
+fn validate_atomic_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    memory_args: &MemoryArg,
+    n: u32,
+    params: &[ValueType],
+    results: &[ValueType],
+) -> ValidationResult {
+    let address_type = validate_memory_arg(context, memory_args)?;
+
+    // Unlike regular memory accesses, atomic accesses must be exactly naturally aligned.
+    if BASE.checked_pow(memory_args.align) != Some(n / 8) {
+        return Err(ValidationError::from("Invalid atomic memory alignment"));
+    }
+
+    expression_context.pop_operands(params)?;
+    expression_context.pop_operand_expected(&Operand::Value(address_type))?;
+    expression_context.push_operands(results);
+    Ok(())
+}
+
+// https://webassembly.github.io/spec/core/valid/instructions.html#vector-instructions
+fn validate_lane_index(lane: u8, lane_count: u32) -> ValidationResult {
+    if u32::from(lane) >= lane_count {
+        return Err(ValidationError::from("Invalid lane index"));
+    }
+
     Ok(())
 }
 
@@ -229,10 +404,27 @@ fn validate_binary_instruction(
     expression_context: &mut ExpressionContext,
     value_type: ValueType,
 ) -> ValidationResult {
-    expression_context.pop_operands(&vec![
-        Operand::Value(value_type),
-        Operand::Value(value_type),
-    ])?;
+    expression_context.pop_operands(&[value_type, value_type])?;
+    expression_context.push_operand(Operand::Value(value_type));
+    Ok(())
+}
+
+// https://webassembly.github.io/spec/core/valid/instructions.html#valid-vternop
+fn validate_ternary_instruction(
+    expression_context: &mut ExpressionContext,
+    value_type: ValueType,
+) -> ValidationResult {
+    expression_context.pop_operands(&[value_type, value_type, value_type])?;
+    expression_context.push_operand(Operand::Value(value_type));
+    Ok(())
+}
+
+// https://webassembly.github.io/spec/core/valid/instructions.html#valid-vshiftop
+fn validate_shift_instruction(
+    expression_context: &mut ExpressionContext,
+    value_type: ValueType,
+) -> ValidationResult {
+    expression_context.pop_operands(&[value_type, ValueType::I32])?;
     expression_context.push_operand(Operand::Value(value_type));
     Ok(())
 }
@@ -252,10 +444,7 @@ fn validate_comparison_instruction(
     expression_context: &mut ExpressionContext,
     value_type: ValueType,
 ) -> ValidationResult {
-    expression_context.pop_operands(&vec![
-        Operand::Value(value_type),
-        Operand::Value(value_type),
-    ])?;
+    expression_context.pop_operands(&[value_type, value_type])?;
     expression_context.push_operand(Operand::Value(ValueType::I32));
     Ok(())
 }
@@ -271,6 +460,426 @@ fn validate_conversion_instruction(
     Ok(())
 }
 
+// https://webassembly.github.io/spec/core/valid/instructions.html#valid-block
+fn validate_block_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    block_type: &BlockType,
+    instructions: &[Instruction],
+    is_loop: bool,
+) -> ValidationResult {
+    let (params, results) = context.get_block_type(block_type)?;
+
+    expression_context.pop_operands(&params)?;
+    expression_context.push_control(is_loop, params, results);
+
+    for instruction in instructions {
+        validate_instruction(context, expression_context, instruction)?;
+    }
+
+    let frame = expression_context.pop_control()?;
+    expression_context.push_operands(&frame.end_types);
+    Ok(())
+}
+
+// https://webassembly.github.io/spec/core/valid/instructions.html#valid-if
+fn validate_if_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    block_type: &BlockType,
+    if_instructions: &[Instruction],
+    else_instructions: &Option<Vec<Instruction>>,
+) -> ValidationResult {
+    let (params, results) = context.get_block_type(block_type)?;
+
+    expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+    expression_context.pop_operands(&params)?;
+
+    expression_context.push_control(false, params.clone(), results.clone());
+    for instruction in if_instructions {
+        validate_instruction(context, expression_context, instruction)?;
+    }
+    expression_context.pop_control()?;
+
+    // A missing else branch behaves like an empty one, forwarding the block parameters as results.
+    expression_context.push_control(false, params, results);
+    if let Some(else_instructions) = else_instructions {
+        for instruction in else_instructions {
+            validate_instruction(context, expression_context, instruction)?;
+        }
+    }
+    let frame = expression_context.pop_control()?;
+
+    expression_context.push_operands(&frame.end_types);
+    Ok(())
+}
+
+// https://webassembly.github.io/exception-handling/core/valid/instructions.html#valid-try-table
+fn validate_try_table_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    block_type: &BlockType,
+    catches: &[Catch],
+    instructions: &[Instruction],
+) -> ValidationResult {
+    let (params, results) = context.get_block_type(block_type)?;
+
+    // Catch clauses branch to labels outside of the try_table block, so they are checked before
+    // its control frame is pushed.
+    for catch in catches {
+        let (label_index, catch_types) = match catch {
+            Catch::Catch(tag_index, label_index) => {
+                (label_index, context.get_tag(*tag_index)?.0.clone())
+            }
+            Catch::CatchRef(tag_index, label_index) => {
+                let mut catch_types = context.get_tag(*tag_index)?.0.clone();
+                catch_types.push(ValueType::Ref(RefType::EXNREF));
+                (label_index, catch_types)
+            }
+            Catch::CatchAll(label_index) => (label_index, vec![]),
+            Catch::CatchAllRef(label_index) => (label_index, vec![ValueType::Ref(RefType::EXNREF)]),
+        };
+
+        let label_types = expression_context.get_frame(*label_index)?.label_types();
+        let is_matching = label_types.len() == catch_types.len()
+            && catch_types
+                .iter()
+                .zip(label_types)
+                .all(|(actual, expected)| match_value_type(context, actual, expected));
+
+        if !is_matching {
+            return Err(ValidationError::from(
+                "Mismatching catch clause types with label types",
+            ));
+        }
+    }
+
+    expression_context.pop_operands(&params)?;
+    expression_context.push_control(false, params, results);
+
+    for instruction in instructions {
+        validate_instruction(context, expression_context, instruction)?;
+    }
+
+    let frame = expression_context.pop_control()?;
+    expression_context.push_operands(&frame.end_types);
+    Ok(())
+}
+
+// https://webassembly.github.io/spec/core/valid/instructions.html#valid-br-table
+fn validate_br_table_instruction(
+    expression_context: &mut ExpressionContext,
+    label_indexes: &[u32],
+    default_index: u32,
+) -> ValidationResult {
+    expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+
+    let default_types = expression_context
+        .get_frame(default_index)?
+        .label_types()
+        .clone();
+
+    for label_index in label_indexes {
+        let label_types = expression_context
+            .get_frame(*label_index)?
+            .label_types()
+            .clone();
+
+        if label_types.len() != default_types.len() {
+            return Err(ValidationError::from("Mismatching br_table label arity"));
+        }
+
+        // Each label is checked against the operand stack independently, so the operands are
+        // restored once checked.
+        let mut operands = Vec::new();
+        for value_type in label_types.iter().rev() {
+            operands.push(expression_context.pop_operand_expected(&Operand::Value(*value_type))?);
+        }
+        for operand in operands.into_iter().rev() {
+            expression_context.push_operand(operand);
+        }
+    }
+
+    expression_context.pop_operands(&default_types)?;
+    expression_context.unreachable()
+}
+
+// https://webassembly.github.io/function-references/core/valid/instructions.html#valid-br-on-null
+fn validate_br_on_null_instruction(
+    expression_context: &mut ExpressionContext,
+    label_index: u32,
+) -> ValidationResult {
+    let label_types = expression_context
+        .get_frame(label_index)?
+        .label_types()
+        .clone();
+
+    let operand = match expression_context.pop_operand()? {
+        Operand::Unknown => Operand::Unknown,
+        Operand::Value(ValueType::Ref(ref_type)) => Operand::Value(ValueType::Ref(RefType {
+            nullable: false,
+            ..ref_type
+        })),
+        Operand::Value(_) => {
+            return Err(ValidationError::from(
+                "Invalid br_on_null on a non reference type",
+            ))
+        }
+    };
+
+    expression_context.pop_operands(&label_types)?;
+    expression_context.push_operands(&label_types);
+    expression_context.push_operand(operand);
+    Ok(())
+}
+
+// The reference is only forwarded to the label when it is not null, so the label must end with a
+// non-null version of the operand type.
+//
+// https://webassembly.github.io/function-references/core/valid/instructions.html#valid-br-on-non-null
+fn validate_br_on_non_null_instruction(
+    expression_context: &mut ExpressionContext,
+    label_index: u32,
+) -> ValidationResult {
+    let label_types = expression_context
+        .get_frame(label_index)?
+        .label_types()
+        .clone();
+
+    let (last_type, label_types) = match label_types.split_last() {
+        Some((ValueType::Ref(last_type), label_types)) => (*last_type, label_types.to_vec()),
+        _ => {
+            return Err(ValidationError::from(
+                "Invalid br_on_non_null on a label not ending with a reference type",
+            ))
+        }
+    };
+
+    match expression_context.pop_operand()? {
+        Operand::Unknown => {}
+        Operand::Value(ValueType::Ref(ref_type)) => {
+            let non_null_type = RefType {
+                nullable: false,
+                ..ref_type
+            };
+
+            if !match_reference_type(expression_context.context, &non_null_type, &last_type) {
+                return Err(ValidationError::from_string(format!(
+                    "Mismatching type. Expected {:?} but received {:?}",
+                    last_type, non_null_type
+                )));
+            }
+        }
+        Operand::Value(_) => {
+            return Err(ValidationError::from(
+                "Invalid br_on_non_null on a non reference type",
+            ))
+        }
+    }
+
+    expression_context.pop_operands(&label_types)?;
+    expression_context.push_operands(&label_types);
+    Ok(())
+}
+
+// The operand keeps its source type when the cast fails, but is known to be non-null when the
+// target type accepts null values.
+//
+// https://webassembly.github.io/gc/core/valid/instructions.html#valid-br-on-cast
+fn validate_br_on_cast_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    label_index: u32,
+    source_type: &RefType,
+    target_type: &RefType,
+    branch_on_fail: bool,
+) -> ValidationResult {
+    validate_heap_type(context, &source_type.heap_type)?;
+    validate_heap_type(context, &target_type.heap_type)?;
+    if !match_reference_type(context, target_type, source_type) {
+        return Err(ValidationError::from(
+            "Mismatching cast target type with its source type",
+        ));
+    }
+
+    let difference_type = RefType {
+        nullable: source_type.nullable && !target_type.nullable,
+        ..*source_type
+    };
+    let (branch_type, fallthrough_type) = if branch_on_fail {
+        (difference_type, *target_type)
+    } else {
+        (*target_type, difference_type)
+    };
+
+    let label_types = expression_context
+        .get_frame(label_index)?
+        .label_types()
+        .clone();
+    let (last_type, label_types) = match label_types.split_last() {
+        Some((ValueType::Ref(last_type), label_types)) => (*last_type, label_types.to_vec()),
+        _ => {
+            return Err(ValidationError::from(
+                "Invalid cast branch on a label not ending with a reference type",
+            ))
+        }
+    };
+    if !match_reference_type(context, &branch_type, &last_type) {
+        return Err(ValidationError::from_string(format!(
+            "Mismatching type. Expected {:?} but received {:?}",
+            last_type, branch_type
+        )));
+    }
+
+    expression_context.pop_operand_expected(&Operand::Value(ValueType::Ref(*source_type)))?;
+    expression_context.pop_operands(&label_types)?;
+    expression_context.push_operands(&label_types);
+    expression_context.push_operand(Operand::Value(ValueType::Ref(fallthrough_type)));
+    Ok(())
+}
+
+// Casts and type tests accept any reference of the same type hierarchy as their target type.
+//
+// https://webassembly.github.io/gc/core/valid/instructions.html#valid-ref-test
+fn validate_cast_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    ref_type: &RefType,
+    result: ValueType,
+) -> ValidationResult {
+    validate_heap_type(context, &ref_type.heap_type)?;
+
+    let top_type = RefType {
+        nullable: true,
+        heap_type: top_heap_type(context, &ref_type.heap_type),
+    };
+    expression_context.pop_operand_expected(&Operand::Value(ValueType::Ref(top_type)))?;
+    expression_context.push_operand(Operand::Value(result));
+    Ok(())
+}
+
+// https://webassembly.github.io/gc/core/valid/instructions.html#aggregate-reference-instructions
+fn concrete_reference(nullable: bool, type_index: u32) -> ValueType {
+    ValueType::Ref(RefType {
+        nullable,
+        heap_type: HeapType::Index(type_index),
+    })
+}
+
+// Packed fields can only be read with the sign or zero extending variants.
+//
+// https://webassembly.github.io/gc/core/valid/instructions.html#valid-struct-get
+fn validate_struct_get_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    type_index: u32,
+    field_index: u32,
+    packed: bool,
+) -> ValidationResult {
+    let field_type = context
+        .get_struct_type(type_index)?
+        .get(field_index as usize)
+        .ok_or(ValidationError::from("Invalid field reference"))?;
+    if packed == matches!(field_type.storage_type, StorageType::Value(_)) {
+        return Err(ValidationError::from("Mismatching field packing"));
+    }
+
+    expression_context
+        .pop_operand_expected(&Operand::Value(concrete_reference(true, type_index)))?;
+    expression_context.push_operand(Operand::Value(field_type.storage_type.unpack()));
+    Ok(())
+}
+
+// https://webassembly.github.io/gc/core/valid/instructions.html#valid-array-get
+fn validate_array_get_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    type_index: u32,
+    packed: bool,
+) -> ValidationResult {
+    let field_type = context.get_array_type(type_index)?;
+    if packed == matches!(field_type.storage_type, StorageType::Value(_)) {
+        return Err(ValidationError::from("Mismatching field packing"));
+    }
+
+    expression_context.pop_operands(&[concrete_reference(true, type_index), ValueType::I32])?;
+    expression_context.push_operand(Operand::Value(field_type.storage_type.unpack()));
+    Ok(())
+}
+
+// Writing to a field requires it to be mutable.
+fn get_mutable_array_type<'a>(
+    context: &Context<'a>,
+    type_index: u32,
+) -> Result<&'a FieldType, ValidationError> {
+    let field_type = context.get_array_type(type_index)?;
+    if field_type.mutability != GlobalTypeMutability::Var {
+        return Err(ValidationError::from("Invalid write to an immutable array"));
+    }
+
+    Ok(field_type)
+}
+
+// Arrays can only be initialized from data segments when they hold numeric or vector values.
+fn validate_array_data_type(field_type: &FieldType) -> ValidationResult {
+    if let StorageType::Value(ValueType::Ref(_)) = field_type.storage_type {
+        return Err(ValidationError::from(
+            "Invalid data segment access on an array of references",
+        ));
+    }
+
+    Ok(())
+}
+
+// Arrays can only be initialized from element segments whose references match their elements.
+fn validate_array_element_type(
+    context: &Context,
+    field_type: &FieldType,
+    element_index: u32,
+) -> ValidationResult {
+    let element = context.get_element(element_index)?;
+    let element_type = StorageType::Value(ValueType::Ref(element.element_type));
+    if !match_storage_type(context, &element_type, &field_type.storage_type) {
+        return Err(ValidationError::from("Mismatching array and element types"));
+    }
+
+    Ok(())
+}
+
+// https://webassembly.github.io/spec/core/valid/instructions.html#valid-call
+fn validate_call_instruction(
+    expression_context: &mut ExpressionContext,
+    function_type: &FunctionType,
+) -> ValidationResult {
+    let (params, results) = function_type;
+
+    expression_context.pop_operands(params)?;
+    expression_context.push_operands(results);
+    Ok(())
+}
+
+// https://webassembly.github.io/tail-call/core/valid/instructions.html#valid-return-call
+fn validate_return_call_instruction(
+    context: &Context,
+    expression_context: &mut ExpressionContext,
+    function_type: &FunctionType,
+) -> ValidationResult {
+    let (params, results) = function_type;
+
+    let return_types = context.return_types.ok_or(ValidationError::from(
+        "Invalid return_call outside of a function",
+    ))?;
+    // The results of the callee are returned as the results of the caller.
+    if !match_result_type(context, results, return_types) {
+        return Err(ValidationError::from(
+            "Mismatching return_call results with function results",
+        ));
+    }
+
+    expression_context.pop_operands(params)?;
+    expression_context.unreachable()
+}
+
 fn validate_instruction(
     context: &Context,
     expression_context: &mut ExpressionContext,
@@ -281,60 +890,490 @@ fn validate_instruction(
             expression_context.unreachable()?;
         }
         Instruction::Nop => {}
-        Instruction::Block(_block_type, _instructions) => {}
-        Instruction::Loop(_block_type, _instructions) => {}
-        Instruction::If(_block_type, _if_instructions, _else_instructions) => {}
-        Instruction::Br(_label_index) => {}
-        Instruction::BrIf(_label_index) => {}
-        Instruction::BrTable(_label_indexes, _default_index) => {}
-        Instruction::Return => {}
-        Instruction::Call(_function_index) => {}
-        Instruction::CallIndirect(_function_index) => {}
+        Instruction::Block(block_type, instructions) => {
+            validate_block_instruction(
+                context,
+                expression_context,
+                block_type,
+                instructions,
+                false,
+            )?;
+        }
+        Instruction::Loop(block_type, instructions) => {
+            validate_block_instruction(
+                context,
+                expression_context,
+                block_type,
+                instructions,
+                true,
+            )?;
+        }
+        Instruction::If(block_type, if_instructions, else_instructions) => {
+            validate_if_instruction(
+                context,
+                expression_context,
+                block_type,
+                if_instructions,
+                else_instructions,
+            )?;
+        }
+        Instruction::TryTable(block_type, catches, instructions) => {
+            validate_try_table_instruction(
+                context,
+                expression_context,
+                block_type,
+                catches,
+                instructions,
+            )?;
+        }
+        Instruction::Throw(tag_index) => {
+            let (params, _) = context.get_tag(*tag_index)?;
+            expression_context.pop_operands(params)?;
+            expression_context.unreachable()?;
+        }
+        Instruction::ThrowRef => {
+            expression_context
+                .pop_operand_expected(&Operand::Value(ValueType::Ref(RefType::EXNREF)))?;
+            expression_context.unreachable()?;
+        }
+        Instruction::Br(label_index) => {
+            let label_types = expression_context
+                .get_frame(*label_index)?
+                .label_types()
+                .clone();
+            expression_context.pop_operands(&label_types)?;
+            expression_context.unreachable()?;
+        }
+        Instruction::BrIf(label_index) => {
+            let label_types = expression_context
+                .get_frame(*label_index)?
+                .label_types()
+                .clone();
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+            expression_context.pop_operands(&label_types)?;
+            expression_context.push_operands(&label_types);
+        }
+        Instruction::BrTable(label_indexes, default_index) => {
+            validate_br_table_instruction(expression_context, label_indexes, *default_index)?;
+        }
+        Instruction::Return => {
+            let return_types = context.return_types.ok_or(ValidationError::from(
+                "Invalid return outside of a function",
+            ))?;
+            expression_context.pop_operands(return_types)?;
+            expression_context.unreachable()?;
+        }
+        Instruction::Call(function_index) => {
+            let function_type = context.get_function(*function_index)?;
+            validate_call_instruction(expression_context, function_type)?;
+        }
+        Instruction::CallIndirect(function_type_index, table_index) => {
+            let table_type = context.get_table(*table_index)?;
+            if !match_reference_type(context, &table_type.element_type, &RefType::FUNCREF) {
+                return Err(ValidationError::from(
+                    "Invalid call_indirect on a non funcref table",
+                ));
+            }
+
+            let function_type = context.get_function_type(*function_type_index)?;
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+            validate_call_instruction(expression_context, function_type)?;
+        }
+        Instruction::ReturnCall(function_index) => {
+            let function_type = context.get_function(*function_index)?;
+            validate_return_call_instruction(context, expression_context, function_type)?;
+        }
+        Instruction::ReturnCallIndirect(function_type_index, table_index) => {
+            let table_type = context.get_table(*table_index)?;
+            if !match_reference_type(context, &table_type.element_type, &RefType::FUNCREF) {
+                return Err(ValidationError::from(
+                    "Invalid return_call_indirect on a non funcref table",
+                ));
+            }
+
+            let function_type = context.get_function_type(*function_type_index)?;
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+            validate_return_call_instruction(context, expression_context, function_type)?;
+        }
+        Instruction::CallRef(function_type_index) => {
+            let function_type = context.get_function_type(*function_type_index)?;
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::Ref(RefType {
+                nullable: true,
+                heap_type: HeapType::Index(*function_type_index),
+            })))?;
+            validate_call_instruction(expression_context, function_type)?;
+        }
+        Instruction::ReturnCallRef(function_type_index) => {
+            let function_type = context.get_function_type(*function_type_index)?;
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::Ref(RefType {
+                nullable: true,
+                heap_type: HeapType::Index(*function_type_index),
+            })))?;
+            validate_return_call_instruction(context, expression_context, function_type)?;
+        }
+        Instruction::BrOnNull(label_index) => {
+            validate_br_on_null_instruction(expression_context, *label_index)?;
+        }
+        Instruction::BrOnNonNull(label_index) => {
+            validate_br_on_non_null_instruction(expression_context, *label_index)?;
+        }
+        Instruction::BrOnCast(label_index, source_type, target_type) => {
+            validate_br_on_cast_instruction(
+                context,
+                expression_context,
+                *label_index,
+                source_type,
+                target_type,
+                false,
+            )?;
+        }
+        Instruction::BrOnCastFail(label_index, source_type, target_type) => {
+            validate_br_on_cast_instruction(
+                context,
+                expression_context,
+                *label_index,
+                source_type,
+                target_type,
+                true,
+            )?;
+        }
+
+        Instruction::RefNull(heap_type) => {
+            validate_heap_type(context, heap_type)?;
+            expression_context.push_operand(Operand::Value(ValueType::Ref(RefType {
+                nullable: true,
+                heap_type: *heap_type,
+            })));
+        }
+        Instruction::RefIsNull => {
+            let operand = expression_context.pop_operand()?;
+            if operand != Operand::Unknown && !operand.is_reference() {
+                return Err(ValidationError::from(
+                    "Invalid ref.is_null on a non reference type",
+                ));
+            }
+
+            expression_context.push_operand(Operand::Value(ValueType::I32));
+        }
+        Instruction::RefFunc(function_index) => {
+            context.get_function(*function_index)?;
+            if !context.references.contains(function_index) {
+                return Err(ValidationError::from("Undeclared function reference"));
+            }
+
+            let function_type_index = context.functions[*function_index as usize];
+            expression_context.push_operand(Operand::Value(ValueType::Ref(RefType {
+                nullable: false,
+                heap_type: HeapType::Index(function_type_index),
+            })));
+        }
+        Instruction::RefAsNonNull => match expression_context.pop_operand()? {
+            Operand::Unknown => expression_context.push_operand(Operand::Unknown),
+            Operand::Value(ValueType::Ref(ref_type)) => {
+                expression_context.push_operand(Operand::Value(ValueType::Ref(RefType {
+                    nullable: false,
+                    ..ref_type
+                })));
+            }
+            Operand::Value(_) => {
+                return Err(ValidationError::from(
+                    "Invalid ref.as_non_null on a non reference type",
+                ));
+            }
+        },
+        Instruction::RefEq => {
+            let eqref = ValueType::Ref(RefType {
+                nullable: true,
+                heap_type: HeapType::Eq,
+            });
+            expression_context.pop_operands(&[eqref, eqref])?;
+            expression_context.push_operand(Operand::Value(ValueType::I32));
+        }
+        Instruction::RefTest(ref_type) => {
+            validate_cast_instruction(context, expression_context, ref_type, ValueType::I32)?;
+        }
+        Instruction::RefCast(ref_type) => {
+            validate_cast_instruction(
+                context,
+                expression_context,
+                ref_type,
+                ValueType::Ref(*ref_type),
+            )?;
+        }
+        Instruction::RefI31 => {
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+            expression_context.push_operand(Operand::Value(ValueType::Ref(RefType {
+                nullable: false,
+                heap_type: HeapType::I31,
+            })));
+        }
+        Instruction::I31GetS | Instruction::I31GetU => {
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::Ref(RefType {
+                nullable: true,
+                heap_type: HeapType::I31,
+            })))?;
+            expression_context.push_operand(Operand::Value(ValueType::I32));
+        }
+        // External references and internal ones can be converted into each other, keeping their
+        // nullability.
+        Instruction::AnyConvertExtern | Instruction::ExternConvertAny => {
+            let (source_type, target_type) = match instruction {
+                Instruction::AnyConvertExtern => (HeapType::Extern, HeapType::Any),
+                _ => (HeapType::Any, HeapType::Extern),
+            };
+
+            let operand = expression_context.pop_operand_expected(&Operand::Value(
+                ValueType::Ref(RefType {
+                    nullable: true,
+                    heap_type: source_type,
+                }),
+            ))?;
+            let nullable = match operand {
+                Operand::Value(ValueType::Ref(ref_type)) => ref_type.nullable,
+                _ => false,
+            };
+
+            expression_context.push_operand(Operand::Value(ValueType::Ref(RefType {
+                nullable,
+                heap_type: target_type,
+            })));
+        }
+
+        Instruction::StructNew(type_index) => {
+            let field_types = context.get_struct_type(*type_index)?;
+            let value_types: Vec<ValueType> = field_types
+                .iter()
+                .map(|field_type| field_type.storage_type.unpack())
+                .collect();
+
+            expression_context.pop_operands(&value_types)?;
+            expression_context.push_operand(Operand::Value(concrete_reference(false, *type_index)));
+        }
+        Instruction::StructNewDefault(type_index) => {
+            let field_types = context.get_struct_type(*type_index)?;
+            if !field_types
+                .iter()
+                .all(|field_type| is_defaultable(&field_type.storage_type.unpack()))
+            {
+                return Err(ValidationError::from(
+                    "Invalid struct.new_default on non defaultable fields",
+                ));
+            }
+
+            expression_context.push_operand(Operand::Value(concrete_reference(false, *type_index)));
+        }
+        Instruction::StructGet(type_index, field_index) => {
+            validate_struct_get_instruction(
+                context,
+                expression_context,
+                *type_index,
+                *field_index,
+                false,
+            )?;
+        }
+        Instruction::StructGetS(type_index, field_index)
+        | Instruction::StructGetU(type_index, field_index) => {
+            validate_struct_get_instruction(
+                context,
+                expression_context,
+                *type_index,
+                *field_index,
+                true,
+            )?;
+        }
+        Instruction::StructSet(type_index, field_index) => {
+            let field_type = context
+                .get_struct_type(*type_index)?
+                .get(*field_index as usize)
+                .ok_or(ValidationError::from("Invalid field reference"))?;
+            if field_type.mutability != GlobalTypeMutability::Var {
+                return Err(ValidationError::from("Invalid write to an immutable field"));
+            }
+
+            expression_context.pop_operands(&[
+                concrete_reference(true, *type_index),
+                field_type.storage_type.unpack(),
+            ])?;
+        }
+        Instruction::ArrayNew(type_index) => {
+            let field_type = context.get_array_type(*type_index)?;
+            expression_context.pop_operands(&[field_type.storage_type.unpack(), ValueType::I32])?;
+            expression_context.push_operand(Operand::Value(concrete_reference(false, *type_index)));
+        }
+        Instruction::ArrayNewDefault(type_index) => {
+            let field_type = context.get_array_type(*type_index)?;
+            if !is_defaultable(&field_type.storage_type.unpack()) {
+                return Err(ValidationError::from(
+                    "Invalid array.new_default on non defaultable elements",
+                ));
+            }
+
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+            expression_context.push_operand(Operand::Value(concrete_reference(false, *type_index)));
+        }
+        Instruction::ArrayNewFixed(type_index, length) => {
+            let field_type = context.get_array_type(*type_index)?;
+            let value_type = Operand::Value(field_type.storage_type.unpack());
+            for _ in 0..*length {
+                expression_context.pop_operand_expected(&value_type)?;
+            }
+
+            expression_context.push_operand(Operand::Value(concrete_reference(false, *type_index)));
+        }
+        Instruction::ArrayNewData(type_index, data_index) => {
+            let field_type = context.get_array_type(*type_index)?;
+            validate_array_data_type(field_type)?;
+            context.get_data(*data_index)?;
+
+            expression_context.pop_operands(&[ValueType::I32, ValueType::I32])?;
+            expression_context.push_operand(Operand::Value(concrete_reference(false, *type_index)));
+        }
+        Instruction::ArrayNewElem(type_index, element_index) => {
+            let field_type = context.get_array_type(*type_index)?;
+            validate_array_element_type(context, field_type, *element_index)?;
+
+            expression_context.pop_operands(&[ValueType::I32, ValueType::I32])?;
+            expression_context.push_operand(Operand::Value(concrete_reference(false, *type_index)));
+        }
+        Instruction::ArrayGet(type_index) => {
+            validate_array_get_instruction(context, expression_context, *type_index, false)?;
+        }
+        Instruction::ArrayGetS(type_index) | Instruction::ArrayGetU(type_index) => {
+            validate_array_get_instruction(context, expression_context, *type_index, true)?;
+        }
+        Instruction::ArraySet(type_index) => {
+            let field_type = get_mutable_array_type(context, *type_index)?;
+            expression_context.pop_operands(&[
+                concrete_reference(true, *type_index),
+                ValueType::I32,
+                field_type.storage_type.unpack(),
+            ])?;
+        }
+        Instruction::ArrayLen => {
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::Ref(RefType {
+                nullable: true,
+                heap_type: HeapType::Array,
+            })))?;
+            expression_context.push_operand(Operand::Value(ValueType::I32));
+        }
+        Instruction::ArrayFill(type_index) => {
+            let field_type = get_mutable_array_type(context, *type_index)?;
+            expression_context.pop_operands(&[
+                concrete_reference(true, *type_index),
+                ValueType::I32,
+                field_type.storage_type.unpack(),
+                ValueType::I32,
+            ])?;
+        }
+        Instruction::ArrayCopy(destination_index, source_index) => {
+            let destination_type = get_mutable_array_type(context, *destination_index)?;
+            let source_type = context.get_array_type(*source_index)?;
+            if !match_storage_type(
+                context,
+                &source_type.storage_type,
+                &destination_type.storage_type,
+            ) {
+                return Err(ValidationError::from("Mismatching array types"));
+            }
+
+            expression_context.pop_operands(&[
+                concrete_reference(true, *destination_index),
+                ValueType::I32,
+                concrete_reference(true, *source_index),
+                ValueType::I32,
+                ValueType::I32,
+            ])?;
+        }
+        Instruction::ArrayInitData(type_index, data_index) => {
+            let field_type = get_mutable_array_type(context, *type_index)?;
+            validate_array_data_type(field_type)?;
+            context.get_data(*data_index)?;
+
+            expression_context.pop_operands(&[
+                concrete_reference(true, *type_index),
+                ValueType::I32,
+                ValueType::I32,
+                ValueType::I32,
+            ])?;
+        }
+        Instruction::ArrayInitElem(type_index, element_index) => {
+            let field_type = get_mutable_array_type(context, *type_index)?;
+            validate_array_element_type(context, field_type, *element_index)?;
+
+            expression_context.pop_operands(&[
+                concrete_reference(true, *type_index),
+                ValueType::I32,
+                ValueType::I32,
+                ValueType::I32,
+            ])?;
+        }
 
         Instruction::Drop => {
             expression_context.pop_operand()?;
         }
         Instruction::Select => {
-            let t1 = expression_context.pop_operand()?;
-            let t2 = expression_context.pop_operand()?;
+            // The untyped select instruction is restricted to numeric operands.
             expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+            let t1 = expression_context.pop_operand()?;
+            let t2 = expression_context.pop_operand_expected(&t1)?;
+
+            if t1.is_reference() || t2.is_reference() {
+                return Err(ValidationError::from("Invalid select on reference types"));
+            }
 
-            if t1 != t2 {
-                return Err(ValidationError::from("Invalid select instruction argument"));
+            if t1 == Operand::Unknown {
+                expression_context.push_operand(t2);
+            } else {
+                expression_context.push_operand(t1);
+            }
+        }
+        Instruction::SelectTyped(value_types) => {
+            if value_types.len() != 1 {
+                return Err(ValidationError::from("Invalid select type arity"));
             }
 
-            expression_context.push_operand(t1);
+            validate_value_type(context, &value_types[0])?;
+
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+            expression_context.pop_operands(&[value_types[0], value_types[0]])?;
+            expression_context.push_operand(Operand::Value(value_types[0]));
         }
 
         Instruction::LocalGet(local_index) => {
             let local = context.get_local(*local_index)?;
-            expression_context.push_operand(Operand::Value(local.clone()));
+            if !expression_context.is_local_initialized(*local_index) {
+                return Err(ValidationError::from("Uninitialized local"));
+            }
+
+            expression_context.push_operand(Operand::Value(*local));
         }
         Instruction::LocalSet(local_index) => {
             let local = context.get_local(*local_index)?;
-            expression_context.pop_operand_expected(&Operand::Value(local.clone()))?;
+            expression_context.pop_operand_expected(&Operand::Value(*local))?;
+            expression_context.initialize_local(*local_index);
         }
         Instruction::LocalTee(local_index) => {
             let local = context.get_local(*local_index)?;
-            expression_context.pop_operand_expected(&Operand::Value(local.clone()))?;
-            expression_context.push_operand(Operand::Value(local.clone()));
+            expression_context.pop_operand_expected(&Operand::Value(*local))?;
+            expression_context.initialize_local(*local_index);
+            expression_context.push_operand(Operand::Value(*local));
         }
         Instruction::GlobalGet(global_index) => {
             let global = context.get_global(*global_index)?;
-            let value_type = global.global_type.value_type;
-            expression_context.push_operand(Operand::Value(value_type.clone()));
+            let value_type = global.value_type;
+            expression_context.push_operand(Operand::Value(value_type));
         }
         Instruction::GlobalSet(global_index) => {
             let global = context.get_global(*global_index)?;
 
-            if global.global_type.mutability != GlobalTypeMutability::Var {
+            if global.mutability != GlobalTypeMutability::Var {
                 return Err(ValidationError::from(
                     "Invalid global.set on a non variable global",
                 ));
             }
 
-            let value_type = global.global_type.value_type;
-            expression_context.pop_operand_expected(&Operand::Value(value_type.clone()))?;
+            let value_type = global.value_type;
+            expression_context.pop_operand_expected(&Operand::Value(value_type))?;
         }
 
         Instruction::I32Load(memory_args) => {
@@ -451,14 +1490,96 @@ fn validate_instruction(
                 32,
             )?;
         }
-        Instruction::MemorySize => {
-            context.get_memory(0)?;
-            expression_context.push_operand(Operand::Value(ValueType::I32));
+        Instruction::MemorySize(memory_index) => {
+            let address_type = context.get_memory(*memory_index)?.address_type.into();
+            expression_context.push_operand(Operand::Value(address_type));
+        }
+        Instruction::MemoryGrow(memory_index) => {
+            let address_type = context.get_memory(*memory_index)?.address_type.into();
+            expression_context.pop_operand_expected(&Operand::Value(address_type))?;
+            expression_context.push_operand(Operand::Value(address_type));
+        }
+        Instruction::MemoryInit { memory, data } => {
+            let address_type = context.get_memory(*memory)?.address_type.into();
+            context.get_data(*data)?;
+            expression_context.pop_operands(&[address_type, ValueType::I32, ValueType::I32])?;
+        }
+        Instruction::DataDrop(data_index) => {
+            context.get_data(*data_index)?;
+        }
+        Instruction::MemoryCopy(destination_index, source_index) => {
+            let destination_type = context.get_memory(*destination_index)?.address_type;
+            let source_type = context.get_memory(*source_index)?.address_type;
+
+            // The copied length must be addressable in both memories.
+            let length_type =
+                if destination_type == AddressType::I64 && source_type == AddressType::I64 {
+                    ValueType::I64
+                } else {
+                    ValueType::I32
+                };
+
+            expression_context.pop_operands(&[
+                destination_type.into(),
+                source_type.into(),
+                length_type,
+            ])?;
+        }
+        Instruction::MemoryFill(memory_index) => {
+            let address_type = context.get_memory(*memory_index)?.address_type.into();
+            expression_context.pop_operands(&[address_type, ValueType::I32, address_type])?;
+        }
+
+        Instruction::TableGet(table_index) => {
+            let table_type = context.get_table(*table_index)?;
+            expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
+            expression_context.push_operand(Operand::Value(table_type.element_type.into()));
+        }
+        Instruction::TableSet(table_index) => {
+            let table_type = context.get_table(*table_index)?;
+            expression_context.pop_operands(&[ValueType::I32, table_type.element_type.into()])?;
+        }
+        Instruction::TableSize(table_index) => {
+            context.get_table(*table_index)?;
+            expression_context.push_operand(Operand::Value(ValueType::I32));
+        }
+        Instruction::TableGrow(table_index) => {
+            let table_type = context.get_table(*table_index)?;
+            expression_context.pop_operands(&[table_type.element_type.into(), ValueType::I32])?;
+            expression_context.push_operand(Operand::Value(ValueType::I32));
+        }
+        Instruction::TableFill(table_index) => {
+            let table_type = context.get_table(*table_index)?;
+            expression_context.pop_operands(&[
+                ValueType::I32,
+                table_type.element_type.into(),
+                ValueType::I32,
+            ])?;
+        }
+        Instruction::TableInit { table, element } => {
+            let table_type = context.get_table(*table)?;
+            let element_type = &context.get_element(*element)?.element_type;
+            if !match_reference_type(context, element_type, &table_type.element_type) {
+                return Err(ValidationError::from("Mismatching table and element types"));
+            }
+
+            expression_context.pop_operands(&[ValueType::I32, ValueType::I32, ValueType::I32])?;
         }
-        Instruction::MemoryGrow => {
-            context.get_memory(0)?;
-            expression_context.pop_operand_expected(&Operand::Value(ValueType::I32))?;
-            expression_context.push_operand(Operand::Value(ValueType::I32));
+        Instruction::ElemDrop(element_index) => {
+            context.get_element(*element_index)?;
+        }
+        Instruction::TableCopy(destination_index, source_index) => {
+            let destination_type = context.get_table(*destination_index)?;
+            let source_type = context.get_table(*source_index)?;
+            if !match_reference_type(
+                context,
+                &source_type.element_type,
+                &destination_type.element_type,
+            ) {
+                return Err(ValidationError::from("Mismatching table types"));
+            }
+
+            expression_context.pop_operands(&[ValueType::I32, ValueType::I32, ValueType::I32])?;
         }
 
         Instruction::I32Const(_value) => {
@@ -595,7 +1716,7 @@ fn validate_instruction(
         | Instruction::F64Min
         | Instruction::F64Max
         | Instruction::F64CopySign => {
-            validate_unary_instruction(expression_context, ValueType::F64)?;
+            validate_binary_instruction(expression_context, ValueType::F64)?;
         }
 
         Instruction::I32WrapI64 => {
@@ -614,22 +1735,22 @@ fn validate_instruction(
             validate_conversion_instruction(expression_context, ValueType::F64, ValueType::I32)?;
         }
         Instruction::I64ExtendSI32 => {
-            validate_conversion_instruction(expression_context, ValueType::I64, ValueType::I32)?;
+            validate_conversion_instruction(expression_context, ValueType::I32, ValueType::I64)?;
         }
         Instruction::I64ExtendUI32 => {
-            validate_conversion_instruction(expression_context, ValueType::I64, ValueType::I32)?;
+            validate_conversion_instruction(expression_context, ValueType::I32, ValueType::I64)?;
         }
         Instruction::I64TruncSF32 => {
-            validate_conversion_instruction(expression_context, ValueType::I64, ValueType::F32)?;
+            validate_conversion_instruction(expression_context, ValueType::F32, ValueType::I64)?;
         }
         Instruction::I64TruncUF32 => {
-            validate_conversion_instruction(expression_context, ValueType::I64, ValueType::F32)?;
+            validate_conversion_instruction(expression_context, ValueType::F32, ValueType::I64)?;
         }
         Instruction::I64TruncSF64 => {
-            validate_conversion_instruction(expression_context, ValueType::I64, ValueType::F64)?;
+            validate_conversion_instruction(expression_context, ValueType::F64, ValueType::I64)?;
         }
         Instruction::I64TruncUF64 => {
-            validate_conversion_instruction(expression_context, ValueType::I64, ValueType::F64)?;
+            validate_conversion_instruction(expression_context, ValueType::F64, ValueType::I64)?;
         }
         Instruction::F32ConvertSI32 => {
             validate_conversion_instruction(expression_context, ValueType::I32, ValueType::F32)?;
@@ -674,6 +1795,751 @@ fn validate_instruction(
         Instruction::F64ReinterpretI64 => {
             validate_conversion_instruction(expression_context, ValueType::I64, ValueType::F64)?;
         }
+
+        Instruction::I32Extend8S | Instruction::I32Extend16S => {
+            validate_unary_instruction(expression_context, ValueType::I32)?;
+        }
+        Instruction::I64Extend8S | Instruction::I64Extend16S | Instruction::I64Extend32S => {
+            validate_unary_instruction(expression_context, ValueType::I64)?;
+        }
+
+        Instruction::I32TruncSatSF32 | Instruction::I32TruncSatUF32 => {
+            validate_conversion_instruction(expression_context, ValueType::F32, ValueType::I32)?;
+        }
+        Instruction::I32TruncSatSF64 | Instruction::I32TruncSatUF64 => {
+            validate_conversion_instruction(expression_context, ValueType::F64, ValueType::I32)?;
+        }
+        Instruction::I64TruncSatSF32 | Instruction::I64TruncSatUF32 => {
+            validate_conversion_instruction(expression_context, ValueType::F32, ValueType::I64)?;
+        }
+        Instruction::I64TruncSatSF64 | Instruction::I64TruncSatUF64 => {
+            validate_conversion_instruction(expression_context, ValueType::F64, ValueType::I64)?;
+        }
+
+        Instruction::V128Load(memory_args) => {
+            validate_load_instruction(context, expression_context, memory_args, ValueType::V128)?;
+        }
+        Instruction::V128Load8x8S(memory_args)
+        | Instruction::V128Load8x8U(memory_args)
+        | Instruction::V128Load16x4S(memory_args)
+        | Instruction::V128Load16x4U(memory_args)
+        | Instruction::V128Load32x2S(memory_args)
+        | Instruction::V128Load32x2U(memory_args)
+        | Instruction::V128Load64Splat(memory_args)
+        | Instruction::V128Load64Zero(memory_args) => {
+            validate_load_instruction_n(
+                context,
+                expression_context,
+                memory_args,
+                ValueType::V128,
+                64,
+            )?;
+        }
+        Instruction::V128Load32Splat(memory_args) | Instruction::V128Load32Zero(memory_args) => {
+            validate_load_instruction_n(
+                context,
+                expression_context,
+                memory_args,
+                ValueType::V128,
+                32,
+            )?;
+        }
+        Instruction::V128Load16Splat(memory_args) => {
+            validate_load_instruction_n(
+                context,
+                expression_context,
+                memory_args,
+                ValueType::V128,
+                16,
+            )?;
+        }
+        Instruction::V128Load8Splat(memory_args) => {
+            validate_load_instruction_n(
+                context,
+                expression_context,
+                memory_args,
+                ValueType::V128,
+                8,
+            )?;
+        }
+        Instruction::V128Store(memory_args) => {
+            validate_store_instruction(context, expression_context, memory_args, ValueType::V128)?;
+        }
+        Instruction::V128Load8Lane(memory_args, lane) => {
+            validate_load_lane_instruction(context, expression_context, memory_args, *lane, 8)?;
+        }
+        Instruction::V128Load16Lane(memory_args, lane) => {
+            validate_load_lane_instruction(context, expression_context, memory_args, *lane, 16)?;
+        }
+        Instruction::V128Load32Lane(memory_args, lane) => {
+            validate_load_lane_instruction(context, expression_context, memory_args, *lane, 32)?;
+        }
+        Instruction::V128Load64Lane(memory_args, lane) => {
+            validate_load_lane_instruction(context, expression_context, memory_args, *lane, 64)?;
+        }
+        Instruction::V128Store8Lane(memory_args, lane) => {
+            validate_store_lane_instruction(context, expression_context, memory_args, *lane, 8)?;
+        }
+        Instruction::V128Store16Lane(memory_args, lane) => {
+            validate_store_lane_instruction(context, expression_context, memory_args, *lane, 16)?;
+        }
+        Instruction::V128Store32Lane(memory_args, lane) => {
+            validate_store_lane_instruction(context, expression_context, memory_args, *lane, 32)?;
+        }
+        Instruction::V128Store64Lane(memory_args, lane) => {
+            validate_store_lane_instruction(context, expression_context, memory_args, *lane, 64)?;
+        }
+        Instruction::V128Const(_value) => {
+            validate_const_instruction(expression_context, ValueType::V128)?;
+        }
+        Instruction::I8x16Shuffle(lanes) => {
+            for lane in lanes {
+                validate_lane_index(*lane, 32)?;
+            }
+            validate_binary_instruction(expression_context, ValueType::V128)?;
+        }
+        Instruction::I8x16Splat | Instruction::I16x8Splat | Instruction::I32x4Splat => {
+            validate_conversion_instruction(expression_context, ValueType::I32, ValueType::V128)?;
+        }
+        Instruction::I64x2Splat => {
+            validate_conversion_instruction(expression_context, ValueType::I64, ValueType::V128)?;
+        }
+        Instruction::F32x4Splat => {
+            validate_conversion_instruction(expression_context, ValueType::F32, ValueType::V128)?;
+        }
+        Instruction::F64x2Splat => {
+            validate_conversion_instruction(expression_context, ValueType::F64, ValueType::V128)?;
+        }
+        Instruction::I8x16ExtractLaneS(lane) | Instruction::I8x16ExtractLaneU(lane) => {
+            validate_lane_index(*lane, 16)?;
+            validate_conversion_instruction(expression_context, ValueType::V128, ValueType::I32)?;
+        }
+        Instruction::I16x8ExtractLaneS(lane) | Instruction::I16x8ExtractLaneU(lane) => {
+            validate_lane_index(*lane, 8)?;
+            validate_conversion_instruction(expression_context, ValueType::V128, ValueType::I32)?;
+        }
+        Instruction::I32x4ExtractLane(lane) => {
+            validate_lane_index(*lane, 4)?;
+            validate_conversion_instruction(expression_context, ValueType::V128, ValueType::I32)?;
+        }
+        Instruction::I64x2ExtractLane(lane) => {
+            validate_lane_index(*lane, 2)?;
+            validate_conversion_instruction(expression_context, ValueType::V128, ValueType::I64)?;
+        }
+        Instruction::F32x4ExtractLane(lane) => {
+            validate_lane_index(*lane, 4)?;
+            validate_conversion_instruction(expression_context, ValueType::V128, ValueType::F32)?;
+        }
+        Instruction::F64x2ExtractLane(lane) => {
+            validate_lane_index(*lane, 2)?;
+            validate_conversion_instruction(expression_context, ValueType::V128, ValueType::F64)?;
+        }
+        Instruction::I8x16ReplaceLane(lane) => {
+            validate_lane_index(*lane, 16)?;
+            expression_context.pop_operands(&[ValueType::V128, ValueType::I32])?;
+            expression_context.push_operand(Operand::Value(ValueType::V128));
+        }
+        Instruction::I16x8ReplaceLane(lane) => {
+            validate_lane_index(*lane, 8)?;
+            expression_context.pop_operands(&[ValueType::V128, ValueType::I32])?;
+            expression_context.push_operand(Operand::Value(ValueType::V128));
+        }
+        Instruction::I32x4ReplaceLane(lane) => {
+            validate_lane_index(*lane, 4)?;
+            expression_context.pop_operands(&[ValueType::V128, ValueType::I32])?;
+            expression_context.push_operand(Operand::Value(ValueType::V128));
+        }
+        Instruction::I64x2ReplaceLane(lane) => {
+            validate_lane_index(*lane, 2)?;
+            expression_context.pop_operands(&[ValueType::V128, ValueType::I64])?;
+            expression_context.push_operand(Operand::Value(ValueType::V128));
+        }
+        Instruction::F32x4ReplaceLane(lane) => {
+            validate_lane_index(*lane, 4)?;
+            expression_context.pop_operands(&[ValueType::V128, ValueType::F32])?;
+            expression_context.push_operand(Operand::Value(ValueType::V128));
+        }
+        Instruction::F64x2ReplaceLane(lane) => {
+            validate_lane_index(*lane, 2)?;
+            expression_context.pop_operands(&[ValueType::V128, ValueType::F64])?;
+            expression_context.push_operand(Operand::Value(ValueType::V128));
+        }
+        Instruction::V128Not
+        | Instruction::F32x4DemoteF64x2Zero
+        | Instruction::F64x2PromoteLowF32x4
+        | Instruction::I8x16Abs
+        | Instruction::I8x16Neg
+        | Instruction::I8x16Popcnt
+        | Instruction::F32x4Ceil
+        | Instruction::F32x4Floor
+        | Instruction::F32x4Trunc
+        | Instruction::F32x4Nearest
+        | Instruction::F64x2Ceil
+        | Instruction::F64x2Floor
+        | Instruction::F64x2Trunc
+        | Instruction::I16x8ExtAddPairwiseI8x16S
+        | Instruction::I16x8ExtAddPairwiseI8x16U
+        | Instruction::I32x4ExtAddPairwiseI16x8S
+        | Instruction::I32x4ExtAddPairwiseI16x8U
+        | Instruction::I16x8Abs
+        | Instruction::I16x8Neg
+        | Instruction::I16x8ExtendLowI8x16S
+        | Instruction::I16x8ExtendHighI8x16S
+        | Instruction::I16x8ExtendLowI8x16U
+        | Instruction::I16x8ExtendHighI8x16U
+        | Instruction::F64x2Nearest
+        | Instruction::I32x4Abs
+        | Instruction::I32x4Neg
+        | Instruction::I32x4ExtendLowI16x8S
+        | Instruction::I32x4ExtendHighI16x8S
+        | Instruction::I32x4ExtendLowI16x8U
+        | Instruction::I32x4ExtendHighI16x8U
+        | Instruction::I64x2Abs
+        | Instruction::I64x2Neg
+        | Instruction::I64x2ExtendLowI32x4S
+        | Instruction::I64x2ExtendHighI32x4S
+        | Instruction::I64x2ExtendLowI32x4U
+        | Instruction::I64x2ExtendHighI32x4U
+        | Instruction::F32x4Abs
+        | Instruction::F32x4Neg
+        | Instruction::F32x4Sqrt
+        | Instruction::F64x2Abs
+        | Instruction::F64x2Neg
+        | Instruction::F64x2Sqrt
+        | Instruction::I32x4TruncSatF32x4S
+        | Instruction::I32x4TruncSatF32x4U
+        | Instruction::F32x4ConvertI32x4S
+        | Instruction::F32x4ConvertI32x4U
+        | Instruction::I32x4TruncSatF64x2SZero
+        | Instruction::I32x4TruncSatF64x2UZero
+        | Instruction::F64x2ConvertLowI32x4S
+        | Instruction::F64x2ConvertLowI32x4U => {
+            validate_unary_instruction(expression_context, ValueType::V128)?;
+        }
+        Instruction::I8x16Swizzle
+        | Instruction::I8x16Eq
+        | Instruction::I8x16Ne
+        | Instruction::I8x16LtS
+        | Instruction::I8x16LtU
+        | Instruction::I8x16GtS
+        | Instruction::I8x16GtU
+        | Instruction::I8x16LeS
+        | Instruction::I8x16LeU
+        | Instruction::I8x16GeS
+        | Instruction::I8x16GeU
+        | Instruction::I16x8Eq
+        | Instruction::I16x8Ne
+        | Instruction::I16x8LtS
+        | Instruction::I16x8LtU
+        | Instruction::I16x8GtS
+        | Instruction::I16x8GtU
+        | Instruction::I16x8LeS
+        | Instruction::I16x8LeU
+        | Instruction::I16x8GeS
+        | Instruction::I16x8GeU
+        | Instruction::I32x4Eq
+        | Instruction::I32x4Ne
+        | Instruction::I32x4LtS
+        | Instruction::I32x4LtU
+        | Instruction::I32x4GtS
+        | Instruction::I32x4GtU
+        | Instruction::I32x4LeS
+        | Instruction::I32x4LeU
+        | Instruction::I32x4GeS
+        | Instruction::I32x4GeU
+        | Instruction::F32x4Eq
+        | Instruction::F32x4Ne
+        | Instruction::F32x4Lt
+        | Instruction::F32x4Gt
+        | Instruction::F32x4Le
+        | Instruction::F32x4Ge
+        | Instruction::F64x2Eq
+        | Instruction::F64x2Ne
+        | Instruction::F64x2Lt
+        | Instruction::F64x2Gt
+        | Instruction::F64x2Le
+        | Instruction::F64x2Ge
+        | Instruction::V128And
+        | Instruction::V128AndNot
+        | Instruction::V128Or
+        | Instruction::V128Xor
+        | Instruction::I8x16NarrowI16x8S
+        | Instruction::I8x16NarrowI16x8U
+        | Instruction::I8x16Add
+        | Instruction::I8x16AddSatS
+        | Instruction::I8x16AddSatU
+        | Instruction::I8x16Sub
+        | Instruction::I8x16SubSatS
+        | Instruction::I8x16SubSatU
+        | Instruction::I8x16MinS
+        | Instruction::I8x16MinU
+        | Instruction::I8x16MaxS
+        | Instruction::I8x16MaxU
+        | Instruction::I8x16AvgrU
+        | Instruction::I16x8Q15MulrSatS
+        | Instruction::I16x8NarrowI32x4S
+        | Instruction::I16x8NarrowI32x4U
+        | Instruction::I16x8Add
+        | Instruction::I16x8AddSatS
+        | Instruction::I16x8AddSatU
+        | Instruction::I16x8Sub
+        | Instruction::I16x8SubSatS
+        | Instruction::I16x8SubSatU
+        | Instruction::I16x8Mul
+        | Instruction::I16x8MinS
+        | Instruction::I16x8MinU
+        | Instruction::I16x8MaxS
+        | Instruction::I16x8MaxU
+        | Instruction::I16x8AvgrU
+        | Instruction::I16x8ExtMulLowI8x16S
+        | Instruction::I16x8ExtMulHighI8x16S
+        | Instruction::I16x8ExtMulLowI8x16U
+        | Instruction::I16x8ExtMulHighI8x16U
+        | Instruction::I32x4Add
+        | Instruction::I32x4Sub
+        | Instruction::I32x4Mul
+        | Instruction::I32x4MinS
+        | Instruction::I32x4MinU
+        | Instruction::I32x4MaxS
+        | Instruction::I32x4MaxU
+        | Instruction::I32x4DotI16x8S
+        | Instruction::I32x4ExtMulLowI16x8S
+        | Instruction::I32x4ExtMulHighI16x8S
+        | Instruction::I32x4ExtMulLowI16x8U
+        | Instruction::I32x4ExtMulHighI16x8U
+        | Instruction::I64x2Add
+        | Instruction::I64x2Sub
+        | Instruction::I64x2Mul
+        | Instruction::I64x2Eq
+        | Instruction::I64x2Ne
+        | Instruction::I64x2LtS
+        | Instruction::I64x2GtS
+        | Instruction::I64x2LeS
+        | Instruction::I64x2GeS
+        | Instruction::I64x2ExtMulLowI32x4S
+        | Instruction::I64x2ExtMulHighI32x4S
+        | Instruction::I64x2ExtMulLowI32x4U
+        | Instruction::I64x2ExtMulHighI32x4U
+        | Instruction::F32x4Add
+        | Instruction::F32x4Sub
+        | Instruction::F32x4Mul
+        | Instruction::F32x4Div
+        | Instruction::F32x4Min
+        | Instruction::F32x4Max
+        | Instruction::F32x4PMin
+        | Instruction::F32x4PMax
+        | Instruction::F64x2Add
+        | Instruction::F64x2Sub
+        | Instruction::F64x2Mul
+        | Instruction::F64x2Div
+        | Instruction::F64x2Min
+        | Instruction::F64x2Max
+        | Instruction::F64x2PMin
+        | Instruction::F64x2PMax => {
+            validate_binary_instruction(expression_context, ValueType::V128)?;
+        }
+        Instruction::V128Bitselect => {
+            validate_ternary_instruction(expression_context, ValueType::V128)?;
+        }
+        Instruction::V128AnyTrue
+        | Instruction::I8x16AllTrue
+        | Instruction::I8x16Bitmask
+        | Instruction::I16x8AllTrue
+        | Instruction::I16x8Bitmask
+        | Instruction::I32x4AllTrue
+        | Instruction::I32x4Bitmask
+        | Instruction::I64x2AllTrue
+        | Instruction::I64x2Bitmask => {
+            validate_test_instruction(expression_context, ValueType::V128)?;
+        }
+        Instruction::I8x16Shl
+        | Instruction::I8x16ShrS
+        | Instruction::I8x16ShrU
+        | Instruction::I16x8Shl
+        | Instruction::I16x8ShrS
+        | Instruction::I16x8ShrU
+        | Instruction::I32x4Shl
+        | Instruction::I32x4ShrS
+        | Instruction::I32x4ShrU
+        | Instruction::I64x2Shl
+        | Instruction::I64x2ShrS
+        | Instruction::I64x2ShrU => {
+            validate_shift_instruction(expression_context, ValueType::V128)?;
+        }
+
+        Instruction::I32x4RelaxedTruncF32x4S
+        | Instruction::I32x4RelaxedTruncF32x4U
+        | Instruction::I32x4RelaxedTruncF64x2SZero
+        | Instruction::I32x4RelaxedTruncF64x2UZero => {
+            validate_unary_instruction(expression_context, ValueType::V128)?;
+        }
+        Instruction::I8x16RelaxedSwizzle
+        | Instruction::F32x4RelaxedMin
+        | Instruction::F32x4RelaxedMax
+        | Instruction::F64x2RelaxedMin
+        | Instruction::F64x2RelaxedMax
+        | Instruction::I16x8RelaxedQ15MulrS
+        | Instruction::I16x8RelaxedDotI8x16I7x16S => {
+            validate_binary_instruction(expression_context, ValueType::V128)?;
+        }
+        Instruction::F32x4RelaxedMadd
+        | Instruction::F32x4RelaxedNmadd
+        | Instruction::F64x2RelaxedMadd
+        | Instruction::F64x2RelaxedNmadd
+        | Instruction::I8x16RelaxedLaneselect
+        | Instruction::I16x8RelaxedLaneselect
+        | Instruction::I32x4RelaxedLaneselect
+        | Instruction::I64x2RelaxedLaneselect
+        | Instruction::I32x4RelaxedDotI8x16I7x16AddS => {
+            validate_ternary_instruction(expression_context, ValueType::V128)?;
+        }
+
+        Instruction::MemoryAtomicNotify(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[ValueType::I32],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::MemoryAtomicWait32(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[ValueType::I32, ValueType::I64],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::MemoryAtomicWait64(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                64,
+                &[ValueType::I64, ValueType::I64],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I32AtomicLoad(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I64AtomicLoad(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                64,
+                &[],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I32AtomicLoad8U(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                8,
+                &[],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I32AtomicLoad16U(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                16,
+                &[],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I64AtomicLoad8U(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                8,
+                &[],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I64AtomicLoad16U(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                16,
+                &[],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I64AtomicLoad32U(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I32AtomicStore(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[ValueType::I32],
+                &[],
+            )?;
+        }
+        Instruction::I64AtomicStore(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                64,
+                &[ValueType::I64],
+                &[],
+            )?;
+        }
+        Instruction::I32AtomicStore8(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                8,
+                &[ValueType::I32],
+                &[],
+            )?;
+        }
+        Instruction::I32AtomicStore16(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                16,
+                &[ValueType::I32],
+                &[],
+            )?;
+        }
+        Instruction::I64AtomicStore8(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                8,
+                &[ValueType::I64],
+                &[],
+            )?;
+        }
+        Instruction::I64AtomicStore16(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                16,
+                &[ValueType::I64],
+                &[],
+            )?;
+        }
+        Instruction::I64AtomicStore32(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[ValueType::I64],
+                &[],
+            )?;
+        }
+        Instruction::I32AtomicRmwAdd(memory_args)
+        | Instruction::I32AtomicRmwSub(memory_args)
+        | Instruction::I32AtomicRmwAnd(memory_args)
+        | Instruction::I32AtomicRmwOr(memory_args)
+        | Instruction::I32AtomicRmwXor(memory_args)
+        | Instruction::I32AtomicRmwXchg(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[ValueType::I32],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I64AtomicRmwAdd(memory_args)
+        | Instruction::I64AtomicRmwSub(memory_args)
+        | Instruction::I64AtomicRmwAnd(memory_args)
+        | Instruction::I64AtomicRmwOr(memory_args)
+        | Instruction::I64AtomicRmwXor(memory_args)
+        | Instruction::I64AtomicRmwXchg(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                64,
+                &[ValueType::I64],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I32AtomicRmw8AddU(memory_args)
+        | Instruction::I32AtomicRmw8SubU(memory_args)
+        | Instruction::I32AtomicRmw8AndU(memory_args)
+        | Instruction::I32AtomicRmw8OrU(memory_args)
+        | Instruction::I32AtomicRmw8XorU(memory_args)
+        | Instruction::I32AtomicRmw8XchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                8,
+                &[ValueType::I32],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I32AtomicRmw16AddU(memory_args)
+        | Instruction::I32AtomicRmw16SubU(memory_args)
+        | Instruction::I32AtomicRmw16AndU(memory_args)
+        | Instruction::I32AtomicRmw16OrU(memory_args)
+        | Instruction::I32AtomicRmw16XorU(memory_args)
+        | Instruction::I32AtomicRmw16XchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                16,
+                &[ValueType::I32],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I64AtomicRmw8AddU(memory_args)
+        | Instruction::I64AtomicRmw8SubU(memory_args)
+        | Instruction::I64AtomicRmw8AndU(memory_args)
+        | Instruction::I64AtomicRmw8OrU(memory_args)
+        | Instruction::I64AtomicRmw8XorU(memory_args)
+        | Instruction::I64AtomicRmw8XchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                8,
+                &[ValueType::I64],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I64AtomicRmw16AddU(memory_args)
+        | Instruction::I64AtomicRmw16SubU(memory_args)
+        | Instruction::I64AtomicRmw16AndU(memory_args)
+        | Instruction::I64AtomicRmw16OrU(memory_args)
+        | Instruction::I64AtomicRmw16XorU(memory_args)
+        | Instruction::I64AtomicRmw16XchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                16,
+                &[ValueType::I64],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I64AtomicRmw32AddU(memory_args)
+        | Instruction::I64AtomicRmw32SubU(memory_args)
+        | Instruction::I64AtomicRmw32AndU(memory_args)
+        | Instruction::I64AtomicRmw32OrU(memory_args)
+        | Instruction::I64AtomicRmw32XorU(memory_args)
+        | Instruction::I64AtomicRmw32XchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[ValueType::I64],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I32AtomicRmwCmpxchg(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[ValueType::I32, ValueType::I32],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I64AtomicRmwCmpxchg(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                64,
+                &[ValueType::I64, ValueType::I64],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I32AtomicRmw8CmpxchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                8,
+                &[ValueType::I32, ValueType::I32],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I32AtomicRmw16CmpxchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                16,
+                &[ValueType::I32, ValueType::I32],
+                &[ValueType::I32],
+            )?;
+        }
+        Instruction::I64AtomicRmw8CmpxchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                8,
+                &[ValueType::I64, ValueType::I64],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I64AtomicRmw16CmpxchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                16,
+                &[ValueType::I64, ValueType::I64],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::I64AtomicRmw32CmpxchgU(memory_args) => {
+            validate_atomic_instruction(
+                context,
+                expression_context,
+                memory_args,
+                32,
+                &[ValueType::I64, ValueType::I64],
+                &[ValueType::I64],
+            )?;
+        }
+        Instruction::AtomicFence => {}
     };
 
     Ok(())
@@ -683,14 +2549,17 @@ fn validate_instruction(
 pub fn validate_expression(
     context: &Context,
     expression: &Expression,
-    _return_types: Vec<ValueType>,
+    return_types: Vec<ValueType>,
 ) -> ValidationResult {
-    let mut expression_context = ExpressionContext::new();
+    let mut expression_context = ExpressionContext::new(context);
+    expression_context.push_control(false, vec![], return_types);
 
     for instruction in expression {
-        validate_instruction(&context, &mut expression_context, &instruction)?;
+        validate_instruction(context, &mut expression_context, instruction)?;
     }
 
+    expression_context.pop_control()?;
+
     Ok(())
 }
 
@@ -705,8 +2574,23 @@ pub fn validate_constant_expression(
             Instruction::I64Const(_) => {}
             Instruction::F32Const(_) => {}
             Instruction::F64Const(_) => {}
+            Instruction::V128Const(_) => {}
+            Instruction::RefNull(_) => {}
+            Instruction::RefFunc(_) => {}
+            Instruction::RefI31 => {}
+            Instruction::AnyConvertExtern | Instruction::ExternConvertAny => {}
+            Instruction::StructNew(_) | Instruction::StructNewDefault(_) => {}
+            Instruction::ArrayNew(_)
+            | Instruction::ArrayNewDefault(_)
+            | Instruction::ArrayNewFixed(_, _) => {}
+            Instruction::I32Add | Instruction::I32Sub | Instruction::I32Mul => {}
+            Instruction::I64Add | Instruction::I64Sub | Instruction::I64Mul => {}
             Instruction::GlobalGet(global) => {
-                context.get_global(*global)?;
+                if context.get_global(*global)?.mutability != GlobalTypeMutability::Const {
+                    return Err(ValidationError::from(
+                        "Invalid global.get on a variable global in constant expression",
+                    ));
+                }
             }
             _ => {
                 return Err(ValidationError::from(
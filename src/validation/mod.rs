@@ -1,5 +1,5 @@
 mod validation;
-mod types;
+pub(crate) mod types;
 mod instructions;
 pub mod modules;
 
@@ -1,22 +1,56 @@
 use crate::structure::*;
 use crate::validation::validation::{ValidationResult, ValidationError, Context};
-use crate::validation::types::{validate_table_type, validate_function_type, validate_memory_type, validate_global_type};
+use crate::validation::types::{validate_table_type, validate_recursive_type, canonicalize_types, validate_memory_type, validate_global_type, validate_value_type, is_defaultable, match_reference_type};
 use crate::validation::instructions::{validate_expression, validate_constant_expression};
 
 
 // https://webassembly.github.io/spec/core/valid/modules.html#valid-func
 fn validate_function(context: &Context, function: &Function) -> ValidationResult {
-    let function_type = context.get_function_type(function.function_type)?;
+    let (params, returns) = context.get_function_type(function.function_type)?;
+
+    // Parameters are always initialized, while declared locals are only initialized when their
+    // type has a default value.
+    let mut locals: Vec<(ValueType, bool)> = params
+        .iter()
+        .map(|value_type| (*value_type, true))
+        .collect();
+    for (count, value_type) in &function.locals {
+        validate_value_type(context, value_type)?;
+
+        for _ in 0..*count {
+            locals.push((*value_type, is_defaultable(value_type)));
+        }
+    }
+
+    let function_context = Context {
+        locals: &locals,
+        return_types: Some(returns),
+        ..*context
+    };
 
-    let (_params, _returns) = function_type;
-    // TODO: Validate expression
+    validate_expression(&function_context, &function.body, returns.clone())?;
 
     Ok(())
 }
 
-// https://webassembly.github.io/spec/core/valid/modules.html#tables
-fn validate_table(table: &Table) -> ValidationResult {
-    validate_table_type(&table.table_type)?;
+// Tables of non-nullable references have no default element, so they must have an initializer.
+//
+// https://webassembly.github.io/function-references/core/valid/modules.html#tables
+fn validate_table(context: &Context, table: &Table) -> ValidationResult {
+    validate_table_type(context, &table.table_type)?;
+
+    let element_type = ValueType::Ref(table.table_type.element_type);
+    match &table.init {
+        Some(init) => {
+            validate_expression(context, init, vec![element_type])?;
+            validate_constant_expression(context, init)?;
+        }
+        None if !is_defaultable(&element_type) => {
+            return Err(ValidationError::from("Missing initializer for a non-nullable table"));
+        }
+        None => {}
+    }
+
     Ok(())
 }
 
@@ -26,9 +60,25 @@ fn validate_memory(memory: &Memory) -> ValidationResult {
     Ok(())
 }
 
+// https://webassembly.github.io/exception-handling/core/valid/types.html#tag-types
+fn validate_tag_type(context: &Context, tag_type: u32) -> ValidationResult {
+    let (_, results) = context.get_function_type(tag_type)?;
+    if !results.is_empty() {
+        return Err(ValidationError::from("Invalid tag type with non-empty results"));
+    }
+
+    Ok(())
+}
+
+// https://webassembly.github.io/exception-handling/core/valid/modules.html#tags
+fn validate_tag(context: &Context, tag: &Tag) -> ValidationResult {
+    validate_tag_type(context, tag.tag_type)?;
+    Ok(())
+}
+
 // https://webassembly.github.io/spec/core/valid/modules.html#valid-global
 fn validate_global(context: &Context, global: &Global) -> ValidationResult {
-    validate_global_type(&global.global_type)?;
+    validate_global_type(context, &global.global_type)?;
 
     validate_expression(context, &global.init, vec![global.global_type.value_type])?;
     validate_constant_expression(context, &global.init)?;
@@ -38,16 +88,19 @@ fn validate_global(context: &Context, global: &Global) -> ValidationResult {
 
 // https://webassembly.github.io/spec/core/valid/modules.html#valid-elem
 fn validate_element(context: &Context, element: &Element) -> ValidationResult {
-    context.get_table(element.table)?;
-
-    // No need to validate the table type, since there is only one type of element types in table
-    // right now.
+    if let ElementMode::Active { table, offset } = &element.mode {
+        let table_type = context.get_table(*table)?;
+        if !match_reference_type(context, &element.element_type, &table_type.element_type) {
+            return Err(ValidationError::from("Mismatching table and element types"));
+        }
 
-    validate_expression(context, &element.offset, vec![ValueType::I32])?;
-    validate_constant_expression(context, &element.offset)?;
+        validate_expression(context, offset, vec![ValueType::I32])?;
+        validate_constant_expression(context, offset)?;
+    }
 
     for init in &element.init {
-        context.get_function(*init)?;
+        validate_expression(context, init, vec![ValueType::Ref(element.element_type)])?;
+        validate_constant_expression(context, init)?;
     }
 
     Ok(())
@@ -55,20 +108,19 @@ fn validate_element(context: &Context, element: &Element) -> ValidationResult {
 
 // https://webassembly.github.io/spec/core/valid/modules.html#valid-data
 fn validate_data(context: &Context, data: &Data) -> ValidationResult {
-    context.get_memory(data.data)?;
+    if let DataMode::Active { memory, offset } = &data.mode {
+        let memory_type = context.get_memory(*memory)?;
 
-    validate_expression(context, &data.offset, vec![ValueType::I32])?;
-    validate_constant_expression(context, &data.offset)?;
+        validate_expression(context, offset, vec![memory_type.address_type.into()])?;
+        validate_constant_expression(context, offset)?;
+    }
 
     Ok(())
 }
 
 // https://webassembly.github.io/spec/core/valid/modules.html#valid-start
 fn validate_start(context: &Context, start: &StartFunction) -> ValidationResult {
-    let function = context.get_function(start.function)?;
-
-    // Function type have been validated previously, we should not worry to unwrap the value.
-    let (params, returns) = context.get_function_type(function.function_type)?;
+    let (params, returns) = context.get_function(start.function)?;
     if !params.is_empty() || !returns.is_empty() {
         return Err(ValidationError::from("Invalid start function"));
     }
@@ -79,13 +131,14 @@ fn validate_start(context: &Context, start: &StartFunction) -> ValidationResult
 // https://webassembly.github.io/spec/core/valid/modules.html#valid-import
 fn validate_import(context: &Context, import: &Import) -> ValidationResult {
     match &import.descriptor {
-        ImportDescriptor::Function(function) => {
-            context.get_function(*function)?;
+        ImportDescriptor::Function(function_type) => {
+            context.get_function_type(*function_type)?;
             Ok(())
         }
-        ImportDescriptor::Table(table_type) => validate_table_type(table_type),
+        ImportDescriptor::Table(table_type) => validate_table_type(context, table_type),
         ImportDescriptor::Memory(memory_type) => validate_memory_type(memory_type),
-        ImportDescriptor::Global(global_type) => validate_global_type(global_type),
+        ImportDescriptor::Global(global_type) => validate_global_type(context, global_type),
+        ImportDescriptor::Tag(tag_type) => validate_tag_type(context, *tag_type),
     }
 }
 
@@ -104,6 +157,9 @@ fn validate_export(context: &Context, export: &Export) -> ValidationResult {
         ExportDescriptor::Global(global) => {
             context.get_global(*global)?;
         }
+        ExportDescriptor::Tag(tag) => {
+            context.get_tag(*tag)?;
+        }
     };
 
     Ok(())
@@ -111,31 +167,108 @@ fn validate_export(context: &Context, export: &Export) -> ValidationResult {
 
 // https://webassembly.github.io/spec/core/valid/modules.html#valid-module
 pub fn validate(module: &Module) -> ValidationResult {
+    let types: Vec<&SubType> = module.types.iter().flatten().collect();
+    let canonical_types = canonicalize_types(&module.types);
+    let mut functions = vec![];
+    let mut tables = vec![];
+    let mut memories = vec![];
+    let mut tags = vec![];
+    let mut globals = vec![];
+    let mut references = vec![];
+
+    for import in &module.imports {
+        match &import.descriptor {
+            ImportDescriptor::Function(function_type) => functions.push(*function_type),
+            ImportDescriptor::Table(table_type) => tables.push(table_type),
+            ImportDescriptor::Memory(memory_type) => memories.push(memory_type),
+            ImportDescriptor::Global(global_type) => globals.push(global_type),
+            ImportDescriptor::Tag(tag_type) => tags.push(*tag_type),
+        }
+    }
+    functions.extend(
+        module
+            .functions
+            .iter()
+            .map(|function| function.function_type),
+    );
+    tables.extend(module.tables.iter().map(|table| &table.table_type));
+    memories.extend(module.memories.iter().map(|memory| &memory.memory_type));
+    tags.extend(module.tags.iter().map(|tag| tag.tag_type));
+    globals.extend(module.globals.iter().map(|global| &global.global_type));
+
+    // Functions can only be referenced with ref.func within function bodies if they are declared
+    // somewhere else in the module: in table and global initializers, element segments or exports.
+    let constant_expressions = module
+        .tables
+        .iter()
+        .filter_map(|table| table.init.as_ref())
+        .chain(module.globals.iter().map(|global| &global.init))
+        .chain(module.elements.iter().flat_map(|element| &element.init));
+    for expression in constant_expressions {
+        for instruction in expression {
+            if let Instruction::RefFunc(function_index) = instruction {
+                references.push(*function_index);
+            }
+        }
+    }
+    for export in &module.exports {
+        if let ExportDescriptor::Function(function_index) = export.descriptor {
+            references.push(function_index);
+        }
+    }
+
     let context = Context {
-        function_types: &module.function_types,
-        functions: &module.functions,
-        tables: &module.tables,
-        memories: &module.memories,
-        globals: &module.globals,
+        types: &types,
+        canonical_types: &canonical_types,
+        functions: &functions,
+        tables: &tables,
+        memories: &memories,
+        tags: &tags,
+        globals: &globals,
         elements: &module.elements,
-        locals: vec![],
+        data_count: module.data_count,
+        references: &references,
+        locals: &[],
+        return_types: None,
     };
 
-    for function_type in &module.function_types {
-        validate_function_type(&function_type)?;
+    // Types can only refer to the types of their own recursion group and of the previous ones.
+    let mut first_type_index = 0;
+    for recursive_type in &module.types {
+        let end_type_index = first_type_index + recursive_type.len();
+        let type_context = Context {
+            types: &types[..end_type_index],
+            ..context
+        };
+        validate_recursive_type(&type_context, first_type_index as u32, recursive_type)?;
+
+        first_type_index = end_type_index;
     }
     for function in &module.functions {
         validate_function(&context, &function)?;
     }
+    // Table and global initializers can only refer to the imported globals, and for globals, the
+    // globals defined before them.
+    let imported_globals = context.globals.len() - module.globals.len();
+    let table_context = Context {
+        globals: &globals[..imported_globals],
+        ..context
+    };
     for table in &module.tables {
-        validate_table(&table)?;
+        validate_table(&table_context, table)?;
     }
     for memory in &module.memories {
         validate_memory(&memory)?;
     }
-    for global in &module.globals {
-        // TODO: Use custom context
-        validate_global(&context, &global)?;
+    for tag in &module.tags {
+        validate_tag(&context, tag)?;
+    }
+    for (index, global) in module.globals.iter().enumerate() {
+        let global_context = Context {
+            globals: &globals[..imported_globals + index],
+            ..context
+        };
+        validate_global(&global_context, global)?;
     }
     for element in &module.elements {
         validate_element(&context, &element)?;
@@ -154,13 +287,6 @@ pub fn validate(module: &Module) -> ValidationResult {
         validate_export(&context, &export)?;
     }
 
-    if module.tables.len() > 1 {
-        return Err(ValidationError::from("Too many tables"));
-    }
-    if module.memories.len() > 1 {
-        return Err(ValidationError::from("Too many memories"));
-    }
-
     for i in 0..module.exports.len() {
         for j in (i + 1)..module.exports.len() {
             if module.exports[i].name == module.exports[j].name {
@@ -1,10 +1,9 @@
-use std::{u16, u32};
-
 use crate::structure::*;
-use crate::validation::validation::{ValidationError, ValidationResult};
+use crate::validation::validation::{Context, ValidationError, ValidationResult};
+use std::collections::HashMap;
 
 // https://webassembly.github.io/spec/core/valid/types.html#limits
-pub fn validate_limits(limits: &Limits, range: u32) -> ValidationResult {
+pub fn validate_limits(limits: &Limits, range: u64) -> ValidationResult {
     if limits.min > range {
         return Err(ValidationError::from("Limit minimum is above valid range"));
     }
@@ -26,30 +25,418 @@ pub fn validate_limits(limits: &Limits, range: u32) -> ValidationResult {
     Ok(())
 }
 
+// https://webassembly.github.io/function-references/core/valid/types.html#heap-types
+pub fn validate_heap_type(context: &Context, heap_type: &HeapType) -> ValidationResult {
+    if let HeapType::Index(type_index) = heap_type {
+        context.get_type(*type_index)?;
+    }
+
+    Ok(())
+}
+
+// https://webassembly.github.io/function-references/core/valid/types.html#value-types
+pub fn validate_value_type(context: &Context, value_type: &ValueType) -> ValidationResult {
+    if let ValueType::Ref(ref_type) = value_type {
+        validate_heap_type(context, &ref_type.heap_type)?;
+    }
+
+    Ok(())
+}
+
 // https://webassembly.github.io/spec/core/valid/types.html#valid-functype
-pub fn validate_function_type(function_type: &FunctionType) -> ValidationResult {
-    let (_params, returns) = function_type;
+pub fn validate_function_type(context: &Context, function_type: &FunctionType) -> ValidationResult {
+    let (params, results) = function_type;
+    for value_type in params.iter().chain(results) {
+        validate_value_type(context, value_type)?;
+    }
+
+    Ok(())
+}
+
+// https://webassembly.github.io/gc/core/valid/types.html#field-types
+fn validate_field_type(context: &Context, field_type: &FieldType) -> ValidationResult {
+    if let StorageType::Value(value_type) = &field_type.storage_type {
+        validate_value_type(context, value_type)?;
+    }
+
+    Ok(())
+}
+
+// https://webassembly.github.io/gc/core/valid/types.html#composite-types
+fn validate_composite_type(context: &Context, composite_type: &CompositeType) -> ValidationResult {
+    match composite_type {
+        CompositeType::Function(function_type) => validate_function_type(context, function_type),
+        CompositeType::Struct(field_types) => {
+            for field_type in field_types {
+                validate_field_type(context, field_type)?;
+            }
+
+            Ok(())
+        }
+        CompositeType::Array(field_type) => validate_field_type(context, field_type),
+    }
+}
 
-    if returns.len() > 1 {
-        return Err(ValidationError::from("Too many return value"));
+// A sub type can only declare a single supertype, which must be defined before it and must not be
+// final.
+//
+// https://webassembly.github.io/gc/core/valid/types.html#sub-types
+fn validate_sub_type(context: &Context, type_index: u32, sub_type: &SubType) -> ValidationResult {
+    validate_composite_type(context, &sub_type.composite_type)?;
+
+    if sub_type.supertypes.len() > 1 {
+        return Err(ValidationError::from("Too many supertypes"));
+    }
+
+    for supertype_index in &sub_type.supertypes {
+        if *supertype_index >= type_index {
+            return Err(ValidationError::from("Invalid supertype reference"));
+        }
+
+        let supertype = context.get_type(*supertype_index)?;
+        if supertype.is_final {
+            return Err(ValidationError::from("Invalid subtype of a final type"));
+        }
+        if !match_composite_type(context, &sub_type.composite_type, &supertype.composite_type) {
+            return Err(ValidationError::from(
+                "Mismatching sub type with its supertype",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// The context types must be limited to the types of the previous recursion groups and of this
+// one, starting at the given type index.
+//
+// https://webassembly.github.io/gc/core/valid/types.html#recursive-types
+pub fn validate_recursive_type(
+    context: &Context,
+    first_type_index: u32,
+    recursive_type: &RecursiveType,
+) -> ValidationResult {
+    for (index, sub_type) in recursive_type.iter().enumerate() {
+        validate_sub_type(context, first_type_index + index as u32, sub_type)?;
     }
 
     Ok(())
 }
 
 // https://webassembly.github.io/spec/core/valid/types.html#valid-tabletype
-pub fn validate_table_type(table_type: &TableType) -> ValidationResult {
-    validate_limits(&table_type.limits, u32::MAX)?;
+pub fn validate_table_type(context: &Context, table_type: &TableType) -> ValidationResult {
+    validate_limits(&table_type.limits, u64::from(u32::MAX))?;
+    validate_heap_type(context, &table_type.element_type.heap_type)?;
     Ok(())
 }
 
 // https://webassembly.github.io/spec/core/valid/types.html#valid-memtype
 pub fn validate_memory_type(memory_type: &MemoryType) -> ValidationResult {
-    validate_limits(&memory_type.limits, u16::MAX as u32)?;
+    // Memories are limited to 4GiB of 64KiB pages with 32 bits addresses, and 2^64 bytes with 64
+    // bits addresses.
+    let range = match memory_type.address_type {
+        AddressType::I32 => 1 << 16,
+        AddressType::I64 => 1 << 48,
+    };
+    validate_limits(&memory_type.limits, range)?;
+
+    if memory_type.shared && memory_type.limits.max.is_none() {
+        return Err(ValidationError::from("Shared memory must have a maximum"));
+    }
+
     Ok(())
 }
 
 // https://webassembly.github.io/spec/core/valid/types.html#valid-globaltype
-pub fn validate_global_type(_global_type: &GlobalType) -> ValidationResult {
+pub fn validate_global_type(context: &Context, global_type: &GlobalType) -> ValidationResult {
+    validate_value_type(context, &global_type.value_type)?;
     Ok(())
-}
\ No newline at end of file
+}
+
+// https://webassembly.github.io/function-references/core/valid/types.html#defaultable-types
+//
+// Only non-nullable references have no default value.
+pub fn is_defaultable(value_type: &ValueType) -> bool {
+    match value_type {
+        ValueType::Ref(ref_type) => ref_type.nullable,
+        _ => true,
+    }
+}
+
+// Type indexes within a sub type are replaced with the given mapping, so that the types of
+// different recursion groups can be compared.
+fn map_value_type(value_type: &ValueType, map_index: &impl Fn(u32) -> u32) -> ValueType {
+    match value_type {
+        ValueType::Ref(RefType {
+            nullable,
+            heap_type: HeapType::Index(type_index),
+        }) => ValueType::Ref(RefType {
+            nullable: *nullable,
+            heap_type: HeapType::Index(map_index(*type_index)),
+        }),
+        _ => *value_type,
+    }
+}
+
+fn map_field_type(field_type: &FieldType, map_index: &impl Fn(u32) -> u32) -> FieldType {
+    let storage_type = match &field_type.storage_type {
+        StorageType::Value(value_type) => StorageType::Value(map_value_type(value_type, map_index)),
+        storage_type => *storage_type,
+    };
+
+    FieldType {
+        storage_type,
+        mutability: field_type.mutability,
+    }
+}
+
+fn map_sub_type(sub_type: &SubType, map_index: &impl Fn(u32) -> u32) -> SubType {
+    let composite_type = match &sub_type.composite_type {
+        CompositeType::Function((params, results)) => CompositeType::Function((
+            params
+                .iter()
+                .map(|value_type| map_value_type(value_type, map_index))
+                .collect(),
+            results
+                .iter()
+                .map(|value_type| map_value_type(value_type, map_index))
+                .collect(),
+        )),
+        CompositeType::Struct(field_types) => CompositeType::Struct(
+            field_types
+                .iter()
+                .map(|field_type| map_field_type(field_type, map_index))
+                .collect(),
+        ),
+        CompositeType::Array(field_type) => {
+            CompositeType::Array(map_field_type(field_type, map_index))
+        }
+    };
+
+    SubType {
+        is_final: sub_type.is_final,
+        supertypes: sub_type
+            .supertypes
+            .iter()
+            .map(|type_index| map_index(*type_index))
+            .collect(),
+        composite_type,
+    }
+}
+
+// References within a recursion group are made relative to the group, counting down from the end
+// of the index space so they can not be confused with the other indexes, which are replaced with
+// the given mapping. Groups are identical once rolled when they define the same types.
+//
+// https://webassembly.github.io/gc/core/valid/conventions.html#rolling-and-unrolling
+pub fn roll_recursive_type(
+    recursive_type: &[SubType],
+    first_type_index: u32,
+    map_index: &impl Fn(u32) -> u32,
+) -> RecursiveType {
+    let end_type_index = first_type_index + recursive_type.len() as u32;
+    let roll_index = |type_index: u32| {
+        if type_index >= first_type_index && type_index < end_type_index {
+            u32::MAX - (type_index - first_type_index)
+        } else {
+            map_index(type_index)
+        }
+    };
+
+    recursive_type
+        .iter()
+        .map(|sub_type| map_sub_type(sub_type, &roll_index))
+        .collect()
+}
+
+// Two types are equivalent when their recursion groups are identical, once references to the
+// previous groups are replaced with the canonical index of the referenced types.
+//
+// The canonical index of a type is the index of the first equivalent type of the module.
+//
+// https://webassembly.github.io/gc/core/valid/conventions.html#rolling-and-unrolling
+pub fn canonicalize_types(recursive_types: &[RecursiveType]) -> Vec<u32> {
+    let mut canonical_types: Vec<u32> = Vec::new();
+    let mut groups: HashMap<RecursiveType, u32> = HashMap::new();
+
+    for recursive_type in recursive_types {
+        let first_type_index = canonical_types.len() as u32;
+        let map_index = |type_index: u32| {
+            canonical_types
+                .get(type_index as usize)
+                .copied()
+                .unwrap_or(type_index)
+        };
+        let group = roll_recursive_type(recursive_type, first_type_index, &map_index);
+
+        let canonical_index = *groups.entry(group).or_insert(first_type_index);
+        canonical_types.extend(canonical_index..canonical_index + recursive_type.len() as u32);
+    }
+
+    canonical_types
+}
+
+// A concrete type matches another one when they are equivalent, or when one of its declared
+// supertypes transitively matches it.
+//
+// https://webassembly.github.io/gc/core/valid/matching.html#heap-types
+fn match_type_index(context: &Context, actual: u32, expected: u32) -> bool {
+    let canonical_type = |type_index: u32| context.canonical_types.get(type_index as usize);
+
+    let mut current = actual;
+    loop {
+        if canonical_type(current).is_some() && canonical_type(current) == canonical_type(expected)
+        {
+            return true;
+        }
+
+        // Supertypes are always defined before their sub types, which guarantees termination.
+        match context
+            .get_type(current)
+            .ok()
+            .and_then(|sub_type| sub_type.supertypes.first())
+        {
+            Some(supertype) if *supertype < current => current = *supertype,
+            _ => return false,
+        }
+    }
+}
+
+// Heap types form separate hierarchies, each one with an abstract top type and bottom type.
+//
+// https://webassembly.github.io/gc/core/valid/matching.html#heap-types
+pub fn top_heap_type(context: &Context, heap_type: &HeapType) -> HeapType {
+    match heap_type {
+        HeapType::Func | HeapType::NoFunc => HeapType::Func,
+        HeapType::Extern | HeapType::NoExtern => HeapType::Extern,
+        HeapType::Exn | HeapType::NoExn => HeapType::Exn,
+        HeapType::Any
+        | HeapType::Eq
+        | HeapType::I31
+        | HeapType::Struct
+        | HeapType::Array
+        | HeapType::None => HeapType::Any,
+        HeapType::Index(type_index) => match context.get_type(*type_index) {
+            Ok(SubType {
+                composite_type: CompositeType::Function(_),
+                ..
+            }) => HeapType::Func,
+            _ => HeapType::Any,
+        },
+    }
+}
+
+pub fn bottom_heap_type(context: &Context, heap_type: &HeapType) -> HeapType {
+    match top_heap_type(context, heap_type) {
+        HeapType::Func => HeapType::NoFunc,
+        HeapType::Extern => HeapType::NoExtern,
+        HeapType::Exn => HeapType::NoExn,
+        _ => HeapType::None,
+    }
+}
+
+// https://webassembly.github.io/gc/core/valid/matching.html#heap-types
+pub fn match_heap_type(context: &Context, actual: &HeapType, expected: &HeapType) -> bool {
+    match (actual, expected) {
+        (HeapType::Index(actual), HeapType::Index(expected)) => {
+            match_type_index(context, *actual, *expected)
+        }
+        (HeapType::Index(type_index), _) => match context.get_type(*type_index) {
+            Ok(sub_type) => {
+                let abstract_type = match sub_type.composite_type {
+                    CompositeType::Function(_) => HeapType::Func,
+                    CompositeType::Struct(_) => HeapType::Struct,
+                    CompositeType::Array(_) => HeapType::Array,
+                };
+
+                match_heap_type(context, &abstract_type, expected)
+            }
+            Err(_) => false,
+        },
+        (HeapType::NoFunc, _)
+        | (HeapType::NoExtern, _)
+        | (HeapType::NoExn, _)
+        | (HeapType::None, _) => *actual == bottom_heap_type(context, expected),
+        (HeapType::Eq, HeapType::Any) => true,
+        (HeapType::I31, HeapType::Any | HeapType::Eq) => true,
+        (HeapType::Struct, HeapType::Any | HeapType::Eq) => true,
+        (HeapType::Array, HeapType::Any | HeapType::Eq) => true,
+        _ => actual == expected,
+    }
+}
+
+// https://webassembly.github.io/function-references/core/valid/matching.html#reference-types
+pub fn match_reference_type(context: &Context, actual: &RefType, expected: &RefType) -> bool {
+    (expected.nullable || !actual.nullable)
+        && match_heap_type(context, &actual.heap_type, &expected.heap_type)
+}
+
+// https://webassembly.github.io/function-references/core/valid/matching.html#value-types
+pub fn match_value_type(context: &Context, actual: &ValueType, expected: &ValueType) -> bool {
+    match (actual, expected) {
+        (ValueType::Ref(actual), ValueType::Ref(expected)) => {
+            match_reference_type(context, actual, expected)
+        }
+        _ => actual == expected,
+    }
+}
+
+// https://webassembly.github.io/function-references/core/valid/matching.html#result-types
+pub fn match_result_type(context: &Context, actual: &[ValueType], expected: &[ValueType]) -> bool {
+    actual.len() == expected.len()
+        && actual
+            .iter()
+            .zip(expected)
+            .all(|(actual, expected)| match_value_type(context, actual, expected))
+}
+
+// https://webassembly.github.io/gc/core/valid/matching.html#storage-types
+pub fn match_storage_type(context: &Context, actual: &StorageType, expected: &StorageType) -> bool {
+    match (actual, expected) {
+        (StorageType::Value(actual), StorageType::Value(expected)) => {
+            match_value_type(context, actual, expected)
+        }
+        _ => actual == expected,
+    }
+}
+
+// Mutable fields are invariant, while immutable ones are covariant.
+//
+// https://webassembly.github.io/gc/core/valid/matching.html#field-types
+fn match_field_type(context: &Context, actual: &FieldType, expected: &FieldType) -> bool {
+    actual.mutability == expected.mutability
+        && match_storage_type(context, &actual.storage_type, &expected.storage_type)
+        && (actual.mutability == GlobalTypeMutability::Const
+            || match_storage_type(context, &expected.storage_type, &actual.storage_type))
+}
+
+// Function parameters are contravariant and results are covariant, while structs can be extended
+// with additional fields.
+//
+// https://webassembly.github.io/gc/core/valid/matching.html#composite-types
+fn match_composite_type(
+    context: &Context,
+    actual: &CompositeType,
+    expected: &CompositeType,
+) -> bool {
+    match (actual, expected) {
+        (
+            CompositeType::Function((actual_params, actual_results)),
+            CompositeType::Function((expected_params, expected_results)),
+        ) => {
+            match_result_type(context, expected_params, actual_params)
+                && match_result_type(context, actual_results, expected_results)
+        }
+        (CompositeType::Struct(actual_fields), CompositeType::Struct(expected_fields)) => {
+            actual_fields.len() >= expected_fields.len()
+                && actual_fields
+                    .iter()
+                    .zip(expected_fields)
+                    .all(|(actual, expected)| match_field_type(context, actual, expected))
+        }
+        (CompositeType::Array(actual_field), CompositeType::Array(expected_field)) => {
+            match_field_type(context, actual_field, expected_field)
+        }
+        _ => false,
+    }
+}
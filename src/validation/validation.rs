@@ -1,4 +1,5 @@
 use crate::structure::*;
+use crate::validation::types::validate_value_type;
 
 #[derive(Debug)]
 pub struct ValidationError {
@@ -19,63 +20,143 @@ impl ValidationError {
 
 pub type ValidationResult = Result<(), ValidationError>;
 
-#[derive(Debug)]
+/// https://webassembly.github.io/spec/core/valid/conventions.html#contexts
+///
+/// The type index space flattens the recursion groups of the module, and each type is paired with
+/// the index of its canonical equivalent in `canonical_types`. The function, table, memory, tag and
+/// global index spaces include the imported entities first, followed by the ones defined in the
+/// module. Locals are paired with their initialization status,
+/// as locals without a default value have to be set before being used.
+///
+/// The index spaces are borrowed, so narrower contexts are copied without copying their entries.
+#[derive(Debug, Clone, Copy)]
 pub struct Context<'a> {
-    pub function_types: &'a Vec<FunctionType>,
-    pub functions: &'a Vec<Function>,
-    pub tables: &'a Vec<Table>,
-    pub memories: &'a Vec<Memory>,
-    pub globals: &'a Vec<Global>,
-    pub elements: &'a Vec<Element>,
-    pub locals: Vec<ValueType>,
+    pub types: &'a [&'a SubType],
+    pub canonical_types: &'a [u32],
+    pub functions: &'a [u32],
+    pub tables: &'a [&'a TableType],
+    pub memories: &'a [&'a MemoryType],
+    pub tags: &'a [u32],
+    pub globals: &'a [&'a GlobalType],
+    pub elements: &'a [Element],
+    pub data_count: Option<u32>,
+    pub references: &'a [u32],
+    pub locals: &'a [(ValueType, bool)],
+    pub return_types: Option<&'a [ValueType]>,
 }
 
 impl<'a> Context<'a> {
+    pub fn get_type(&self, type_index: u32) -> Result<&'a SubType, ValidationError> {
+        self
+            .types
+            .get(type_index as usize)
+            .copied()
+            .ok_or(ValidationError::from("Invalid type reference"))
+    }
+
     pub fn get_function_type(
         &self,
         function_type_index: u32,
     ) -> Result<&'a FunctionType, ValidationError> {
-        self
-            .function_types
-            .get(function_type_index as usize)
-            .ok_or(ValidationError::from("Invalid function type reference"))
+        match &self.get_type(function_type_index)?.composite_type {
+            CompositeType::Function(function_type) => Ok(function_type),
+            _ => Err(ValidationError::from("Invalid function type reference")),
+        }
     }
 
-    pub fn get_function(
+    pub fn get_struct_type(
         &self,
-        function_index: u32,
-    ) -> Result<&'a Function, ValidationError> {
-        self
+        struct_type_index: u32,
+    ) -> Result<&'a Vec<FieldType>, ValidationError> {
+        match &self.get_type(struct_type_index)?.composite_type {
+            CompositeType::Struct(field_types) => Ok(field_types),
+            _ => Err(ValidationError::from("Invalid struct type reference")),
+        }
+    }
+
+    pub fn get_array_type(&self, array_type_index: u32) -> Result<&'a FieldType, ValidationError> {
+        match &self.get_type(array_type_index)?.composite_type {
+            CompositeType::Array(field_type) => Ok(field_type),
+            _ => Err(ValidationError::from("Invalid array type reference")),
+        }
+    }
+
+    pub fn get_function(&self, function_index: u32) -> Result<&'a FunctionType, ValidationError> {
+        let function_type_index = self
             .functions
             .get(function_index as usize)
-            .ok_or(ValidationError::from("Invalid function reference"))
+            .ok_or(ValidationError::from("Invalid function reference"))?;
+
+        self.get_function_type(*function_type_index)
     }
 
-    pub fn get_table(&self, table_index: u32) -> Result<&'a Table, ValidationError> {
+    pub fn get_table(&self, table_index: u32) -> Result<&'a TableType, ValidationError> {
         self
             .tables
             .get(table_index as usize)
+            .copied()
             .ok_or(ValidationError::from("Invalid table reference"))
     }
 
-    pub fn get_memory(&self, memory_index: u32) -> Result<&'a Memory, ValidationError> {
+    pub fn get_memory(&self, memory_index: u32) -> Result<&'a MemoryType, ValidationError> {
         self
             .memories
             .get(memory_index as usize)
+            .copied()
             .ok_or(ValidationError::from("Invalid memory reference"))
     }
 
-    pub fn get_global(&self, global_index: u32) -> Result<&'a Global, ValidationError> {
+    pub fn get_tag(&self, tag_index: u32) -> Result<&'a FunctionType, ValidationError> {
+        let function_type_index = self
+            .tags
+            .get(tag_index as usize)
+            .ok_or(ValidationError::from("Invalid tag reference"))?;
+
+        self.get_function_type(*function_type_index)
+    }
+
+    pub fn get_global(&self, global_index: u32) -> Result<&'a GlobalType, ValidationError> {
         self
             .globals
             .get(global_index as usize)
+            .copied()
             .ok_or(ValidationError::from("Invalid global reference"))
     }
 
+    pub fn get_element(&self, element_index: u32) -> Result<&'a Element, ValidationError> {
+        self
+            .elements
+            .get(element_index as usize)
+            .ok_or(ValidationError::from("Invalid element reference"))
+    }
+
+    // Data segments can only be referenced from the code section when the number of segments has
+    // been declared upfront in the data count section.
+    pub fn get_data(&self, data_index: u32) -> ValidationResult {
+        match self.data_count {
+            Some(count) if data_index < count => Ok(()),
+            Some(_) => Err(ValidationError::from("Invalid data reference")),
+            None => Err(ValidationError::from("Missing data count section")),
+        }
+    }
+
     pub fn get_local(&self, local_index: u32) -> Result<&ValueType, ValidationError> {
         self
             .locals
             .get(local_index as usize)
+            .map(|(value_type, _)| value_type)
             .ok_or(ValidationError::from("Invalid local reference"))
     }
+
+    // https://webassembly.github.io/spec/core/valid/types.html#valid-blocktype
+    pub fn get_block_type(&self, block_type: &BlockType) -> Result<FunctionType, ValidationError> {
+        match block_type {
+            BlockType::Void => Ok((vec![], vec![])),
+            BlockType::Return(value_type) => {
+                validate_value_type(self, value_type)?;
+                Ok((vec![], vec![*value_type]))
+            }
+            BlockType::TypeIndex(index) => Ok(self.get_function_type(*index)?.clone()),
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,146 @@
+;; Bulk memory and table instructions, and passive segments.
+
+(module
+  (memory 1)
+  (data "\aa\bb\cc\dd")
+
+  (func (export "fill") (param i32 i32 i32)
+    (memory.fill (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "copy") (param i32 i32 i32)
+    (memory.copy (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init") (param i32 i32 i32)
+    (memory.init 0 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "load8_u") (param i32) (result i32)
+    (i32.load8_u (local.get 0)))
+)
+
+(invoke "fill" (i32.const 1) (i32.const 0xff) (i32.const 3))
+(assert_return (invoke "load8_u" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "load8_u" (i32.const 1)) (i32.const 0xff))
+(assert_return (invoke "load8_u" (i32.const 3)) (i32.const 0xff))
+(assert_return (invoke "load8_u" (i32.const 4)) (i32.const 0))
+
+;; Only the low byte of the value is written.
+(invoke "fill" (i32.const 10) (i32.const 0x1234) (i32.const 1))
+(assert_return (invoke "load8_u" (i32.const 10)) (i32.const 0x34))
+
+(assert_trap (invoke "fill" (i32.const 65535) (i32.const 0) (i32.const 2))
+  "out of bounds memory access")
+(invoke "fill" (i32.const 65536) (i32.const 0) (i32.const 0))
+(assert_trap (invoke "fill" (i32.const 65537) (i32.const 0) (i32.const 0))
+  "out of bounds memory access")
+
+;; Overlapping copies behave as if the source was copied to a temporary buffer first.
+(invoke "init" (i32.const 100) (i32.const 0) (i32.const 4))
+(invoke "copy" (i32.const 101) (i32.const 100) (i32.const 4))
+(assert_return (invoke "load8_u" (i32.const 100)) (i32.const 0xaa))
+(assert_return (invoke "load8_u" (i32.const 101)) (i32.const 0xaa))
+(assert_return (invoke "load8_u" (i32.const 102)) (i32.const 0xbb))
+(assert_return (invoke "load8_u" (i32.const 104)) (i32.const 0xdd))
+(invoke "copy" (i32.const 100) (i32.const 102) (i32.const 3))
+(assert_return (invoke "load8_u" (i32.const 100)) (i32.const 0xbb))
+(assert_return (invoke "load8_u" (i32.const 101)) (i32.const 0xcc))
+(assert_return (invoke "load8_u" (i32.const 102)) (i32.const 0xdd))
+(assert_return (invoke "load8_u" (i32.const 103)) (i32.const 0xcc))
+
+(assert_trap (invoke "copy" (i32.const 65535) (i32.const 0) (i32.const 2))
+  "out of bounds memory access")
+(assert_trap (invoke "copy" (i32.const 0) (i32.const 65535) (i32.const 2))
+  "out of bounds memory access")
+(invoke "copy" (i32.const 65536) (i32.const 65536) (i32.const 0))
+(assert_trap (invoke "copy" (i32.const 65537) (i32.const 0) (i32.const 0))
+  "out of bounds memory access")
+(assert_trap (invoke "copy" (i32.const 0) (i32.const 65537) (i32.const 0))
+  "out of bounds memory access")
+
+(module
+  (table 10 funcref)
+  (elem (i32.const 0) $zero $one $two)
+  (elem $passive func $zero)
+  (func $zero (result i32) (i32.const 0))
+  (func $one (result i32) (i32.const 1))
+  (func $two (result i32) (i32.const 2))
+
+  (func (export "copy") (param i32 i32 i32)
+    (table.copy (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init") (param i32)
+    (table.init $passive (local.get 0) (i32.const 0) (i32.const 1)))
+  (func (export "drop") (elem.drop $passive))
+  (func (export "call") (param i32) (result i32)
+    (call_indirect (result i32) (local.get 0)))
+)
+
+(invoke "copy" (i32.const 3) (i32.const 0) (i32.const 3))
+(assert_return (invoke "call" (i32.const 3)) (i32.const 0))
+(assert_return (invoke "call" (i32.const 4)) (i32.const 1))
+(assert_return (invoke "call" (i32.const 5)) (i32.const 2))
+(assert_trap (invoke "call" (i32.const 6)) "uninitialized element")
+
+(invoke "copy" (i32.const 1) (i32.const 0) (i32.const 3))
+(assert_return (invoke "call" (i32.const 1)) (i32.const 0))
+(assert_return (invoke "call" (i32.const 2)) (i32.const 1))
+(assert_return (invoke "call" (i32.const 3)) (i32.const 2))
+
+(assert_trap (invoke "copy" (i32.const 9) (i32.const 0) (i32.const 2))
+  "out of bounds table access")
+(invoke "copy" (i32.const 10) (i32.const 0) (i32.const 0))
+(assert_trap (invoke "copy" (i32.const 11) (i32.const 0) (i32.const 0))
+  "out of bounds table access")
+
+(invoke "init" (i32.const 9))
+(assert_return (invoke "call" (i32.const 9)) (i32.const 0))
+(invoke "drop")
+(assert_trap (invoke "init" (i32.const 9)) "out of bounds table access")
+
+(assert_invalid
+  (module (func (memory.fill (i32.const 0) (i32.const 0) (i32.const 0))))
+  "unknown memory")
+(assert_invalid
+  (module (func (memory.copy (i32.const 0) (i32.const 0) (i32.const 0))))
+  "unknown memory")
+(assert_invalid
+  (module (memory 1) (func (memory.fill (i32.const 0) (i64.const 0) (i32.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (func (table.copy (i32.const 0) (i32.const 0) (i32.const 0))))
+  "unknown table")
+(assert_invalid
+  (module (table 1 funcref) (func (elem.drop 0)))
+  "unknown elem segment")
+
+;; The data count section has to match the data section.
+(assert_malformed
+  (module binary
+    "\00asm" "\01\00\00\00"
+    "\0c\01\01"                       ;; data count section: 1 segment
+  )
+  "data count and data section have inconsistent lengths")
+(assert_malformed
+  (module binary
+    "\00asm" "\01\00\00\00"
+    "\05\03\01\00\01"                 ;; memory section
+    "\0c\01\01"                       ;; data count section: 1 segment
+    "\0b\05\02\01\00\01\00"           ;; data section: 2 passive segments
+  )
+  "data count and data section have inconsistent lengths")
+
+;; Data indices can only be used in the code section after a data count section.
+(assert_malformed
+  (module binary
+    "\00asm" "\01\00\00\00"
+    "\01\04\01\60\00\00"              ;; type section
+    "\03\02\01\00"                    ;; function section
+    "\05\03\01\00\01"                 ;; memory section
+    "\0a\07\01\05\00\fc\09\00\0b"     ;; code section: data.drop 0
+    "\0b\03\01\01\00"                 ;; data section: 1 passive segment
+  )
+  "data count section required")
+(module binary
+  "\00asm" "\01\00\00\00"
+  "\01\04\01\60\00\00"                ;; type section
+  "\03\02\01\00"                      ;; function section
+  "\05\03\01\00\01"                   ;; memory section
+  "\0c\01\01"                         ;; data count section: 1 segment
+  "\0a\07\01\05\00\fc\09\00\0b"       ;; code section: data.drop 0
+  "\0b\03\01\01\00"                   ;; data section: 1 passive segment
+)
@@ -0,0 +1,106 @@
+;; Tags, throw, throw_ref and try_table. The spec tests assert uncaught exceptions with
+;; assert_exception, which isn't supported, so they are asserted as traps.
+
+(module
+  (tag $empty)
+  (tag $i32 (param i32))
+  (tag $pair (export "pair") (param i32 i64))
+
+  (func (export "no_throw") (result i32)
+    (block $h (result i32)
+      (try_table (result i32) (catch $i32 $h)
+        (i32.const 1))))
+
+  (func (export "catch") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (result i32) (catch $i32 $h)
+        (throw $i32 (local.get 0)))))
+
+  (func (export "catch_all") (result i32)
+    (block $h
+      (try_table (catch_all $h)
+        (throw $empty)))
+    (i32.const 2))
+
+  (func (export "rethrow") (param i32) (result i32)
+    (block $outer (result i32)
+      (try_table (result i32) (catch $i32 $outer)
+        (block $inner (result exnref)
+          (try_table (catch_all_ref $inner)
+            (throw $i32 (local.get 0)))
+          (unreachable))
+        (throw_ref))))
+
+  (func (export "uncaught")
+    (throw $pair (i32.const 1) (i64.const 2)))
+
+  (func (export "catch_ref") (result i32)
+    (block $h (result i32 i64 exnref)
+      (try_table (catch_ref $pair $h)
+        (throw $pair (i32.const 3) (i64.const 4)))
+      (unreachable))
+    (drop)
+    (drop))
+
+  (func $throw_i32 (param i32)
+    (throw $i32 (local.get 0)))
+
+  (func (export "catch_callee") (param i32) (result i32)
+    (block $h (result i32)
+      (try_table (result i32) (catch $i32 $h)
+        (call $throw_i32 (local.get 0))
+        (i32.const 0))))
+
+  (func (export "catch_mismatch") (result i32)
+    (block $outer (result i32)
+      (try_table (result i32) (catch $i32 $outer)
+        (block $inner
+          (try_table (catch $empty $inner)
+            (throw $i32 (i32.const 5))))
+        (i32.const 0))))
+
+  (func (export "throw_null")
+    (throw_ref (ref.null exn)))
+)
+
+(assert_return (invoke "no_throw") (i32.const 1))
+(assert_return (invoke "catch" (i32.const 42)) (i32.const 42))
+(assert_return (invoke "catch_all") (i32.const 2))
+(assert_return (invoke "rethrow" (i32.const 7)) (i32.const 7))
+(assert_return (invoke "catch_ref") (i32.const 3))
+(assert_return (invoke "catch_callee" (i32.const 6)) (i32.const 6))
+(assert_return (invoke "catch_mismatch") (i32.const 5))
+(assert_trap (invoke "uncaught") "uncaught exception")
+(assert_trap (invoke "throw_null") "null exception reference")
+
+(register "test")
+
+(module
+  (import "test" "pair" (tag (param i32 i64)))
+  (tag (export "tag") (param f64))
+)
+
+(assert_unlinkable
+  (module (import "test" "pair" (tag (param i32))))
+  "incompatible import type")
+
+(assert_invalid
+  (module (func (throw 0)))
+  "unknown tag")
+(assert_invalid
+  (module (tag (param i32)) (func (throw 0 (i64.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (type (func (result i32))) (tag (type 0)))
+  "non-empty tag result type")
+(assert_invalid
+  (module (tag (param i32))
+    (func (block $h (result i64) (try_table (catch 0 $h)) (unreachable)) (drop)))
+  "type mismatch")
+(assert_invalid
+  (module (tag)
+    (func (block $h (try_table (catch_ref 0 $h)))))
+  "type mismatch")
+(assert_invalid
+  (module (func (throw_ref (ref.null func))))
+  "type mismatch")
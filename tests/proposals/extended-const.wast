@@ -0,0 +1,55 @@
+;; Constant expressions with integer addition, subtraction and multiplication.
+
+(module
+  (import "spectest" "global_i32" (global $imported i32))
+  (global $base i32 (i32.add (global.get $imported) (i32.const 8)))
+  (global $scaled i32 (i32.mul (global.get $base) (i32.const 2)))
+  (global $wrapped i32 (i32.sub (i32.const 0) (i32.const 1)))
+  (global $large i64 (i64.mul (i64.const 0x100000000) (i64.sub (i64.const 5) (i64.const 2))))
+  (global $overflow i64 (i64.add (i64.const 0x7fffffffffffffff) (i64.const 1)))
+
+  (memory 1)
+  (data (offset (i32.sub (i32.mul (i32.const 4) (i32.const 4)) (i32.const 1))) "\2a")
+  (table 10 funcref)
+  (elem (offset (i32.add (i32.const 2) (i32.const 3))) func $f)
+  (func $f (result i32) (i32.const 5))
+
+  (func (export "base") (result i32) (global.get $base))
+  (func (export "scaled") (result i32) (global.get $scaled))
+  (func (export "wrapped") (result i32) (global.get $wrapped))
+  (func (export "large") (result i64) (global.get $large))
+  (func (export "overflow") (result i64) (global.get $overflow))
+  (func (export "load8_u") (param i32) (result i32) (i32.load8_u (local.get 0)))
+  (func (export "call") (param i32) (result i32) (call_indirect (result i32) (local.get 0)))
+)
+
+(assert_return (invoke "base") (i32.const 674))
+(assert_return (invoke "scaled") (i32.const 1348))
+(assert_return (invoke "wrapped") (i32.const -1))
+(assert_return (invoke "large") (i64.const 0x300000000))
+(assert_return (invoke "overflow") (i64.const 0x8000000000000000))
+(assert_return (invoke "load8_u" (i32.const 15)) (i32.const 42))
+(assert_return (invoke "call" (i32.const 5)) (i32.const 5))
+
+;; An offset computed at instantiation is still checked against the segment bounds.
+(assert_unlinkable
+  (module
+    (memory 1)
+    (data (offset (i32.mul (i32.const 0x100) (i32.const 0x100))) "\00"))
+  "data segment does not fit")
+
+(assert_invalid
+  (module (global i32 (i32.div_s (i32.const 1) (i32.const 1))))
+  "constant expression required")
+(assert_invalid
+  (module (global i32 (i32.and (i32.const 1) (i32.const 1))))
+  "constant expression required")
+(assert_invalid
+  (module (global f32 (f32.add (f32.const 1) (f32.const 1))))
+  "constant expression required")
+(assert_invalid
+  (module (global i64 (i64.add (i32.const 1) (i32.const 1))))
+  "type mismatch")
+(assert_invalid
+  (module (global $g (mut i32) (i32.const 0)) (global i32 (i32.add (global.get $g) (i32.const 1))))
+  "constant expression required")
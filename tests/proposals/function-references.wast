@@ -0,0 +1,78 @@
+;; Typed function references, call_ref and the null checks on references.
+
+(module
+  (type $i32_i32 (func (param i32) (result i32)))
+  (elem declare func $double $square)
+
+  (func $double (type $i32_i32) (i32.mul (local.get 0) (i32.const 2)))
+  (func $square (type $i32_i32) (i32.mul (local.get 0) (local.get 0)))
+
+  (func $pick (param i32) (result (ref null $i32_i32))
+    (if (result (ref null $i32_i32)) (i32.eqz (local.get 0))
+      (then (ref.null $i32_i32))
+      (else
+        (select (result (ref $i32_i32))
+          (ref.func $double) (ref.func $square) (i32.eq (local.get 0) (i32.const 1))))))
+
+  (func (export "call_ref") (param i32 i32) (result i32)
+    (call_ref $i32_i32 (local.get 1) (call $pick (local.get 0))))
+  (func (export "return_call_ref") (param i32 i32) (result i32)
+    (return_call_ref $i32_i32 (local.get 1) (call $pick (local.get 0))))
+  (func (export "as_non_null") (param i32) (result i32)
+    (call_ref $i32_i32 (i32.const 3) (ref.as_non_null (call $pick (local.get 0)))))
+
+  (func (export "br_on_null") (param i32) (result i32)
+    (block $null
+      (return (call_ref $i32_i32 (i32.const 5) (br_on_null $null (call $pick (local.get 0))))))
+    (i32.const -1))
+  (func (export "br_on_non_null") (param i32) (result i32)
+    (local $f (ref null $i32_i32))
+    (block $non_null (result (ref $i32_i32))
+      (br_on_non_null $non_null (call $pick (local.get 0)))
+      (return (i32.const -1)))
+    (local.set $f)
+    (call_ref $i32_i32 (i32.const 6) (local.get $f)))
+
+  ;; Non-nullable locals can be read once they are set.
+  (func (export "local") (param i32) (result i32)
+    (local $f (ref $i32_i32))
+    (local.set $f (ref.func $square))
+    (call_ref $i32_i32 (local.get 0) (local.get $f)))
+)
+
+(assert_return (invoke "call_ref" (i32.const 1) (i32.const 7)) (i32.const 14))
+(assert_return (invoke "call_ref" (i32.const 2) (i32.const 7)) (i32.const 49))
+(assert_trap (invoke "call_ref" (i32.const 0) (i32.const 7)) "null function reference")
+(assert_return (invoke "return_call_ref" (i32.const 2) (i32.const 4)) (i32.const 16))
+(assert_trap (invoke "return_call_ref" (i32.const 0) (i32.const 4)) "null function reference")
+(assert_return (invoke "as_non_null" (i32.const 1)) (i32.const 6))
+(assert_trap (invoke "as_non_null" (i32.const 0)) "null reference")
+(assert_return (invoke "br_on_null" (i32.const 2)) (i32.const 25))
+(assert_return (invoke "br_on_null" (i32.const 0)) (i32.const -1))
+(assert_return (invoke "br_on_non_null" (i32.const 1)) (i32.const 12))
+(assert_return (invoke "br_on_non_null" (i32.const 0)) (i32.const -1))
+(assert_return (invoke "local" (i32.const 9)) (i32.const 81))
+
+(assert_invalid
+  (module (type $t (func)) (func (local $f (ref $t)) (drop (local.get $f))))
+  "uninitialized local")
+(assert_invalid
+  (module (type $t (func)) (func (result (ref $t)) (ref.null $t)))
+  "type mismatch")
+(assert_invalid
+  (module
+    (type $t (func (param i32)))
+    (func (param (ref $t)) (call_ref $t (i64.const 0) (local.get 0))))
+  "type mismatch")
+(assert_invalid
+  (module
+    (type $t (func))
+    (type $u (func (param i32)))
+    (func (param (ref $u)) (call_ref $t (local.get 0))))
+  "type mismatch")
+(assert_invalid
+  (module (func (drop (br_on_null 0 (i32.const 0)))))
+  "type mismatch")
+(assert_invalid
+  (module (table 1 (ref func)))
+  "type mismatch")
@@ -0,0 +1,200 @@
+;; Struct and array types, recursive type groups and subtyping. References aren't supported as
+;; invoke arguments or results, so the functions return the numeric values read from them.
+
+(module
+  (rec
+    (type $node (sub (struct (field $value i32) (field $next (ref null $node)))))
+    (type $list (struct (field (ref null $node)))))
+  (type $point (sub (struct (field $x (mut f64)) (field $y (mut f64)))))
+  (type $point3 (sub final $point (struct (field (mut f64)) (field (mut f64)) (field $z (mut f64)))))
+  (type $bytes (array (mut i8)))
+  (type $points (array (ref null $point)))
+
+  (func (export "struct") (param i32) (result i32)
+    (struct.get $node $value
+      (struct.new $node (local.get 0) (struct.new_default $node))))
+  (func (export "struct_set") (param f64) (result f64)
+    (local $p (ref $point3))
+    (local.set $p (struct.new_default $point3))
+    (struct.set $point3 $z (local.get $p) (local.get 0))
+    (struct.get $point3 $z (local.get $p)))
+  (func (export "array") (param i32) (result i32)
+    (local $a (ref $bytes))
+    (local.set $a (array.new $bytes (i32.const 0xff) (local.get 0)))
+    (array.set $bytes (local.get $a) (i32.const 0) (i32.const 1))
+    (i32.add
+      (array.len (local.get $a))
+      (array.get_s $bytes (local.get $a) (i32.const 1))))
+  (func (export "upcast") (result i32)
+    (ref.is_null (array.new_fixed $points 1 (struct.new_default $point3))))
+
+  ;; A subtype can be used wherever its supertype is expected.
+  (func $x (param (ref $point)) (result f64) (struct.get $point $x (local.get 0)))
+  (func (param (ref $point3)) (result f64) (call $x (local.get 0)))
+  (func (param (ref $bytes)) (result (ref array)) (local.get 0))
+  (func (param (ref $list)) (result (ref null struct)) (local.get 0))
+  (func (param (ref i31)) (result (ref eq)) (local.get 0))
+
+  (global $origin (ref $point) (struct.new $point (f64.const 1) (f64.const 2)))
+  (data $data "\01\80\ff")
+  (elem $elem func $x)
+  (type $funcs (array (mut funcref)))
+
+  (func (export "global") (result f64)
+    (struct.get $point $y (global.get $origin)))
+  (func (export "packed_s") (param i32) (result i32)
+    (array.get_s $bytes (array.new $bytes (local.get 0) (i32.const 1)) (i32.const 0)))
+  (func (export "packed_u") (param i32) (result i32)
+    (array.get_u $bytes (array.new $bytes (local.get 0) (i32.const 1)) (i32.const 0)))
+  (func (export "array_data") (param i32) (result i32)
+    (array.get_s $bytes (array.new_data $bytes $data (i32.const 0) (i32.const 3)) (local.get 0)))
+  (func (export "array_elem") (result i32)
+    (ref.is_null (array.get $funcs (array.new_elem $funcs $elem (i32.const 0) (i32.const 1)) (i32.const 0))))
+  (func (export "array_copy") (result i32)
+    (local $a (ref $bytes))
+    (local.set $a (array.new_default $bytes (i32.const 4)))
+    (array.fill $bytes (local.get $a) (i32.const 1) (i32.const 7) (i32.const 2))
+    (array.copy $bytes $bytes (local.get $a) (i32.const 2) (local.get $a) (i32.const 0) (i32.const 2))
+    (array.init_data $bytes $data (local.get $a) (i32.const 0) (i32.const 0) (i32.const 1))
+    (i32.add
+      (i32.mul (array.get_u $bytes (local.get $a) (i32.const 0)) (i32.const 1000))
+      (i32.add
+        (i32.mul (array.get_u $bytes (local.get $a) (i32.const 1)) (i32.const 100))
+        (i32.add
+          (i32.mul (array.get_u $bytes (local.get $a) (i32.const 2)) (i32.const 10))
+          (array.get_u $bytes (local.get $a) (i32.const 3))))))
+  (func (export "array_oob") (param i32) (result i32)
+    (array.get_u $bytes (array.new_default $bytes (i32.const 2)) (local.get 0)))
+  (func (export "array_data_oob") (result i32)
+    (array.len (array.new_data $bytes $data (i32.const 1) (i32.const 3))))
+  (func (export "struct_null") (result i32)
+    (struct.get $node $value (ref.null $node)))
+
+  (func (export "i31_s") (param i32) (result i32)
+    (i31.get_s (ref.i31 (local.get 0))))
+  (func (export "i31_u") (param i32) (result i32)
+    (i31.get_u (ref.i31 (local.get 0))))
+  (func (export "i31_null") (result i32)
+    (i31.get_u (ref.null i31)))
+
+  (func (export "ref_eq") (result i32)
+    (local $p (ref $point))
+    (local.set $p (struct.new_default $point))
+    (i32.add
+      (i32.add
+        (ref.eq (local.get $p) (local.get $p))
+        (ref.eq (local.get $p) (struct.new_default $point)))
+      (i32.add
+        (ref.eq (ref.i31 (i32.const 5)) (ref.i31 (i32.const 5)))
+        (ref.eq (ref.null eq) (ref.null struct)))))
+
+  (func $test (param anyref) (result i32)
+    (i32.add
+      (i32.add
+        (i32.mul (ref.test (ref $point) (local.get 0)) (i32.const 1))
+        (i32.mul (ref.test (ref $point3) (local.get 0)) (i32.const 2)))
+      (i32.add
+        (i32.mul (ref.test (ref i31) (local.get 0)) (i32.const 4))
+        (i32.mul (ref.test (ref null array) (local.get 0)) (i32.const 8)))))
+  (func (export "test_point") (result i32)
+    (call $test (struct.new_default $point)))
+  (func (export "test_point3") (result i32)
+    (call $test (struct.new_default $point3)))
+  (func (export "test_i31") (result i32)
+    (call $test (ref.i31 (i32.const 0))))
+  (func (export "test_null") (result i32)
+    (call $test (ref.null any)))
+  (func (export "test_extern") (result i32)
+    (call $test (any.convert_extern (extern.convert_any (array.new_default $bytes (i32.const 0))))))
+
+  (func (export "cast") (result f64)
+    (struct.get $point3 $z
+      (ref.cast (ref $point3) (struct.new $point3 (f64.const 1) (f64.const 2) (f64.const 3)))))
+  (func (export "cast_failure") (result i32)
+    (ref.is_null (ref.cast (ref $point3) (struct.new_default $point))))
+  (func (export "br_on_cast") (param i32) (result i32)
+    (block $i31 (result (ref i31))
+      (br_on_cast $i31 anyref (ref i31)
+        (select (result anyref)
+          (ref.i31 (i32.const 3))
+          (struct.new_default $point)
+          (local.get 0)))
+      (drop)
+      (return (i32.const -1)))
+    (i31.get_u))
+  (func (export "br_on_cast_fail") (param i32) (result i32)
+    (block $other (result anyref)
+      (br_on_cast_fail $other anyref (ref $point)
+        (select (result anyref)
+          (ref.i31 (i32.const 3))
+          (struct.new_default $point)
+          (local.get 0)))
+      (return (i32.trunc_f64_s (struct.get $point $x))))
+    (drop)
+    (i32.const -1))
+)
+
+(assert_return (invoke "struct" (i32.const 3)) (i32.const 3))
+(assert_return (invoke "struct_set" (f64.const 1.5)) (f64.const 1.5))
+(assert_return (invoke "array" (i32.const 4)) (i32.const 3))
+(assert_return (invoke "upcast") (i32.const 0))
+(assert_return (invoke "global") (f64.const 2))
+(assert_return (invoke "packed_s" (i32.const 0x180)) (i32.const -128))
+(assert_return (invoke "packed_u" (i32.const 0x180)) (i32.const 128))
+(assert_return (invoke "array_data" (i32.const 0)) (i32.const 1))
+(assert_return (invoke "array_data" (i32.const 1)) (i32.const -128))
+(assert_return (invoke "array_data" (i32.const 2)) (i32.const -1))
+(assert_return (invoke "array_elem") (i32.const 0))
+(assert_return (invoke "array_copy") (i32.const 1707))
+(assert_return (invoke "array_oob" (i32.const 1)) (i32.const 0))
+(assert_trap (invoke "array_oob" (i32.const 2)) "out of bounds array access")
+(assert_trap (invoke "array_data_oob") "out of bounds memory access")
+(assert_trap (invoke "struct_null") "null structure reference")
+
+(assert_return (invoke "i31_s" (i32.const 0x40000000)) (i32.const -0x40000000))
+(assert_return (invoke "i31_u" (i32.const 0x40000000)) (i32.const 0x40000000))
+(assert_return (invoke "i31_s" (i32.const -1)) (i32.const -1))
+(assert_return (invoke "i31_u" (i32.const -1)) (i32.const 0x7fffffff))
+(assert_trap (invoke "i31_null") "null i31 reference")
+
+(assert_return (invoke "ref_eq") (i32.const 3))
+(assert_return (invoke "test_point") (i32.const 1))
+(assert_return (invoke "test_point3") (i32.const 3))
+(assert_return (invoke "test_i31") (i32.const 4))
+(assert_return (invoke "test_null") (i32.const 8))
+(assert_return (invoke "test_extern") (i32.const 8))
+(assert_return (invoke "cast") (f64.const 3))
+(assert_trap (invoke "cast_failure") "cast failure")
+(assert_return (invoke "br_on_cast" (i32.const 1)) (i32.const 3))
+(assert_return (invoke "br_on_cast" (i32.const 0)) (i32.const -1))
+(assert_return (invoke "br_on_cast_fail" (i32.const 1)) (i32.const -1))
+(assert_return (invoke "br_on_cast_fail" (i32.const 0)) (i32.const 0))
+
+(assert_invalid
+  (module (type $t (struct)) (type (sub $t (struct))))
+  "sub type")
+(assert_invalid
+  (module (type $t (sub (struct (field i32)))) (type (sub $t (struct (field i64)))))
+  "sub type")
+(assert_invalid
+  (module (type $t (sub (struct (field (mut i32))))) (type (sub $t (struct (field i32)))))
+  "sub type")
+(assert_invalid
+  (module (type $t (sub (array i32))) (type (sub $t (struct))))
+  "sub type")
+(assert_invalid
+  (module (type $t (sub (func))) (type (sub $t (func (param i32)))))
+  "sub type")
+(assert_invalid
+  (module (type (sub 1 (struct))) (type (sub (struct))))
+  "unknown type")
+(assert_invalid
+  (module (type $t (struct (field i32))) (func (param (ref $t)) (struct.set $t 0 (local.get 0) (i32.const 0))))
+  "field is immutable")
+(assert_invalid
+  (module (type $t (array i8)) (func (param (ref $t)) (result i32) (array.get $t (local.get 0) (i32.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (type $t (struct)) (type $u (struct (field i32)))
+    (func (param (ref $t)) (result (ref $u)) (local.get 0)))
+  "type mismatch")
@@ -0,0 +1,30 @@
+;; Function references read from globals already hold the address of their function, while
+;; ref.func refers to the functions of the module evaluating the constant expression.
+
+(module $M
+  (func $f0 (result i32) (i32.const 40))
+  (func $f1 (result i32) (i32.const 41))
+  (func $f2 (result i32) (i32.const 42))
+  (global (export "f2") funcref (ref.func $f2))
+)
+(register "M" $M)
+
+(module
+  (type $result (func (result i32)))
+  (import "M" "f2" (global $imported funcref))
+  (global $copy funcref (global.get $imported))
+  (global $local funcref (ref.func $f))
+  (func $f (result i32) (i32.const 7))
+
+  (table 4 funcref)
+  (elem (i32.const 0) funcref
+    (global.get $imported) (global.get $copy) (global.get $local) (ref.func $f))
+
+  (func (export "call") (param i32) (result i32)
+    (call_indirect (type $result) (local.get 0)))
+)
+
+(assert_return (invoke "call" (i32.const 0)) (i32.const 42))
+(assert_return (invoke "call" (i32.const 1)) (i32.const 42))
+(assert_return (invoke "call" (i32.const 2)) (i32.const 7))
+(assert_return (invoke "call" (i32.const 3)) (i32.const 7))
@@ -0,0 +1,60 @@
+;; Memories with 64-bit addresses.
+
+(module
+  (memory i64 1 3)
+  (data (i64.const 8) "\01\02\03\04")
+
+  (func (export "load8_u") (param i64) (result i32) (i32.load8_u (local.get 0)))
+  (func (export "load") (param i64) (result i64) (i64.load offset=4 (local.get 0)))
+  (func (export "store") (param i64 i64) (i64.store (local.get 0) (local.get 1)))
+  (func (export "size") (result i64) (memory.size))
+  (func (export "grow") (param i64) (result i64) (memory.grow (local.get 0)))
+  (func (export "fill") (param i64 i32 i64)
+    (memory.fill (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "copy") (param i64 i64 i64)
+    (memory.copy (local.get 0) (local.get 1) (local.get 2)))
+)
+
+(assert_return (invoke "load8_u" (i64.const 8)) (i32.const 1))
+(assert_return (invoke "load8_u" (i64.const 11)) (i32.const 4))
+(assert_return (invoke "load" (i64.const 4)) (i64.const 0x04030201))
+(assert_trap (invoke "load8_u" (i64.const 0x10000)) "out of bounds memory access")
+(assert_trap (invoke "load8_u" (i64.const 0x100000000)) "out of bounds memory access")
+(assert_trap (invoke "load8_u" (i64.const -1)) "out of bounds memory access")
+(assert_trap (invoke "load" (i64.const -4)) "out of bounds memory access")
+
+(invoke "store" (i64.const 0xfff8) (i64.const -1))
+(assert_return (invoke "load8_u" (i64.const 0xffff)) (i32.const 0xff))
+(assert_trap (invoke "store" (i64.const 0xfff9) (i64.const 0)) "out of bounds memory access")
+
+(assert_return (invoke "size") (i64.const 1))
+(assert_return (invoke "grow" (i64.const 1)) (i64.const 1))
+(assert_return (invoke "size") (i64.const 2))
+(assert_return (invoke "grow" (i64.const 2)) (i64.const -1))
+(assert_return (invoke "grow" (i64.const 0x100000000)) (i64.const -1))
+(assert_return (invoke "load8_u" (i64.const 0x1ffff)) (i32.const 0))
+
+(invoke "fill" (i64.const 0x1fffe) (i32.const 7) (i64.const 2))
+(assert_return (invoke "load8_u" (i64.const 0x1ffff)) (i32.const 7))
+(invoke "copy" (i64.const 0) (i64.const 0x1fffe) (i64.const 2))
+(assert_return (invoke "load8_u" (i64.const 1)) (i32.const 7))
+(assert_trap (invoke "fill" (i64.const 0x1ffff) (i32.const 0) (i64.const 2))
+  "out of bounds memory access")
+(assert_trap (invoke "copy" (i64.const 0) (i64.const -1) (i64.const 2))
+  "out of bounds memory access")
+
+(assert_invalid
+  (module (memory i64 1) (func (drop (i32.load (i32.const 0)))))
+  "type mismatch")
+(assert_invalid
+  (module (memory i64 1) (func (result i32) (memory.size)))
+  "type mismatch")
+(assert_invalid
+  (module (memory i64 1) (data (i32.const 0) ""))
+  "type mismatch")
+(assert_invalid
+  (module (memory 1) (func (drop (i32.load (i64.const 0)))))
+  "type mismatch")
+(assert_invalid
+  (module (memory i64 0x1000000000001))
+  "memory size must be at most 2^48 pages (256TiB)")
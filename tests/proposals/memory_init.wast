@@ -0,0 +1,97 @@
+;; memory.init copies a passive data segment into a memory.
+
+(module
+  (memory (export "memory0") 1 1)
+  (memory $m1 1 1)
+  (data "\01\02\03\04")
+  (data "\10\20\30\40\50")
+  (data $d2 (memory $m1) (i32.const 0) "\aa\bb")
+
+  (func (export "init0") (param i32 i32 i32)
+    (memory.init 0 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init1") (param i32 i32 i32)
+    (memory.init 1 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init1-memory1") (param i32 i32 i32)
+    (memory.init $m1 1 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "drop0") (data.drop 0))
+  (func (export "drop1") (data.drop 1))
+  (func (export "drop2") (data.drop $d2))
+  (func (export "init2") (param i32 i32 i32)
+    (memory.init $m1 $d2 (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "load8_u") (param i32) (result i32)
+    (i32.load8_u (local.get 0)))
+  (func (export "load8_u-memory1") (param i32) (result i32)
+    (i32.load8_u $m1 (local.get 0)))
+)
+
+;; Passive segments are left untouched by the instantiation.
+(assert_return (invoke "load8_u" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "load8_u-memory1" (i32.const 0)) (i32.const 0xaa))
+
+(invoke "init0" (i32.const 2) (i32.const 1) (i32.const 3))
+(assert_return (invoke "load8_u" (i32.const 1)) (i32.const 0))
+(assert_return (invoke "load8_u" (i32.const 2)) (i32.const 2))
+(assert_return (invoke "load8_u" (i32.const 3)) (i32.const 3))
+(assert_return (invoke "load8_u" (i32.const 4)) (i32.const 4))
+(assert_return (invoke "load8_u" (i32.const 5)) (i32.const 0))
+
+;; The second segment is copied to the first memory.
+(invoke "init1" (i32.const 100) (i32.const 0) (i32.const 5))
+(assert_return (invoke "load8_u" (i32.const 100)) (i32.const 0x10))
+(assert_return (invoke "load8_u" (i32.const 104)) (i32.const 0x50))
+(assert_return (invoke "load8_u-memory1" (i32.const 100)) (i32.const 0))
+
+;; The second segment is copied to the second memory.
+(invoke "init1-memory1" (i32.const 200) (i32.const 3) (i32.const 2))
+(assert_return (invoke "load8_u-memory1" (i32.const 200)) (i32.const 0x40))
+(assert_return (invoke "load8_u-memory1" (i32.const 201)) (i32.const 0x50))
+(assert_return (invoke "load8_u" (i32.const 200)) (i32.const 0))
+
+;; Accesses are bounds checked against both the segment and the memory.
+(assert_trap (invoke "init0" (i32.const 0) (i32.const 1) (i32.const 4))
+  "out of bounds memory access")
+(assert_trap (invoke "init0" (i32.const 65534) (i32.const 0) (i32.const 3))
+  "out of bounds memory access")
+(assert_trap (invoke "init1-memory1" (i32.const 65535) (i32.const 0) (i32.const 2))
+  "out of bounds memory access")
+(invoke "init0" (i32.const 65536) (i32.const 0) (i32.const 0))
+(invoke "init0" (i32.const 0) (i32.const 4) (i32.const 0))
+(assert_trap (invoke "init0" (i32.const 65537) (i32.const 0) (i32.const 0))
+  "out of bounds memory access")
+(assert_trap (invoke "init0" (i32.const 0) (i32.const 5) (i32.const 0))
+  "out of bounds memory access")
+
+;; Dropped segments are empty.
+(invoke "drop0")
+(invoke "init0" (i32.const 0) (i32.const 0) (i32.const 0))
+(assert_trap (invoke "init0" (i32.const 0) (i32.const 0) (i32.const 1))
+  "out of bounds memory access")
+(invoke "init1" (i32.const 300) (i32.const 4) (i32.const 1))
+(assert_return (invoke "load8_u" (i32.const 300)) (i32.const 0x50))
+(invoke "drop1")
+(assert_trap (invoke "init1" (i32.const 300) (i32.const 0) (i32.const 1))
+  "out of bounds memory access")
+
+;; Active segments are dropped once instantiated.
+(invoke "init2" (i32.const 0) (i32.const 0) (i32.const 0))
+(assert_trap (invoke "init2" (i32.const 0) (i32.const 0) (i32.const 1))
+  "out of bounds memory access")
+(invoke "drop2")
+
+(assert_invalid
+  (module (memory 1) (func (memory.init 1 (i32.const 0) (i32.const 0) (i32.const 0))) (data ""))
+  "unknown data segment")
+(assert_invalid
+  (module (func (memory.init 0 (i32.const 0) (i32.const 0) (i32.const 0))) (data ""))
+  "unknown memory")
+(assert_invalid
+  (module (memory 1) (func (data.drop 1)) (data ""))
+  "unknown data segment")
+(assert_invalid
+  (module (memory 1) (func (memory.init 0 (i64.const 0) (i32.const 0) (i32.const 0))) (data ""))
+  "type mismatch")
+(assert_invalid
+  (module (memory i64 1) (func (memory.init 0 (i32.const 0) (i32.const 0) (i32.const 0))) (data ""))
+  "type mismatch")
+(module (memory i64 1) (func (memory.init 0 (i64.const 0) (i32.const 0) (i32.const 0))) (data ""))
@@ -0,0 +1,66 @@
+;; Modules with several memories, addressed by index.
+
+(module
+  (memory $a 1)
+  (memory $b 2)
+  (memory $c i64 1)
+  (data (memory $b) (i32.const 0) "\2a")
+  (data $passive "\01\02\03")
+
+  (func (export "load_a") (param i32) (result i32) (i32.load8_u $a (local.get 0)))
+  (func (export "load_b") (param i32) (result i32) (i32.load8_u $b (local.get 0)))
+  (func (export "load_c") (param i64) (result i32) (i32.load8_u $c (local.get 0)))
+  (func (export "store_b") (param i32 i32) (i32.store8 $b (local.get 0) (local.get 1)))
+  (func (export "size_b") (result i32) (memory.size $b))
+  (func (export "grow_a") (param i32) (result i32) (memory.grow $a (local.get 0)))
+  (func (export "copy_b_to_a") (param i32 i32 i32)
+    (memory.copy $a $b (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "copy_b_to_c") (param i64 i32 i32)
+    (memory.copy $c $b (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "fill_c") (param i64 i32 i64)
+    (memory.fill $c (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init_b") (param i32 i32 i32)
+    (memory.init $b $passive (local.get 0) (local.get 1) (local.get 2)))
+)
+
+(assert_return (invoke "load_a" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "load_b" (i32.const 0)) (i32.const 42))
+(assert_return (invoke "size_b") (i32.const 2))
+(assert_return (invoke "load_b" (i32.const 0x1ffff)) (i32.const 0))
+(assert_trap (invoke "load_a" (i32.const 0x10000)) "out of bounds memory access")
+
+(invoke "store_b" (i32.const 0x1fffe) (i32.const 7))
+(assert_return (invoke "load_a" (i32.const 0xfffe)) (i32.const 0))
+(invoke "copy_b_to_a" (i32.const 0xfffe) (i32.const 0x1fffe) (i32.const 2))
+(assert_return (invoke "load_a" (i32.const 0xfffe)) (i32.const 7))
+(assert_trap (invoke "copy_b_to_a" (i32.const 0xffff) (i32.const 0) (i32.const 2))
+  "out of bounds memory access")
+
+(assert_return (invoke "grow_a" (i32.const 1)) (i32.const 1))
+(invoke "copy_b_to_a" (i32.const 0xffff) (i32.const 0) (i32.const 2))
+(assert_return (invoke "load_a" (i32.const 0xffff)) (i32.const 42))
+(assert_return (invoke "size_b") (i32.const 2))
+
+(invoke "copy_b_to_c" (i64.const 0xffff) (i32.const 0) (i32.const 1))
+(assert_return (invoke "load_c" (i64.const 0xffff)) (i32.const 42))
+(assert_trap (invoke "copy_b_to_c" (i64.const 0xffff) (i32.const 0) (i32.const 2))
+  "out of bounds memory access")
+(invoke "fill_c" (i64.const 10) (i32.const 9) (i64.const 1))
+(assert_return (invoke "load_c" (i64.const 10)) (i32.const 9))
+(assert_return (invoke "load_a" (i32.const 10)) (i32.const 0))
+
+(invoke "init_b" (i32.const 100) (i32.const 1) (i32.const 2))
+(assert_return (invoke "load_b" (i32.const 100)) (i32.const 2))
+(assert_return (invoke "load_b" (i32.const 101)) (i32.const 3))
+(assert_return (invoke "load_a" (i32.const 100)) (i32.const 0))
+
+(assert_invalid
+  (module (memory 1) (func (drop (i32.load 1 (i32.const 0)))))
+  "unknown memory")
+(assert_invalid
+  (module (memory 1) (memory i64 1)
+    (func (memory.copy 0 1 (i32.const 0) (i32.const 0) (i64.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (memory 1) (data (memory 1) (i32.const 0) ""))
+  "unknown memory")
@@ -0,0 +1,121 @@
+;; Functions and blocks with multiple results, and blocks with parameters.
+
+(module
+  (type $pair (func (param i32 i32) (result i32 i32)))
+
+  (func $swap (type $pair) (local.get 1) (local.get 0))
+  (func (export "swap") (param i32 i32) (result i32 i32)
+    (call $swap (local.get 0) (local.get 1)))
+
+  (func (export "mixed") (result i32 i64 f32 f64)
+    (i32.const 1) (i64.const 2) (f32.const 3) (f64.const 4))
+
+  (func (export "block") (result i32 i32)
+    (block (result i32 i32) (i32.const 1) (i32.const 2)))
+  (func (export "block-params") (param i32 i32) (result i32)
+    (local.get 0) (local.get 1)
+    (block (param i32 i32) (result i32) (i32.sub)))
+  (func (export "block-type-index") (param i32 i32) (result i32 i32)
+    (local.get 0) (local.get 1)
+    (block (type $pair) (call $swap)))
+
+  (func (export "br") (result i32 i32)
+    (block (result i32 i32)
+      (i32.const 1) (i32.const 2) (br 0) (i32.const 3) (i32.const 4)))
+  (func (export "br_if") (param i32) (result i32 i32)
+    (block (result i32 i32)
+      (i32.const 1) (i32.const 2) (local.get 0) (br_if 0)
+      (drop) (drop) (i32.const 3) (i32.const 4)))
+  (func (export "br_table") (param i32) (result i32 i32)
+    (block (result i32 i32)
+      (block (result i32 i32)
+        (i32.const 1) (i32.const 2) (local.get 0) (br_table 0 1))
+      (drop) (drop) (i32.const 3) (i32.const 4)))
+
+  (func (export "if") (param i32 i32) (result i32 i32)
+    (local.get 0)
+    (if (param i32) (result i32 i32) (local.get 1)
+      (then (i32.const 1))
+      (else (i32.const 2))))
+
+  ;; Loop parameters are passed again by branches to the loop.
+  (func (export "sum") (param i32) (result i32)
+    (i32.const 0) (local.get 0)
+    (loop (param i32 i32) (result i32)
+      (local.set 0)
+      (local.get 0) (i32.add)
+      (local.get 0) (i32.const 1) (i32.sub) (local.tee 0)
+      (local.get 0) (br_if 0)
+      (drop)))
+
+  (func (export "return") (result i32 i32)
+    (i32.const 5) (i32.const 6) (return) (i32.const 7) (i32.const 8))
+
+  (table funcref (elem $swap))
+  (func (export "call_indirect") (param i32 i32) (result i32 i32)
+    (call_indirect (type $pair) (local.get 0) (local.get 1) (i32.const 0)))
+)
+
+(assert_return (invoke "swap" (i32.const 1) (i32.const 2)) (i32.const 2) (i32.const 1))
+(assert_return (invoke "mixed") (i32.const 1) (i64.const 2) (f32.const 3) (f64.const 4))
+(assert_return (invoke "block") (i32.const 1) (i32.const 2))
+(assert_return (invoke "block-params" (i32.const 5) (i32.const 3)) (i32.const 2))
+(assert_return (invoke "block-type-index" (i32.const 1) (i32.const 2))
+  (i32.const 2) (i32.const 1))
+(assert_return (invoke "br") (i32.const 1) (i32.const 2))
+(assert_return (invoke "br_if" (i32.const 1)) (i32.const 1) (i32.const 2))
+(assert_return (invoke "br_if" (i32.const 0)) (i32.const 3) (i32.const 4))
+(assert_return (invoke "br_table" (i32.const 0)) (i32.const 3) (i32.const 4))
+(assert_return (invoke "br_table" (i32.const 1)) (i32.const 1) (i32.const 2))
+(assert_return (invoke "if" (i32.const 7) (i32.const 1)) (i32.const 7) (i32.const 1))
+(assert_return (invoke "if" (i32.const 7) (i32.const 0)) (i32.const 7) (i32.const 2))
+(assert_return (invoke "sum" (i32.const 10)) (i32.const 55))
+(assert_return (invoke "return") (i32.const 5) (i32.const 6))
+(assert_return (invoke "call_indirect" (i32.const 1) (i32.const 2))
+  (i32.const 2) (i32.const 1))
+
+(assert_invalid
+  (module (func (result i32 i32) (i32.const 0)))
+  "type mismatch")
+(assert_invalid
+  (module (func (result i32 i32) (i32.const 0) (i64.const 0)))
+  "type mismatch")
+(assert_invalid
+  (module (func (result i32) (block (param i32) (result i32))))
+  "type mismatch")
+(assert_invalid
+  (module (func (i32.const 0) (block (param i32) (result i32 i32) (i32.const 1)) (drop) (drop) (drop)))
+  "type mismatch")
+(assert_invalid
+  (module (func (result i32 i32) (block (result i32 i32) (i32.const 0) (br 0))))
+  "type mismatch")
+(assert_invalid
+  (module (type (func (param i32) (result i32))) (func (block (type 0) (i32.const 0)) (drop)))
+  "type mismatch")
+(assert_invalid
+  (module (func (i32.const 0) (loop (param i32) (drop) (br 0))))
+  "type mismatch")
+
+;; Operands popped in unreachable code stay unknown, so the labels of a br_table only have to agree
+;; on their arity.
+(module
+  (func (result f32 f32)
+    (block (result f32 f32)
+      (block (result i32 i64)
+        (unreachable)
+        (br_table 0 1 (i32.const 0)))
+      (drop)
+      (drop)
+      (f32.const 0)
+      (f32.const 1)))
+)
+(assert_invalid
+  (module
+    (func
+      (block (result f32)
+        (block (result i32) (unreachable) (br_table 0 1 (i32.const 0) (i32.const 0))))
+      (drop)))
+  "type mismatch")
+(assert_invalid
+  (module (func (result i32) (unreachable) (i64.const 1) (i32.const 0) (select) (i32.eqz)))
+  "type mismatch")
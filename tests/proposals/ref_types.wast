@@ -0,0 +1,84 @@
+;; Reference types, multiple tables and the table instructions.
+
+(module
+  (table $funcs 4 8 funcref)
+  (table $externs 2 externref)
+  (elem declare func $seven)
+  (elem (table $funcs) (i32.const 0) func $seven)
+
+  (func $seven (result i32) (i32.const 7))
+  (func $eight (result i32) (i32.const 8))
+
+  (func (export "is_null_func") (param i32) (result i32)
+    (ref.is_null (table.get $funcs (local.get 0))))
+  (func (export "is_null_extern") (param i32) (result i32)
+    (ref.is_null (table.get $externs (local.get 0))))
+  (func (export "call") (param i32) (result i32)
+    (call_indirect $funcs (result i32) (local.get 0)))
+
+  (func (export "set") (param i32)
+    (table.set $funcs (local.get 0) (ref.func $seven)))
+  (func (export "clear") (param i32)
+    (table.set $funcs (local.get 0) (ref.null func)))
+  (func (export "size_funcs") (result i32) (table.size $funcs))
+  (func (export "size_externs") (result i32) (table.size $externs))
+  (func (export "grow_funcs") (param i32) (result i32)
+    (table.grow $funcs (ref.null func) (local.get 0)))
+  (func (export "fill") (param i32 i32)
+    (table.fill $funcs (local.get 0) (ref.func $seven) (local.get 1)))
+
+  (func (export "select") (param i32) (result i32)
+    (ref.is_null
+      (select (result funcref) (ref.null func) (ref.func $seven) (local.get 0))))
+)
+
+(assert_return (invoke "is_null_func" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "is_null_func" (i32.const 1)) (i32.const 1))
+(assert_return (invoke "is_null_extern" (i32.const 1)) (i32.const 1))
+(assert_return (invoke "call" (i32.const 0)) (i32.const 7))
+(assert_trap (invoke "call" (i32.const 1)) "uninitialized element")
+(assert_trap (invoke "is_null_func" (i32.const 4)) "out of bounds table access")
+(assert_trap (invoke "is_null_extern" (i32.const 2)) "out of bounds table access")
+
+(invoke "set" (i32.const 1))
+(assert_return (invoke "call" (i32.const 1)) (i32.const 7))
+(invoke "clear" (i32.const 1))
+(assert_return (invoke "is_null_func" (i32.const 1)) (i32.const 1))
+(assert_trap (invoke "set" (i32.const 4)) "out of bounds table access")
+
+(assert_return (invoke "size_funcs") (i32.const 4))
+(assert_return (invoke "size_externs") (i32.const 2))
+(assert_return (invoke "grow_funcs" (i32.const 3)) (i32.const 4))
+(assert_return (invoke "size_funcs") (i32.const 7))
+(assert_return (invoke "grow_funcs" (i32.const 2)) (i32.const -1))
+(assert_return (invoke "grow_funcs" (i32.const 1)) (i32.const 7))
+(assert_return (invoke "size_funcs") (i32.const 8))
+
+(invoke "fill" (i32.const 5) (i32.const 3))
+(assert_return (invoke "call" (i32.const 5)) (i32.const 7))
+(assert_return (invoke "call" (i32.const 7)) (i32.const 7))
+(assert_return (invoke "is_null_func" (i32.const 4)) (i32.const 1))
+(assert_trap (invoke "fill" (i32.const 6) (i32.const 3)) "out of bounds table access")
+(invoke "fill" (i32.const 8) (i32.const 0))
+
+(assert_return (invoke "select" (i32.const 1)) (i32.const 1))
+(assert_return (invoke "select" (i32.const 0)) (i32.const 0))
+
+(assert_invalid
+  (module (func $f) (func (drop (ref.func $f))))
+  "undeclared function reference")
+(assert_invalid
+  (module (table 1 externref) (func (table.set 0 (i32.const 0) (ref.null func))))
+  "type mismatch")
+(assert_invalid
+  (module (table 1 externref) (func (result i32) (call_indirect 0 (result i32) (i32.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (table 1 funcref) (func (drop (table.get 1 (i32.const 0)))))
+  "unknown table")
+(assert_invalid
+  (module (func (result funcref) (select (ref.null func) (ref.null func) (i32.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (func (drop (ref.is_null (i32.const 0)))))
+  "type mismatch")
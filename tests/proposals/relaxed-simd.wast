@@ -0,0 +1,105 @@
+;; Relaxed SIMD. Execution picks the results of the matching non-relaxed instructions, which the
+;; assertions check for the inputs the relaxed instructions leave open.
+
+(module
+  (func (export "i32x4.relaxed_trunc_f32x4_s") (param f32) (result i32)
+    (i32x4.extract_lane 0 (i32x4.relaxed_trunc_f32x4_s (f32x4.splat (local.get 0)))))
+  (func (export "i32x4.relaxed_trunc_f32x4_u") (param f32) (result i32)
+    (i32x4.extract_lane 1 (i32x4.relaxed_trunc_f32x4_u (f32x4.splat (local.get 0)))))
+  (func (export "i32x4.relaxed_trunc_f64x2_s_zero") (param f64) (result i32)
+    (i32.add
+      (i32x4.extract_lane 1 (i32x4.relaxed_trunc_f64x2_s_zero (f64x2.splat (local.get 0))))
+      (i32x4.extract_lane 3 (i32x4.relaxed_trunc_f64x2_s_zero (f64x2.splat (local.get 0))))))
+  (func (export "i8x16.relaxed_swizzle") (param i32) (result i32)
+    (i8x16.extract_lane_u 0
+      (i8x16.relaxed_swizzle (v128.const i8x16 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25)
+                             (i8x16.splat (local.get 0)))))
+  (func (export "f32x4.relaxed_madd") (param f32 f32 f32) (result f32)
+    (f32x4.extract_lane 2
+      (f32x4.relaxed_madd (f32x4.splat (local.get 0)) (f32x4.splat (local.get 1))
+                          (f32x4.splat (local.get 2)))))
+  (func (export "f64x2.relaxed_nmadd") (param f64 f64 f64) (result f64)
+    (f64x2.extract_lane 1
+      (f64x2.relaxed_nmadd (f64x2.splat (local.get 0)) (f64x2.splat (local.get 1))
+                           (f64x2.splat (local.get 2)))))
+  (func (export "i32x4.relaxed_laneselect") (param i32 i32 i32) (result i32)
+    (i32x4.extract_lane 0
+      (i32x4.relaxed_laneselect (i32x4.splat (local.get 0)) (i32x4.splat (local.get 1))
+                                (i32x4.splat (local.get 2)))))
+  (func (export "f32x4.relaxed_min") (param f32 f32) (result f32)
+    (f32x4.extract_lane 0 (f32x4.relaxed_min (f32x4.splat (local.get 0)) (f32x4.splat (local.get 1)))))
+  (func (export "f64x2.relaxed_max") (param f64 f64) (result f64)
+    (f64x2.extract_lane 0 (f64x2.relaxed_max (f64x2.splat (local.get 0)) (f64x2.splat (local.get 1)))))
+  (func (export "i16x8.relaxed_q15mulr_s") (param i32 i32) (result i32)
+    (i16x8.extract_lane_s 0
+      (i16x8.relaxed_q15mulr_s (i16x8.splat (local.get 0)) (i16x8.splat (local.get 1)))))
+  (func (export "i16x8.relaxed_dot_i8x16_i7x16_s") (param i32 i32) (result i32)
+    (i16x8.extract_lane_s 4
+      (i16x8.relaxed_dot_i8x16_i7x16_s (i8x16.splat (local.get 0)) (i8x16.splat (local.get 1)))))
+  (func (export "i32x4.relaxed_dot_i8x16_i7x16_add_s") (param i32 i32 i32) (result i32)
+    (i32x4.extract_lane 3
+      (i32x4.relaxed_dot_i8x16_i7x16_add_s (i8x16.splat (local.get 0)) (i8x16.splat (local.get 1))
+                                           (i32x4.splat (local.get 2)))))
+
+  (func (export "relaxed_swizzle") (param v128 v128) (result v128)
+    (i8x16.relaxed_swizzle (local.get 0) (local.get 1)))
+  (func (export "relaxed_trunc") (param v128) (result v128)
+    (i32x4.relaxed_trunc_f64x2_u_zero (local.get 0)))
+  (func (export "relaxed_madd") (param v128 v128 v128) (result v128)
+    (f32x4.relaxed_nmadd (f32x4.relaxed_madd (local.get 0) (local.get 1) (local.get 2))
+                         (local.get 1) (local.get 2)))
+  (func (export "relaxed_laneselect") (param v128 v128 v128) (result v128)
+    (i64x2.relaxed_laneselect (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "relaxed_min_max") (param v128 v128) (result v128)
+    (f64x2.relaxed_max (f64x2.relaxed_min (local.get 0) (local.get 1)) (local.get 1)))
+  (func (export "relaxed_q15mulr") (param v128 v128) (result v128)
+    (i16x8.relaxed_q15mulr_s (local.get 0) (local.get 1)))
+  (func (export "relaxed_dot") (param v128 v128 v128) (result v128)
+    (i32x4.relaxed_dot_i8x16_i7x16_add_s
+      (local.get 0) (local.get 1)
+      (i16x8.relaxed_dot_i8x16_i7x16_s (local.get 1) (local.get 2))))
+)
+
+(assert_return (invoke "i32x4.relaxed_trunc_f32x4_s" (f32.const 1.5)) (i32.const 1))
+(assert_return (invoke "i32x4.relaxed_trunc_f32x4_s" (f32.const nan)) (i32.const 0))
+(assert_return (invoke "i32x4.relaxed_trunc_f32x4_s" (f32.const 3e9)) (i32.const 0x7fffffff))
+(assert_return (invoke "i32x4.relaxed_trunc_f32x4_u" (f32.const -1)) (i32.const 0))
+(assert_return (invoke "i32x4.relaxed_trunc_f32x4_u" (f32.const 5e9)) (i32.const -1))
+(assert_return (invoke "i32x4.relaxed_trunc_f64x2_s_zero" (f64.const -7.9)) (i32.const -7))
+(assert_return (invoke "i32x4.relaxed_trunc_f64x2_s_zero" (f64.const -3e9)) (i32.const 0x80000000))
+(assert_return (invoke "i8x16.relaxed_swizzle" (i32.const 3)) (i32.const 13))
+(assert_return (invoke "i8x16.relaxed_swizzle" (i32.const 16)) (i32.const 0))
+(assert_return (invoke "i8x16.relaxed_swizzle" (i32.const 0x80)) (i32.const 0))
+(assert_return (invoke "f32x4.relaxed_madd" (f32.const 2) (f32.const 3) (f32.const 1)) (f32.const 7))
+;; The product rounds to 1 + 2^-11 before the addition, a fused operation would keep 2^-24.
+(assert_return
+  (invoke "f32x4.relaxed_madd" (f32.const 0x1.001p+0) (f32.const 0x1.001p+0) (f32.const -0x1.002p+0))
+  (f32.const 0))
+(assert_return (invoke "f64x2.relaxed_nmadd" (f64.const 2) (f64.const 3) (f64.const 1)) (f64.const -5))
+(assert_return
+  (invoke "i32x4.relaxed_laneselect" (i32.const 0xaaaa) (i32.const 0x5555) (i32.const 0xff00))
+  (i32.const 0xaa55))
+(assert_return (invoke "f32x4.relaxed_min" (f32.const 0) (f32.const -0)) (f32.const -0))
+(assert_return (invoke "f32x4.relaxed_min" (f32.const nan) (f32.const 1)) (f32.const nan:canonical))
+(assert_return (invoke "f64x2.relaxed_max" (f64.const -0) (f64.const 0)) (f64.const 0))
+(assert_return (invoke "f64x2.relaxed_max" (f64.const 1) (f64.const 2)) (f64.const 2))
+(assert_return (invoke "i16x8.relaxed_q15mulr_s" (i32.const 0x4000) (i32.const -0x4000)) (i32.const -0x2000))
+(assert_return (invoke "i16x8.relaxed_q15mulr_s" (i32.const -0x8000) (i32.const -0x8000)) (i32.const 0x7fff))
+(assert_return (invoke "i16x8.relaxed_dot_i8x16_i7x16_s" (i32.const 3) (i32.const -5)) (i32.const -30))
+(assert_return (invoke "i16x8.relaxed_dot_i8x16_i7x16_s" (i32.const -128) (i32.const 127)) (i32.const -32512))
+(assert_return (invoke "i16x8.relaxed_dot_i8x16_i7x16_s" (i32.const -128) (i32.const -128)) (i32.const 0x7fff))
+(assert_return
+  (invoke "i32x4.relaxed_dot_i8x16_i7x16_add_s" (i32.const 2) (i32.const -3) (i32.const 10))
+  (i32.const -14))
+
+(assert_invalid
+  (module (func (result v128) (i8x16.relaxed_swizzle (v128.const i64x2 0 0))))
+  "type mismatch")
+(assert_invalid
+  (module
+    (func (result v128)
+      (f32x4.relaxed_madd (v128.const i64x2 0 0) (v128.const i64x2 0 0) (f32.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (func (result i32) (i32x4.relaxed_trunc_f32x4_u (v128.const i64x2 0 0))))
+  "type mismatch")
@@ -0,0 +1,40 @@
+;; Tail calls return the results of the callee, which only have to match the caller results.
+
+(module
+  (type $t (func (result i32)))
+  (type $u (func (result i32)))
+  (func $f (type $t) (i32.const 1))
+  (elem declare func $f)
+
+  (func $non-null (result (ref $t)) (ref.func $f))
+  (func $nullable (result (ref null $t)) (return_call $non-null))
+  (func $func (result funcref) (return_call $non-null))
+  (func $equivalent (result (ref null $u)) (return_call $non-null))
+
+  (func (export "is-null") (result i32) (ref.is_null (call $nullable)))
+  (func (export "call") (result i32) (call_ref $t (call $non-null)))
+  (func (export "func-is-null") (result i32) (ref.is_null (call $func)))
+  (func (export "equivalent-is-null") (result i32) (ref.is_null (call $equivalent)))
+)
+
+(assert_return (invoke "is-null") (i32.const 0))
+(assert_return (invoke "call") (i32.const 1))
+(assert_return (invoke "func-is-null") (i32.const 0))
+(assert_return (invoke "equivalent-is-null") (i32.const 0))
+
+(assert_invalid
+  (module
+    (type $t (func))
+    (func $nullable (result (ref null $t)) (ref.null $t))
+    (func (result (ref $t)) (return_call $nullable)))
+  "type mismatch")
+(assert_invalid
+  (module
+    (func $func (result funcref) (ref.null func))
+    (func (result externref) (return_call $func)))
+  "type mismatch")
+(assert_invalid
+  (module
+    (func $i32 (result i32) (i32.const 0))
+    (func (result i32 i32) (return_call $i32)))
+  "type mismatch")
@@ -0,0 +1,204 @@
+;; Fixed-width SIMD. Vectors aren't supported as arguments or results of the invocations, so the
+;; functions build their vectors from scalars and return one of their lanes.
+
+(module
+  (memory 1)
+  (data (i32.const 0) "\ff\02\03\04\05\06\07\08\09\0a\0b\0c\0d\0e\0f\10")
+  (global $zero v128 (v128.const i64x2 0 0))
+
+  (func (export "i32x4.extract_lane") (param i32) (result i32)
+    (i32x4.extract_lane 2 (i32x4.splat (local.get 0))))
+  (func (export "i8x16.add") (result i32)
+    (i8x16.extract_lane_u 0
+      (i8x16.add (v128.const i8x16 255 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0)
+                 (v128.const i8x16 2 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0))))
+  (func (export "f32x4.mul") (param f32) (result f32)
+    (f32x4.extract_lane 3 (f32x4.mul (f32x4.splat (local.get 0)) (f32x4.splat (f32.const 2)))))
+  (func (export "any_true") (param i32) (result i32)
+    (v128.any_true (i32x4.replace_lane 1 (global.get $zero) (local.get 0))))
+
+  (func (export "i8x16.add_sat_s") (param i32 i32) (result i32)
+    (i8x16.extract_lane_s 5 (i8x16.add_sat_s (i8x16.splat (local.get 0)) (i8x16.splat (local.get 1)))))
+  (func (export "i8x16.sub_sat_u") (param i32 i32) (result i32)
+    (i8x16.extract_lane_u 5 (i8x16.sub_sat_u (i8x16.splat (local.get 0)) (i8x16.splat (local.get 1)))))
+  (func (export "i8x16.narrow_i16x8_s") (param i32) (result i32)
+    (i8x16.extract_lane_s 9 (i8x16.narrow_i16x8_s (i16x8.splat (local.get 0)) (i16x8.splat (local.get 0)))))
+  (func (export "i8x16.popcnt") (param i32) (result i32)
+    (i8x16.extract_lane_u 0 (i8x16.popcnt (i8x16.splat (local.get 0)))))
+  (func (export "i8x16.bitmask") (result i32)
+    (i8x16.bitmask (v128.const i8x16 -1 0 -1 0 -1 0 -1 0 -1 0 -1 0 -1 0 -1 0)))
+  (func (export "i8x16.shl") (param i32 i32) (result i32)
+    (i8x16.extract_lane_s 0 (i8x16.shl (i8x16.splat (local.get 0)) (local.get 1))))
+  (func (export "i8x16.swizzle") (param i32) (result i32)
+    (i8x16.extract_lane_u 0
+      (i8x16.swizzle (v128.const i8x16 10 11 12 13 14 15 16 17 18 19 20 21 22 23 24 25)
+                     (i8x16.splat (local.get 0)))))
+  (func (export "i8x16.shuffle") (param i32) (result i32)
+    (i16x8.extract_lane_u 0
+      (i8x16.shuffle 31 0 2 3 4 5 6 7 8 9 10 11 12 13 14 15
+        (global.get $zero) (i8x16.splat (local.get 0)))))
+  (func (export "i16x8.q15mulr_sat_s") (param i32 i32) (result i32)
+    (i16x8.extract_lane_s 7 (i16x8.q15mulr_sat_s (i16x8.splat (local.get 0)) (i16x8.splat (local.get 1)))))
+  (func (export "i16x8.extend_high_i8x16_u") (param i32) (result i32)
+    (i16x8.extract_lane_s 0 (i16x8.extend_high_i8x16_u (i8x16.splat (local.get 0)))))
+  (func (export "i16x8.extadd_pairwise_i8x16_s") (param i32) (result i32)
+    (i16x8.extract_lane_s 3 (i16x8.extadd_pairwise_i8x16_s (i8x16.splat (local.get 0)))))
+  (func (export "i16x8.avgr_u") (param i32 i32) (result i32)
+    (i16x8.extract_lane_u 1 (i16x8.avgr_u (i16x8.splat (local.get 0)) (i16x8.splat (local.get 1)))))
+  (func (export "i32x4.dot_i16x8_s") (param i32 i32) (result i32)
+    (i32x4.extract_lane 0 (i32x4.dot_i16x8_s (i16x8.splat (local.get 0)) (i16x8.splat (local.get 1)))))
+  (func (export "i32x4.shr_s") (param i32 i32) (result i32)
+    (i32x4.extract_lane 1 (i32x4.shr_s (i32x4.splat (local.get 0)) (local.get 1))))
+  (func (export "i32x4.lt_s") (param i32 i32) (result i32)
+    (i32x4.extract_lane 0 (i32x4.lt_s (i32x4.splat (local.get 0)) (i32x4.splat (local.get 1)))))
+  (func (export "i32x4.lt_u") (param i32 i32) (result i32)
+    (i32x4.extract_lane 0 (i32x4.lt_u (i32x4.splat (local.get 0)) (i32x4.splat (local.get 1)))))
+  (func (export "i32x4.all_true") (param i32) (result i32)
+    (i32x4.all_true (i32x4.replace_lane 3 (i32x4.splat (i32.const 1)) (local.get 0))))
+  (func (export "i32x4.trunc_sat_f32x4_s") (param f32) (result i32)
+    (i32x4.extract_lane 2 (i32x4.trunc_sat_f32x4_s (f32x4.splat (local.get 0)))))
+  (func (export "i32x4.trunc_sat_f64x2_u_zero") (param f64) (result i32)
+    (i32.add
+      (i32x4.extract_lane 1 (i32x4.trunc_sat_f64x2_u_zero (f64x2.splat (local.get 0))))
+      (i32x4.extract_lane 2 (i32x4.trunc_sat_f64x2_u_zero (f64x2.splat (local.get 0))))))
+  (func (export "i64x2.mul") (param i64 i64) (result i64)
+    (i64x2.extract_lane 1 (i64x2.mul (i64x2.splat (local.get 0)) (i64x2.splat (local.get 1)))))
+  (func (export "i64x2.extmul_high_i32x4_u") (param i32 i32) (result i64)
+    (i64x2.extract_lane 1
+      (i64x2.extmul_high_i32x4_u (i32x4.splat (local.get 0)) (i32x4.splat (local.get 1)))))
+  (func (export "f32x4.min") (param f32 f32) (result f32)
+    (f32x4.extract_lane 0 (f32x4.min (f32x4.splat (local.get 0)) (f32x4.splat (local.get 1)))))
+  (func (export "f32x4.pmax") (param f32 f32) (result f32)
+    (f32x4.extract_lane 0 (f32x4.pmax (f32x4.splat (local.get 0)) (f32x4.splat (local.get 1)))))
+  (func (export "f32x4.nearest") (param f32) (result f32)
+    (f32x4.extract_lane 1 (f32x4.nearest (f32x4.splat (local.get 0)))))
+  (func (export "f32x4.convert_i32x4_u") (param i32) (result f32)
+    (f32x4.extract_lane 0 (f32x4.convert_i32x4_u (i32x4.splat (local.get 0)))))
+  (func (export "f32x4.demote_f64x2_zero") (param f64) (result f32)
+    (f32.add
+      (f32x4.extract_lane 1 (f32x4.demote_f64x2_zero (f64x2.splat (local.get 0))))
+      (f32x4.extract_lane 3 (f32x4.demote_f64x2_zero (f64x2.splat (local.get 0))))))
+  (func (export "f64x2.promote_low_f32x4") (param f32) (result f64)
+    (f64x2.extract_lane 1 (f64x2.promote_low_f32x4 (f32x4.splat (local.get 0)))))
+  (func (export "v128.bitselect") (param i32 i32 i32) (result i32)
+    (i32x4.extract_lane 0
+      (v128.bitselect (i32x4.splat (local.get 0)) (i32x4.splat (local.get 1))
+                      (i32x4.splat (local.get 2)))))
+
+  (func (export "v128.load") (param i32) (result i32)
+    (i8x16.extract_lane_u 15 (v128.load (local.get 0))))
+  (func (export "v128.load8x8_s") (result i32)
+    (i16x8.extract_lane_s 0 (v128.load8x8_s (i32.const 0))))
+  (func (export "v128.load8x8_u") (result i32)
+    (i16x8.extract_lane_s 0 (v128.load8x8_u (i32.const 0))))
+  (func (export "v128.load32_splat") (result i32)
+    (i32x4.extract_lane 3 (v128.load32_splat (i32.const 4))))
+  (func (export "v128.load64_zero") (result i64)
+    (i64x2.extract_lane 1 (v128.load64_zero (i32.const 0))))
+  (func (export "v128.load16_lane") (result i32)
+    (i16x8.extract_lane_u 2 (v128.load16_lane 2 (i32.const 1) (global.get $zero))))
+  (func (export "v128.store8_lane") (param i32) (result i32)
+    (v128.store8_lane 1 (i32.const 32) (i8x16.splat (local.get 0)))
+    (i32.load8_u (i32.const 32)))
+  (func (export "v128.store") (param i32) (result i32)
+    (v128.store offset=16 align=16 (i32.const 32) (i32x4.splat (local.get 0)))
+    (i32.load offset=60 (i32.const 0)))
+
+  (func (export "shuffle") (param v128 v128) (result v128)
+    (i8x16.shuffle 0 17 2 19 4 21 6 23 8 25 10 27 12 29 14 31 (local.get 0) (local.get 1)))
+  (func (export "bitselect") (param v128 v128 v128) (result v128)
+    (v128.bitselect (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "shift") (param v128 i32) (result v128)
+    (i64x2.shl (local.get 0) (local.get 1)))
+  (func (export "convert") (param v128) (result v128)
+    (f64x2.promote_low_f32x4 (f32x4.convert_i32x4_u (local.get 0))))
+  (func (export "dot") (param v128 v128) (result v128)
+    (i32x4.dot_i16x8_s (local.get 0) (local.get 1)))
+)
+
+(assert_return (invoke "i32x4.extract_lane" (i32.const 42)) (i32.const 42))
+(assert_return (invoke "i8x16.add") (i32.const 1))
+(assert_return (invoke "f32x4.mul" (f32.const 1.5)) (f32.const 3))
+(assert_return (invoke "any_true" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "any_true" (i32.const 0x100)) (i32.const 1))
+
+(assert_return (invoke "i8x16.add_sat_s" (i32.const 100) (i32.const 100)) (i32.const 127))
+(assert_return (invoke "i8x16.add_sat_s" (i32.const -100) (i32.const -100)) (i32.const -128))
+(assert_return (invoke "i8x16.sub_sat_u" (i32.const 5) (i32.const 10)) (i32.const 0))
+(assert_return (invoke "i8x16.sub_sat_u" (i32.const 0x1ff) (i32.const 1)) (i32.const 254))
+(assert_return (invoke "i8x16.narrow_i16x8_s" (i32.const 300)) (i32.const 127))
+(assert_return (invoke "i8x16.narrow_i16x8_s" (i32.const -300)) (i32.const -128))
+(assert_return (invoke "i8x16.popcnt" (i32.const 0xf7)) (i32.const 7))
+(assert_return (invoke "i8x16.bitmask") (i32.const 0x5555))
+(assert_return (invoke "i8x16.shl" (i32.const 1) (i32.const 9)) (i32.const 2))
+(assert_return (invoke "i8x16.shl" (i32.const 1) (i32.const 7)) (i32.const -128))
+(assert_return (invoke "i8x16.swizzle" (i32.const 5)) (i32.const 15))
+(assert_return (invoke "i8x16.swizzle" (i32.const 16)) (i32.const 0))
+(assert_return (invoke "i8x16.swizzle" (i32.const 0x80)) (i32.const 0))
+(assert_return (invoke "i8x16.shuffle" (i32.const 7)) (i32.const 7))
+(assert_return (invoke "i16x8.q15mulr_sat_s" (i32.const 0x4000) (i32.const 0x4000)) (i32.const 0x2000))
+(assert_return (invoke "i16x8.q15mulr_sat_s" (i32.const -0x8000) (i32.const -0x8000)) (i32.const 0x7fff))
+(assert_return (invoke "i16x8.extend_high_i8x16_u" (i32.const -1)) (i32.const 255))
+(assert_return (invoke "i16x8.extadd_pairwise_i8x16_s" (i32.const -128)) (i32.const -256))
+(assert_return (invoke "i16x8.avgr_u" (i32.const 1) (i32.const 2)) (i32.const 2))
+(assert_return (invoke "i16x8.avgr_u" (i32.const 0xffff) (i32.const 0xffff)) (i32.const 0xffff))
+(assert_return (invoke "i32x4.dot_i16x8_s" (i32.const 3) (i32.const -4)) (i32.const -24))
+(assert_return (invoke "i32x4.dot_i16x8_s" (i32.const -0x8000) (i32.const -0x8000)) (i32.const 0x80000000))
+(assert_return (invoke "i32x4.shr_s" (i32.const -8) (i32.const 33)) (i32.const -4))
+(assert_return (invoke "i32x4.lt_s" (i32.const -1) (i32.const 1)) (i32.const -1))
+(assert_return (invoke "i32x4.lt_u" (i32.const -1) (i32.const 1)) (i32.const 0))
+(assert_return (invoke "i32x4.all_true" (i32.const 2)) (i32.const 1))
+(assert_return (invoke "i32x4.all_true" (i32.const 0)) (i32.const 0))
+(assert_return (invoke "i32x4.trunc_sat_f32x4_s" (f32.const 3e9)) (i32.const 0x7fffffff))
+(assert_return (invoke "i32x4.trunc_sat_f32x4_s" (f32.const nan)) (i32.const 0))
+(assert_return (invoke "i32x4.trunc_sat_f32x4_s" (f32.const -1.5)) (i32.const -1))
+(assert_return (invoke "i32x4.trunc_sat_f64x2_u_zero" (f64.const -1)) (i32.const 0))
+(assert_return (invoke "i32x4.trunc_sat_f64x2_u_zero" (f64.const 7.9)) (i32.const 7))
+(assert_return (invoke "i64x2.mul" (i64.const 0x100000000) (i64.const 0x100000001)) (i64.const 0x100000000))
+(assert_return (invoke "i64x2.extmul_high_i32x4_u" (i32.const -1) (i32.const 2)) (i64.const 0x1fffffffe))
+(assert_return (invoke "f32x4.min" (f32.const 0) (f32.const -0)) (f32.const -0))
+(assert_return (invoke "f32x4.min" (f32.const 1) (f32.const -2)) (f32.const -2))
+(assert_return (invoke "f32x4.pmax" (f32.const -0) (f32.const 0)) (f32.const -0))
+(assert_return (invoke "f32x4.nearest" (f32.const 2.5)) (f32.const 2))
+(assert_return (invoke "f32x4.convert_i32x4_u" (i32.const -1)) (f32.const 4294967296))
+(assert_return (invoke "f32x4.demote_f64x2_zero" (f64.const 1.5)) (f32.const 1.5))
+(assert_return (invoke "f64x2.promote_low_f32x4" (f32.const -0.25)) (f64.const -0.25))
+(assert_return (invoke "v128.bitselect" (i32.const 0xaaaa) (i32.const 0x5555) (i32.const 0xff00)) (i32.const 0xaa55))
+
+(assert_return (invoke "v128.load" (i32.const 0)) (i32.const 0x10))
+(assert_return (invoke "v128.load8x8_s") (i32.const -1))
+(assert_return (invoke "v128.load8x8_u") (i32.const 255))
+(assert_return (invoke "v128.load32_splat") (i32.const 0x08070605))
+(assert_return (invoke "v128.load64_zero") (i64.const 0))
+(assert_return (invoke "v128.load16_lane") (i32.const 0x0302))
+(assert_return (invoke "v128.store8_lane" (i32.const 42)) (i32.const 42))
+(assert_return (invoke "v128.store" (i32.const 42)) (i32.const 42))
+(assert_trap (invoke "v128.load" (i32.const 65521)) "out of bounds memory access")
+
+(assert_invalid
+  (module (func (result i32) (i32x4.extract_lane 4 (v128.const i64x2 0 0))))
+  "invalid lane index")
+(assert_invalid
+  (module (func (result v128) (i8x16.add (v128.const i64x2 0 0) (i32.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (memory 1) (func (drop (v128.load align=32 (i32.const 0)))))
+  "alignment must not be larger than natural")
+(assert_invalid
+  (module (memory 1) (func (v128.store16_lane 8 (i32.const 0) (v128.const i64x2 0 0))))
+  "invalid lane index")
+(assert_invalid
+  (module (func (result v128) (i64x2.shl (v128.const i64x2 0 0) (i64.const 1))))
+  "type mismatch")
+(assert_invalid
+  (module (func (drop (i32x4.splat (f32.const 0)))))
+  "type mismatch")
+
+(assert_malformed
+  (module binary
+    "\00asm" "\01\00\00\00"
+    "\01\05\01\60\00\01\7b"           ;; type section: [] -> [v128]
+    "\03\02\01\00"                    ;; function section
+    "\0a\07\01\05\00\fd\ff\7f\0b"     ;; code section: unknown vector opcode 0x3fff
+  )
+  "illegal opcode")
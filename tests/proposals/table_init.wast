@@ -0,0 +1,89 @@
+;; table.init copies a passive element segment into a table.
+
+(module
+  (type $result (func (result i32)))
+  (table $t0 4 funcref)
+  (table $t1 4 funcref)
+  (elem $e0 funcref (ref.func $f0) (ref.func $f1))
+  (elem $e1 func $f2 $f3 $f4)
+  (elem $e2 (table $t1) (i32.const 0) func $f4)
+
+  (func $f0 (result i32) (i32.const 10))
+  (func $f1 (result i32) (i32.const 11))
+  (func $f2 (result i32) (i32.const 12))
+  (func $f3 (result i32) (i32.const 13))
+  (func $f4 (result i32) (i32.const 14))
+
+  (func (export "init0") (param i32 i32 i32)
+    (table.init $t0 $e0 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init1") (param i32 i32 i32)
+    (table.init $t0 $e1 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init1-table1") (param i32 i32 i32)
+    (table.init $t1 $e1 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "init2") (param i32 i32 i32)
+    (table.init $t1 $e2 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "drop1") (elem.drop $e1))
+
+  (func (export "call0") (param i32) (result i32)
+    (call_indirect $t0 (type $result) (local.get 0)))
+  (func (export "call1") (param i32) (result i32)
+    (call_indirect $t1 (type $result) (local.get 0)))
+)
+
+;; Passive segments are left untouched by the instantiation.
+(assert_trap (invoke "call0" (i32.const 0)) "uninitialized element")
+(assert_return (invoke "call1" (i32.const 0)) (i32.const 14))
+
+(invoke "init0" (i32.const 1) (i32.const 0) (i32.const 2))
+(assert_trap (invoke "call0" (i32.const 0)) "uninitialized element")
+(assert_return (invoke "call0" (i32.const 1)) (i32.const 10))
+(assert_return (invoke "call0" (i32.const 2)) (i32.const 11))
+
+;; The second segment is copied to the first table.
+(invoke "init1" (i32.const 0) (i32.const 1) (i32.const 1))
+(assert_return (invoke "call0" (i32.const 0)) (i32.const 13))
+(assert_trap (invoke "call1" (i32.const 1)) "uninitialized element")
+
+;; The second segment is copied to the second table.
+(invoke "init1-table1" (i32.const 1) (i32.const 0) (i32.const 3))
+(assert_return (invoke "call1" (i32.const 1)) (i32.const 12))
+(assert_return (invoke "call1" (i32.const 3)) (i32.const 14))
+(assert_trap (invoke "call0" (i32.const 3)) "uninitialized element")
+
+;; Accesses are bounds checked against both the segment and the table.
+(assert_trap (invoke "init1" (i32.const 0) (i32.const 1) (i32.const 3))
+  "out of bounds table access")
+(assert_trap (invoke "init1" (i32.const 2) (i32.const 0) (i32.const 3))
+  "out of bounds table access")
+(invoke "init1" (i32.const 4) (i32.const 0) (i32.const 0))
+(invoke "init1" (i32.const 0) (i32.const 3) (i32.const 0))
+(assert_trap (invoke "init1" (i32.const 5) (i32.const 0) (i32.const 0))
+  "out of bounds table access")
+(assert_trap (invoke "init1" (i32.const 0) (i32.const 4) (i32.const 0))
+  "out of bounds table access")
+
+;; Dropped segments are empty.
+(invoke "drop1")
+(invoke "init1" (i32.const 0) (i32.const 0) (i32.const 0))
+(assert_trap (invoke "init1" (i32.const 0) (i32.const 0) (i32.const 1))
+  "out of bounds table access")
+(invoke "init0" (i32.const 3) (i32.const 1) (i32.const 1))
+(assert_return (invoke "call0" (i32.const 3)) (i32.const 11))
+
+;; Active segments are dropped once instantiated.
+(invoke "init2" (i32.const 0) (i32.const 0) (i32.const 0))
+(assert_trap (invoke "init2" (i32.const 0) (i32.const 0) (i32.const 1))
+  "out of bounds table access")
+
+(assert_invalid
+  (module (table 1 funcref) (func (table.init 0 1 (i32.const 0) (i32.const 0) (i32.const 0))) (elem funcref))
+  "unknown elem segment")
+(assert_invalid
+  (module (func (table.init 0 0 (i32.const 0) (i32.const 0) (i32.const 0))) (elem funcref))
+  "unknown table")
+(assert_invalid
+  (module (table 1 funcref) (func (elem.drop 1)) (elem funcref))
+  "unknown elem segment")
+(assert_invalid
+  (module (table 1 funcref) (func (table.init 0 0 (i32.const 0) (i32.const 0) (i32.const 0))) (elem externref))
+  "type mismatch")
@@ -0,0 +1,61 @@
+;; Tail calls replace the frame of the caller, so they don't exhaust the call stack.
+
+(module
+  (type $i64_i64 (func (param i64 i64) (result i64)))
+  (table funcref (elem $even $odd $count))
+
+  (func $count (export "count") (type $i64_i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (local.get 1))
+      (else
+        (return_call $count
+          (i64.sub (local.get 0) (i64.const 1))
+          (i64.add (local.get 1) (i64.const 1))))))
+
+  (func $even (export "even") (type $i64_i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (i64.const 1))
+      (else
+        (return_call_indirect (type $i64_i64)
+          (i64.sub (local.get 0) (i64.const 1)) (i64.const 0) (i32.const 1)))))
+  (func $odd (export "odd") (type $i64_i64)
+    (if (result i64) (i64.eqz (local.get 0))
+      (then (i64.const 0))
+      (else
+        (return_call_indirect (type $i64_i64)
+          (i64.sub (local.get 0) (i64.const 1)) (i64.const 0) (i32.const 0)))))
+
+  (func (export "call_indirect") (param i32) (result i64)
+    (return_call_indirect (type $i64_i64) (i64.const 5) (i64.const 0) (local.get 0)))
+  (func (export "call_mismatch") (result i32)
+    (return_call_indirect (result i32) (i32.const 0)))
+)
+
+(assert_return (invoke "count" (i64.const 0) (i64.const 0)) (i64.const 0))
+(assert_return (invoke "count" (i64.const 100000) (i64.const 0)) (i64.const 100000))
+(assert_return (invoke "even" (i64.const 10001) (i64.const 0)) (i64.const 0))
+(assert_return (invoke "odd" (i64.const 10001) (i64.const 0)) (i64.const 1))
+
+(assert_return (invoke "call_indirect" (i32.const 0)) (i64.const 0))
+(assert_return (invoke "call_indirect" (i32.const 2)) (i64.const 5))
+(assert_trap (invoke "call_indirect" (i32.const 3)) "undefined element")
+(assert_trap (invoke "call_mismatch") "indirect call type mismatch")
+
+(assert_invalid
+  (module (func $f (result i32) (i32.const 0)) (func (return_call $f)))
+  "type mismatch")
+(assert_invalid
+  (module (func $f (result i32) (i32.const 0)) (func (result i64) (return_call $f)))
+  "type mismatch")
+(assert_invalid
+  (module (func $f (param i32)) (func (return_call $f (i64.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (table 1 funcref) (func (result i32) (return_call_indirect (result i64) (i32.const 0))))
+  "type mismatch")
+(assert_invalid
+  (module (func (return_call_indirect (i32.const 0))))
+  "unknown table")
+(assert_invalid
+  (module (func (return_call 1)))
+  "unknown function")
@@ -0,0 +1,105 @@
+;; Shared memories and atomic instructions. Execution is single threaded, so no other thread can
+;; notify a waiter, and the waits on the expected value time out.
+
+(module
+  (memory 1 1 shared)
+
+  (func (export "store") (param i32 i32) (i32.store (local.get 0) (local.get 1)))
+  (func (export "load") (param i32) (result i32) (i32.load (local.get 0)))
+
+  (func (export "atomic.load") (param i32) (result i32) (i32.atomic.load (local.get 0)))
+  (func (export "atomic.rmw.add") (param i32 i32) (result i32)
+    (i32.atomic.rmw.add (local.get 0) (local.get 1)))
+  (func (export "atomic.rmw.cmpxchg") (param i32 i64 i64) (result i64)
+    (i64.atomic.rmw.cmpxchg (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "atomic.rmw8.sub_u") (param i32 i32) (result i32)
+    (i32.atomic.rmw8.sub_u (local.get 0) (local.get 1)))
+  (func (export "atomic.rmw16.cmpxchg_u") (param i32 i32 i32) (result i32)
+    (i32.atomic.rmw16.cmpxchg_u (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "atomic.rmw.xchg") (param i32 i64) (result i64)
+    (i64.atomic.rmw.xchg (local.get 0) (local.get 1)))
+  (func (export "atomic.load16_u") (param i32) (result i64)
+    (i64.atomic.load16_u (local.get 0)))
+  (func (export "notify") (param i32 i32) (result i32)
+    (memory.atomic.notify (local.get 0) (local.get 1)))
+  (func (export "wait32") (param i32 i32 i64) (result i32)
+    (memory.atomic.wait32 (local.get 0) (local.get 1) (local.get 2)))
+  (func (export "wait64") (param i32 i64 i64) (result i32)
+    (memory.atomic.wait64 (local.get 0) (local.get 1) (local.get 2)))
+
+  (func (export "atomics") (param i32)
+    (i64.atomic.store8 (local.get 0) (i64.const 1))
+    (drop (i32.atomic.rmw16.xchg_u offset=2 (local.get 0) (i32.const 2)))
+    (drop (i64.atomic.rmw32.sub_u (local.get 0) (i64.const 3)))
+    (drop (memory.atomic.notify (local.get 0) (i32.const 1)))
+    (drop (memory.atomic.wait32 (local.get 0) (i32.const 0) (i64.const 0)))
+    (drop (memory.atomic.wait64 (local.get 0) (i64.const 0) (i64.const -1)))
+    (atomic.fence))
+)
+
+(invoke "store" (i32.const 8) (i32.const 42))
+(assert_return (invoke "load" (i32.const 8)) (i32.const 42))
+
+(assert_return (invoke "atomic.load" (i32.const 8)) (i32.const 42))
+(assert_return (invoke "atomic.rmw.add" (i32.const 8) (i32.const 1)) (i32.const 42))
+(assert_return (invoke "atomic.rmw.cmpxchg" (i32.const 8) (i64.const 43) (i64.const 0))
+  (i64.const 43))
+(assert_return (invoke "load" (i32.const 8)) (i32.const 0))
+
+(invoke "store" (i32.const 0) (i32.const 0x12345678))
+(assert_return (invoke "atomic.rmw8.sub_u" (i32.const 0) (i32.const 0x79)) (i32.const 0x78))
+(assert_return (invoke "load" (i32.const 0)) (i32.const 0x123456ff))
+(assert_return (invoke "atomic.rmw16.cmpxchg_u" (i32.const 2) (i32.const 0xffff1234) (i32.const 0xabcd))
+  (i32.const 0x1234))
+(assert_return (invoke "atomic.rmw16.cmpxchg_u" (i32.const 2) (i32.const 0) (i32.const 1))
+  (i32.const 0xabcd))
+(assert_return (invoke "load" (i32.const 0)) (i32.const 0xabcd56ff))
+(assert_return (invoke "atomic.load16_u" (i32.const 2)) (i64.const 0xabcd))
+(assert_return (invoke "atomic.rmw.xchg" (i32.const 0) (i64.const 5)) (i64.const 0xabcd56ff))
+(assert_return (invoke "atomics" (i32.const 16)))
+
+(assert_return (invoke "notify" (i32.const 0) (i32.const 1)) (i32.const 0))
+(assert_return (invoke "wait32" (i32.const 0) (i32.const 0) (i64.const 0)) (i32.const 1))
+(assert_return (invoke "wait32" (i32.const 0) (i32.const 5) (i64.const 0)) (i32.const 2))
+(assert_return (invoke "wait64" (i32.const 0) (i64.const 5) (i64.const 10)) (i32.const 2))
+
+(assert_trap (invoke "atomic.load" (i32.const 2)) "unaligned atomic")
+(assert_trap (invoke "atomic.rmw.xchg" (i32.const 4) (i64.const 0)) "unaligned atomic")
+(assert_trap (invoke "wait32" (i32.const 1) (i32.const 0) (i64.const 0)) "unaligned atomic")
+(assert_trap (invoke "atomic.load" (i32.const 65536)) "out of bounds memory access")
+(assert_trap (invoke "notify" (i32.const 65536) (i32.const 1)) "out of bounds memory access")
+
+;; Atomic instructions can also access memories that aren't shared, but waiting requires a shared
+;; memory.
+(module
+  (memory 1)
+  (func (export "atomic.rmw.or") (param i32 i32) (result i32)
+    (drop (i32.atomic.rmw.or (local.get 0) (local.get 1)))
+    (i32.atomic.load (local.get 0)))
+  (func (export "wait32") (param i32 i32 i64) (result i32)
+    (memory.atomic.wait32 (local.get 0) (local.get 1) (local.get 2)))
+)
+
+(assert_return (invoke "atomic.rmw.or" (i32.const 4) (i32.const 6)) (i32.const 6))
+(assert_trap (invoke "wait32" (i32.const 0) (i32.const 0) (i64.const 0)) "expected shared memory")
+
+(assert_invalid
+  (module (memory 1 shared))
+  "shared memory must have maximum")
+(assert_invalid
+  (module (func (drop (i32.atomic.load (i32.const 0)))))
+  "unknown memory")
+(assert_invalid
+  (module (memory 1 1 shared) (func (drop (i32.atomic.load align=2 (i32.const 0)))))
+  "atomic alignment must be natural")
+(assert_invalid
+  (module (memory 1 1 shared) (func (drop (i64.atomic.load8_u align=2 (i32.const 0)))))
+  "atomic alignment must be natural")
+(assert_invalid
+  (module (memory 1 1 shared)
+    (func (drop (i32.atomic.rmw.cmpxchg (i32.const 0) (i32.const 0) (i64.const 0)))))
+  "type mismatch")
+(assert_invalid
+  (module (memory 1 1 shared)
+    (func (drop (memory.atomic.wait32 (i32.const 0) (i32.const 0) (i32.const 0)))))
+  "type mismatch")
@@ -0,0 +1,81 @@
+;; Types are identified by their recursion group rather than by their structure, across modules,
+;; and functions match the supertypes of their type.
+
+(module
+  (type $super (sub (func (result funcref))))
+  (type $sub (sub final $super (func (result (ref func)))))
+  (rec (type $in_group (func (result i32))) (type (struct)))
+  (type $t (func))
+  (type $refers (func (param (ref $t))))
+
+  (func $sub (export "sub") (type $sub) (ref.func $sub))
+  (func $in_group (export "in_group") (type $in_group) (i32.const 1))
+  (func $refers (export "refers") (type $refers))
+  (func $plain (export "plain") (result i32) (i32.const 2))
+
+  (table (export "table") funcref (elem $sub $in_group $plain))
+
+  (func (export "call_super") (result i32)
+    (ref.is_null (call_indirect (type $super) (i32.const 0))))
+  (func (export "call_i32") (param i32) (result i32)
+    (call_indirect (result i32) (local.get 0)))
+)
+(register "types")
+
+(assert_return (invoke "call_super") (i32.const 0))
+(assert_trap (invoke "call_i32" (i32.const 1)) "indirect call type mismatch")
+(assert_return (invoke "call_i32" (i32.const 2)) (i32.const 2))
+
+(module
+  (import "types" "table" (table 3 funcref))
+  (type $super (sub (func (result funcref))))
+
+  (func (export "call_super") (result i32)
+    (ref.is_null (call_indirect (type $super) (i32.const 0))))
+  (func (export "call_i32") (param i32) (result i32)
+    (call_indirect (result i32) (local.get 0)))
+)
+
+(assert_return (invoke "call_super") (i32.const 0))
+(assert_trap (invoke "call_i32" (i32.const 1)) "indirect call type mismatch")
+(assert_return (invoke "call_i32" (i32.const 2)) (i32.const 2))
+
+;; Functions can be imported with a supertype of their type.
+(module
+  (type $super (sub (func (result funcref))))
+  (import "types" "sub" (func (type $super)))
+)
+(assert_unlinkable
+  (module
+    (type $super (sub (func (result funcref))))
+    (type $other (sub $super (func (result funcref))))
+    (import "types" "sub" (func (type $other))))
+  "incompatible import type")
+
+;; Types of different recursion groups are different, even when they have the same structure.
+(module
+  (rec (type $in_group (func (result i32))) (type (struct)))
+  (import "types" "in_group" (func (type $in_group)))
+  (import "types" "plain" (func (result i32)))
+)
+(assert_unlinkable
+  (module (import "types" "in_group" (func (result i32))))
+  "incompatible import type")
+(assert_unlinkable
+  (module
+    (rec (type $in_group (func (result i32))) (type (struct)))
+    (import "types" "plain" (func (type $in_group))))
+  "incompatible import type")
+
+;; Type indices refer to the types of their own module.
+(module
+  (type $t (func))
+  (type $refers (func (param (ref $t))))
+  (import "types" "refers" (func (type $refers)))
+)
+(assert_unlinkable
+  (module
+    (type $u (func (param i32)))
+    (type $refers (func (param (ref $u))))
+    (import "types" "refers" (func (type $refers))))
+  "incompatible import type")